@@ -5,10 +5,11 @@ use quote::quote;
 use syn::{
     braced,
     parse::{Parse, ParseStream},
-    Attribute, Field, Token, Type,
+    Attribute, Field, Ident, LitStr, Token, Type,
 };
 
 mod metadata;
+mod openapi;
 mod request;
 mod response;
 
@@ -48,6 +49,22 @@ impl Api {
         let method = &metadata.method;
         let name = &metadata.name;
         let path = &metadata.path;
+        let optional_path = |path: &Option<LitStr>| match path {
+            Some(path) => quote! { ::std::option::Option::Some(#path) },
+            None => quote! { ::std::option::Option::None },
+        };
+        let unstable_path = optional_path(&metadata.unstable_path);
+        let r0_path = optional_path(&metadata.r0_path);
+        let stable_path = optional_path(&metadata.stable_path);
+        let optional_version = |version: &Option<Ident>| match version {
+            Some(version) => {
+                quote! { ::std::option::Option::Some(#ruma_api::MatrixVersion::#version) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        let added = optional_version(&metadata.added);
+        let deprecated = optional_version(&metadata.deprecated);
+        let removed = optional_version(&metadata.removed);
         let rate_limited: TokenStream = metadata
             .rate_limited
             .iter()
@@ -74,6 +91,14 @@ impl Api {
             })
             .collect();
 
+        let openapi = openapi::expand(
+            metadata,
+            self.request.as_ref(),
+            self.response.as_ref(),
+            self.error_ty.as_ref(),
+            &ruma_api,
+        );
+
         let error_ty = self
             .error_ty
             .map_or_else(|| quote! { #ruma_api::error::MatrixError }, |err_ty| quote! { #err_ty });
@@ -90,12 +115,19 @@ impl Api {
                 method: #http::Method::#method,
                 name: #name,
                 path: #path,
+                unstable_path: #unstable_path,
+                r0_path: #r0_path,
+                stable_path: #stable_path,
                 #rate_limited
                 #authentication
+                added: #added,
+                deprecated: #deprecated,
+                removed: #removed,
             };
 
             #request
             #response
+            #openapi
 
             #[cfg(not(any(feature = "client", feature = "server")))]
             type _SilenceUnusedError = #error_ty;
@@ -15,8 +15,14 @@ mod kw {
     syn::custom_keyword!(method);
     syn::custom_keyword!(name);
     syn::custom_keyword!(path);
+    syn::custom_keyword!(unstable_path);
+    syn::custom_keyword!(r0_path);
+    syn::custom_keyword!(stable_path);
     syn::custom_keyword!(rate_limited);
     syn::custom_keyword!(authentication);
+    syn::custom_keyword!(added);
+    syn::custom_keyword!(deprecated);
+    syn::custom_keyword!(removed);
 }
 
 /// A field of Metadata that contains attribute macros
@@ -39,14 +45,40 @@ pub struct Metadata {
     /// The name field.
     pub name: LitStr,
 
-    /// The path field.
+    /// The path field, or the first of the `r0_path`, `stable_path` and `unstable_path` fields if
+    /// it is missing.
     pub path: LitStr,
 
+    /// The unstable_path field.
+    pub unstable_path: Option<LitStr>,
+
+    /// The r0_path field.
+    pub r0_path: Option<LitStr>,
+
+    /// The stable_path field.
+    pub stable_path: Option<LitStr>,
+
     /// The rate_limited field.
     pub rate_limited: Vec<MetadataField<LitBool>>,
 
     /// The authentication field.
     pub authentication: Vec<MetadataField<AuthScheme>>,
+
+    /// The added field.
+    pub added: Option<Ident>,
+
+    /// The deprecated field.
+    pub deprecated: Option<Ident>,
+
+    /// The removed field.
+    pub removed: Option<Ident>,
+}
+
+impl Metadata {
+    /// The unstable, r0 and stable paths that are set.
+    pub fn path_variants(&self) -> impl Iterator<Item = &LitStr> {
+        self.unstable_path.iter().chain(&self.r0_path).chain(&self.stable_path)
+    }
 }
 
 fn set_field<T: ToTokens>(field: &mut Option<T>, value: T) -> syn::Result<()> {
@@ -78,8 +110,14 @@ impl Parse for Metadata {
         let mut method = None;
         let mut name = None;
         let mut path = None;
+        let mut unstable_path = None;
+        let mut r0_path = None;
+        let mut stable_path = None;
         let mut rate_limited = vec![];
         let mut authentication = vec![];
+        let mut added = None;
+        let mut deprecated = None;
+        let mut removed = None;
 
         for field_value in field_values {
             match field_value {
@@ -87,6 +125,12 @@ impl Parse for Metadata {
                 FieldValue::Method(m) => set_field(&mut method, m)?,
                 FieldValue::Name(n) => set_field(&mut name, n)?,
                 FieldValue::Path(p) => set_field(&mut path, p)?,
+                FieldValue::UnstablePath(p) => set_field(&mut unstable_path, p)?,
+                FieldValue::R0Path(p) => set_field(&mut r0_path, p)?,
+                FieldValue::StablePath(p) => set_field(&mut stable_path, p)?,
+                FieldValue::Added(v) => set_field(&mut added, v)?,
+                FieldValue::Deprecated(v) => set_field(&mut deprecated, v)?,
+                FieldValue::Removed(v) => set_field(&mut removed, v)?,
                 FieldValue::RateLimited(value, attrs) => {
                     rate_limited.push(MetadataField { attrs, value });
                 }
@@ -99,11 +143,31 @@ impl Parse for Metadata {
         let missing_field =
             |name| syn::Error::new_spanned(metadata_kw, format!("missing field `{}`", name));
 
+        let path = path
+            .or_else(|| r0_path.clone())
+            .or_else(|| stable_path.clone())
+            .or_else(|| unstable_path.clone())
+            .ok_or_else(|| missing_field("path"))?;
+
+        let path_value = path.value();
+        let params = path_params(&path_value);
+        for variant in unstable_path.iter().chain(&r0_path).chain(&stable_path) {
+            if path_params(&variant.value()) != params {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "all paths must have the same path parameters, in the same order",
+                ));
+            }
+        }
+
         Ok(Self {
             description: description.ok_or_else(|| missing_field("description"))?,
             method: method.ok_or_else(|| missing_field("method"))?,
             name: name.ok_or_else(|| missing_field("name"))?,
-            path: path.ok_or_else(|| missing_field("path"))?,
+            path,
+            unstable_path,
+            r0_path,
+            stable_path,
             rate_limited: if rate_limited.is_empty() {
                 return Err(missing_field("rate_limited"));
             } else {
@@ -114,17 +178,30 @@ impl Parse for Metadata {
             } else {
                 authentication
             },
+            added,
+            deprecated,
+            removed,
         })
     }
 }
 
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('/').filter(|segment| segment.starts_with(':')).collect()
+}
+
 enum Field {
     Description,
     Method,
     Name,
     Path,
+    UnstablePath,
+    R0Path,
+    StablePath,
     RateLimited,
     Authentication,
+    Added,
+    Deprecated,
+    Removed,
 }
 
 impl Parse for Field {
@@ -143,12 +220,30 @@ impl Parse for Field {
         } else if lookahead.peek(kw::path) {
             let _: kw::path = input.parse()?;
             Ok(Self::Path)
+        } else if lookahead.peek(kw::unstable_path) {
+            let _: kw::unstable_path = input.parse()?;
+            Ok(Self::UnstablePath)
+        } else if lookahead.peek(kw::r0_path) {
+            let _: kw::r0_path = input.parse()?;
+            Ok(Self::R0Path)
+        } else if lookahead.peek(kw::stable_path) {
+            let _: kw::stable_path = input.parse()?;
+            Ok(Self::StablePath)
         } else if lookahead.peek(kw::rate_limited) {
             let _: kw::rate_limited = input.parse()?;
             Ok(Self::RateLimited)
         } else if lookahead.peek(kw::authentication) {
             let _: kw::authentication = input.parse()?;
             Ok(Self::Authentication)
+        } else if lookahead.peek(kw::added) {
+            let _: kw::added = input.parse()?;
+            Ok(Self::Added)
+        } else if lookahead.peek(kw::deprecated) {
+            let _: kw::deprecated = input.parse()?;
+            Ok(Self::Deprecated)
+        } else if lookahead.peek(kw::removed) {
+            let _: kw::removed = input.parse()?;
+            Ok(Self::Removed)
         } else {
             Err(lookahead.error())
         }
@@ -160,8 +255,14 @@ enum FieldValue {
     Method(Ident),
     Name(LitStr),
     Path(LitStr),
+    UnstablePath(LitStr),
+    R0Path(LitStr),
+    StablePath(LitStr),
     RateLimited(LitBool, Vec<Attribute>),
     Authentication(AuthScheme, Vec<Attribute>),
+    Added(Ident),
+    Deprecated(Ident),
+    Removed(Ident),
 }
 
 impl Parse for FieldValue {
@@ -182,20 +283,28 @@ impl Parse for FieldValue {
             Field::Description => Self::Description(input.parse()?),
             Field::Method => Self::Method(input.parse()?),
             Field::Name => Self::Name(input.parse()?),
-            Field::Path => {
-                let path: LitStr = input.parse()?;
-
-                if !util::is_valid_endpoint_path(&path.value()) {
-                    return Err(syn::Error::new_spanned(
-                        &path,
-                        "path may only contain printable ASCII characters with no spaces",
-                    ));
-                }
-
-                Self::Path(path)
-            }
+            Field::Path => Self::Path(parse_path(input)?),
+            Field::UnstablePath => Self::UnstablePath(parse_path(input)?),
+            Field::R0Path => Self::R0Path(parse_path(input)?),
+            Field::StablePath => Self::StablePath(parse_path(input)?),
             Field::RateLimited => Self::RateLimited(input.parse()?, attrs),
             Field::Authentication => Self::Authentication(input.parse()?, attrs),
+            Field::Added => Self::Added(input.parse()?),
+            Field::Deprecated => Self::Deprecated(input.parse()?),
+            Field::Removed => Self::Removed(input.parse()?),
         })
     }
 }
+
+fn parse_path(input: ParseStream<'_>) -> syn::Result<LitStr> {
+    let path: LitStr = input.parse()?;
+
+    if !util::is_valid_endpoint_path(&path.value()) {
+        return Err(syn::Error::new_spanned(
+            &path,
+            "path may only contain printable ASCII characters with no spaces",
+        ));
+    }
+
+    Ok(path)
+}
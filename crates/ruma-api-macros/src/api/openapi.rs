@@ -0,0 +1,444 @@
+//! Generation of the OpenAPI description of an endpoint.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Attribute, Field, GenericArgument, Ident, Lit, NestedMeta, PathArguments, Type};
+
+use super::{metadata::Metadata, request::Request, response::Response};
+use crate::{
+    attribute::{Meta, MetaNameValue},
+    util::all_cfgs,
+};
+
+/// The kind of a request or response field, as far as the OpenAPI description is concerned.
+enum FieldKind {
+    Body,
+    NewtypeBody,
+    RawBody,
+    Path,
+    Query,
+    QueryMap,
+    Header(Ident),
+}
+
+impl FieldKind {
+    /// Gets the kind of the field from its `ruma_api` attribute.
+    ///
+    /// Invalid attributes are ignored, they are reported by the `Request` and `Response` derives.
+    fn of(field: &Field) -> Self {
+        let meta = field.attrs.iter().find_map(|attr| Meta::from_attribute(attr).ok().flatten());
+
+        match meta {
+            Some(Meta::Word(ident)) if ident == "body" => Self::NewtypeBody,
+            Some(Meta::Word(ident)) if ident == "raw_body" => Self::RawBody,
+            Some(Meta::Word(ident)) if ident == "path" => Self::Path,
+            Some(Meta::Word(ident)) if ident == "query" => Self::Query,
+            Some(Meta::Word(ident)) if ident == "query_map" => Self::QueryMap,
+            Some(Meta::NameValue(MetaNameValue { name, value })) if name == "header" => {
+                Self::Header(value)
+            }
+            _ => Self::Body,
+        }
+    }
+}
+
+/// Expands to the `openapi` function of an endpoint.
+pub(super) fn expand(
+    metadata: &Metadata,
+    request: Option<&Request>,
+    response: Option<&Response>,
+    error_ty: Option<&Type>,
+    ruma_api: &TokenStream,
+) -> TokenStream {
+    let http = quote! { #ruma_api::exports::http };
+    let serde_json = quote! { #ruma_api::exports::serde_json };
+
+    let request_fields: Vec<_> = request.map(|r| r.fields.iter().collect()).unwrap_or_default();
+    let response_fields: Vec<_> = response.map(|r| r.fields.iter().collect()).unwrap_or_default();
+
+    let parameters: Vec<_> = request_fields
+        .iter()
+        .filter_map(|field| {
+            let cfg = all_cfgs(&field.attrs);
+            let description = description(&field.attrs).map(|d| quote! { "description": #d, });
+            let (name, location, required, schema) = match FieldKind::of(field) {
+                FieldKind::Path => {
+                    let name = field_ident(field);
+                    (quote! { #name }, "path", true, type_schema(&field.ty))
+                }
+                FieldKind::Query => {
+                    let name = serialized_name(field);
+                    (quote! { #name }, "query", is_required(field), type_schema(&field.ty))
+                }
+                FieldKind::QueryMap => {
+                    let name = field_ident(field);
+                    let schema = quote! {
+                        { "type": "object", "additionalProperties": { "type": "string" } }
+                    };
+                    (quote! { #name }, "query", false, schema)
+                }
+                FieldKind::Header(header) => (
+                    quote! { #http::header::#header.as_str() },
+                    "header",
+                    is_required(field),
+                    type_schema(&field.ty),
+                ),
+                _ => return None,
+            };
+
+            Some(quote! {
+                #cfg
+                parameters.push(#serde_json::json!({
+                    "name": #name,
+                    "in": #location,
+                    "required": #required,
+                    #description
+                    "schema": #schema,
+                }));
+            })
+        })
+        .collect();
+
+    let parameters = (!parameters.is_empty()).then(|| {
+        quote! {
+            #[allow(unused_mut)]
+            let mut parameters = ::std::vec::Vec::new();
+            #( #parameters )*
+            if !parameters.is_empty() {
+                operation.insert("parameters".into(), #serde_json::Value::Array(parameters));
+            }
+        }
+    });
+
+    let request_body = body_content(&request_fields, &serde_json).map(|content| {
+        quote! {
+            let content = #content;
+            operation.insert(
+                "requestBody".into(),
+                #serde_json::json!({ "required": true, "content": content }),
+            );
+        }
+    });
+
+    let response_headers: Vec<_> = response_fields
+        .iter()
+        .filter_map(|field| {
+            let header = match FieldKind::of(field) {
+                FieldKind::Header(header) => header,
+                _ => return None,
+            };
+
+            let cfg = all_cfgs(&field.attrs);
+            let description = description(&field.attrs).map(|d| quote! { "description": #d, });
+            let required = is_required(field);
+            let schema = type_schema(&field.ty);
+
+            Some(quote! {
+                #cfg
+                headers.insert(
+                    #http::header::#header.as_str().into(),
+                    #serde_json::json!({
+                        "required": #required,
+                        #description
+                        "schema": #schema,
+                    }),
+                );
+            })
+        })
+        .collect();
+
+    let response_headers = (!response_headers.is_empty()).then(|| {
+        quote! {
+            #[allow(unused_mut)]
+            let mut headers = #serde_json::Map::new();
+            #( #response_headers )*
+            if !headers.is_empty() {
+                response.insert("headers".into(), #serde_json::Value::Object(headers));
+            }
+        }
+    });
+
+    let response_content = body_content(&response_fields, &serde_json).map(|content| {
+        quote! {
+            response.insert("content".into(), #content);
+        }
+    });
+
+    let error_schema = match error_ty {
+        Some(ty) => type_schema(ty),
+        None => quote! {
+            {
+                "type": "object",
+                "properties": {
+                    "errcode": { "type": "string" },
+                    "error": { "type": "string" },
+                },
+                "required": ["errcode"],
+            }
+        },
+    };
+
+    let doc = format!(
+        "The OpenAPI description of the `{}` API endpoint.\n\nThe description is derived from the \
+         fields of `Request` and `Response`, see `ruma_api::openapi` for details.",
+        metadata.name.value(),
+    );
+
+    quote! {
+        #[cfg(feature = "openapi")]
+        #[doc = #doc]
+        pub fn openapi() -> #ruma_api::openapi::Endpoint {
+            let mut operation = #serde_json::Map::new();
+            operation.insert("operationId".into(), ::std::module_path!().into());
+
+            #parameters
+            #request_body
+
+            let mut response = #serde_json::Map::new();
+            response.insert("description".into(), "The request was successful.".into());
+            #response_headers
+            #response_content
+
+            let mut responses = #serde_json::Map::new();
+            responses.insert("200".into(), #serde_json::Value::Object(response));
+            responses.insert(
+                "default".into(),
+                #serde_json::json!({
+                    "description": "An error occurred.",
+                    "content": { "application/json": { "schema": #error_schema } },
+                }),
+            );
+            operation.insert("responses".into(), #serde_json::Value::Object(responses));
+
+            #ruma_api::openapi::Endpoint::new(METADATA, #serde_json::Value::Object(operation))
+        }
+    }
+}
+
+/// Expands to an expression of the OpenAPI media type map of the body described by the given
+/// request or response fields, if they have a body.
+fn body_content(fields: &[&Field], serde_json: &TokenStream) -> Option<TokenStream> {
+    let mut body_fields = Vec::new();
+
+    for field in fields {
+        match FieldKind::of(field) {
+            FieldKind::Body => body_fields.push(*field),
+            FieldKind::NewtypeBody => {
+                let schema = type_schema(&field.ty);
+                return Some(quote! {
+                    #serde_json::json!({ "application/json": { "schema": #schema } })
+                });
+            }
+            FieldKind::RawBody => {
+                return Some(quote! {
+                    #serde_json::json!({
+                        "application/octet-stream": {
+                            "schema": { "type": "string", "format": "binary" },
+                        },
+                    })
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if body_fields.is_empty() {
+        return None;
+    }
+
+    let properties = body_fields.iter().map(|field| {
+        let cfg = all_cfgs(&field.attrs);
+        let schema = type_schema(&field.ty);
+        let description = description(&field.attrs).map(|d| {
+            quote! { schema["description"] = #d.into(); }
+        });
+
+        if has_serde_flag(field, "flatten") {
+            return quote! {
+                #cfg
+                flattened.push(#serde_json::json!(#schema));
+            };
+        }
+
+        let name = serialized_name(field);
+        let required = is_required(field).then(|| quote! { required.push(#name.into()); });
+
+        quote! {
+            #cfg
+            {
+                #[allow(unused_mut)]
+                let mut schema = #serde_json::json!(#schema);
+                #description
+                properties.insert(#name.into(), schema);
+                #required
+            }
+        }
+    });
+
+    Some(quote! {{
+        #[allow(unused_mut)]
+        let mut properties = #serde_json::Map::new();
+        #[allow(unused_mut)]
+        let mut required = ::std::vec::Vec::<#serde_json::Value>::new();
+        #[allow(unused_mut)]
+        let mut flattened = ::std::vec::Vec::<#serde_json::Value>::new();
+
+        #( #properties )*
+
+        let mut schema = #serde_json::Map::new();
+        schema.insert("type".into(), "object".into());
+        schema.insert("properties".into(), #serde_json::Value::Object(properties));
+        if !required.is_empty() {
+            schema.insert("required".into(), #serde_json::Value::Array(required));
+        }
+
+        let schema = if flattened.is_empty() {
+            #serde_json::Value::Object(schema)
+        } else {
+            flattened.insert(0, #serde_json::Value::Object(schema));
+            #serde_json::json!({ "allOf": flattened })
+        };
+
+        #serde_json::json!({ "application/json": { "schema": schema } })
+    }})
+}
+
+/// Expands to the JSON schema of a type, in the syntax of `serde_json::json!`.
+///
+/// The schema is derived from the syntax of the type only. Types that are not known to have a
+/// simple JSON representation are described by their name in an `x-rust-type` field.
+fn type_schema(ty: &Type) -> TokenStream {
+    match ty {
+        Type::Reference(r) => type_schema(&r.elem),
+        Type::Paren(p) => type_schema(&p.elem),
+        Type::Group(g) => type_schema(&g.elem),
+        Type::Slice(s) => array_schema(&s.elem),
+        Type::Array(a) => array_schema(&a.elem),
+        Type::Path(p) if p.qself.is_none() => {
+            let segment = p.path.segments.last().expect("type path has at least one segment");
+            let name = segment.ident.to_string();
+            let type_args: Vec<_> = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            match (name.as_str(), type_args.as_slice()) {
+                ("Option" | "Box" | "Arc" | "Rc" | "Cow" | "Raw", [inner]) => type_schema(inner),
+                ("Vec" | "BTreeSet" | "HashSet", [inner]) => array_schema(inner),
+                ("BTreeMap" | "HashMap", [_, value]) => {
+                    let value = type_schema(value);
+                    quote! { { "type": "object", "additionalProperties": #value } }
+                }
+                ("String" | "str", []) => quote! { { "type": "string" } },
+                ("bool", []) => quote! { { "type": "boolean" } },
+                ("UInt" | "u8" | "u16" | "u32" | "u64" | "usize", []) => {
+                    quote! { { "type": "integer", "minimum": 0 } }
+                }
+                ("Int" | "i8" | "i16" | "i32" | "i64" | "isize", []) => {
+                    quote! { { "type": "integer" } }
+                }
+                ("f32" | "f64", []) => quote! { { "type": "number" } },
+                ("#serde_json::Value" | "RawJsonValue" | "CanonicalJsonValue", []) => quote! { {} },
+                ("MilliSecondsSinceUnixEpoch" | "SecondsSinceUnixEpoch" | "Duration", []) => {
+                    quote! { { "type": "integer", "minimum": 0, "x-rust-type": #name } }
+                }
+                (_, []) if is_identifier(&name) => {
+                    quote! { { "type": "string", "x-rust-type": #name } }
+                }
+                _ => quote! { { "x-rust-type": #name } },
+            }
+        }
+        _ => {
+            let name = ty.to_token_stream().to_string();
+            quote! { { "x-rust-type": #name } }
+        }
+    }
+}
+
+fn array_schema(item_ty: &Type) -> TokenStream {
+    let items = type_schema(item_ty);
+    quote! { { "type": "array", "items": #items } }
+}
+
+/// Whether the type with the given name is one of the string-based identifier types.
+fn is_identifier(name: &str) -> bool {
+    name.ends_with("Id")
+        || name.ends_with("IdBox")
+        || matches!(name, "ServerName" | "ServerNameBox" | "MxcUri" | "ClientSecret")
+}
+
+fn field_ident(field: &Field) -> String {
+    let ident = field.ident.as_ref().expect("expected field to have an identifier");
+    ident.to_string().trim_start_matches("r#").to_owned()
+}
+
+/// The name of the field in the serialized body or query string.
+fn serialized_name(field: &Field) -> String {
+    serde_metas(field)
+        .into_iter()
+        .find_map(|meta| match meta {
+            syn::Meta::NameValue(syn::MetaNameValue { path, lit: Lit::Str(s), .. })
+                if path.is_ident("rename") =>
+            {
+                Some(s.value())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| field_ident(field))
+}
+
+/// Whether the field always has to be present.
+fn is_required(field: &Field) -> bool {
+    let is_option = match &field.ty {
+        Type::Path(p) => p.path.segments.last().map_or(false, |s| s.ident == "Option"),
+        _ => false,
+    };
+
+    !is_option && !has_serde_flag(field, "default")
+}
+
+fn has_serde_flag(field: &Field, flag: &str) -> bool {
+    serde_metas(field).iter().any(|meta| meta.path().is_ident(flag))
+}
+
+/// All the arguments of the `#[serde(...)]` attributes of a field.
+fn serde_metas(field: &Field) -> Vec<syn::Meta> {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(meta) => Some(meta),
+            NestedMeta::Lit(_) => None,
+        })
+        .collect()
+}
+
+/// The documentation of a field, for the `description` of its OpenAPI object.
+fn description(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue { lit: Lit::Str(s), .. })) => {
+                Some(s.value().trim().to_owned())
+            }
+            _ => None,
+        })
+        .collect();
+
+    let description = lines.join("\n").trim().to_owned();
+    (!description.is_empty()).then(|| description)
+}
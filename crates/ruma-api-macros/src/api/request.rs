@@ -82,6 +82,7 @@ impl Request {
 
         let method = &metadata.method;
         let path = &metadata.path;
+        let path_variants = metadata.path_variants();
         let auth_attributes = metadata.authentication.iter().map(|field| {
             let cfg_expr = all_cfgs_expr(&field.attrs);
             let value = &field.value;
@@ -111,6 +112,7 @@ impl Request {
             #[ruma_api(
                 method = #method,
                 path = #path,
+                #( path_variant = #path_variants, )*
                 error_ty = #error_ty,
             )]
             #( #auth_attributes )*
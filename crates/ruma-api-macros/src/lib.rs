@@ -16,11 +16,13 @@ use syn::{parse_macro_input, DeriveInput};
 mod api;
 mod attribute;
 mod auth_scheme;
+mod openapi_endpoints;
 mod request;
 mod response;
 mod util;
 
 use api::Api;
+use openapi_endpoints::expand_openapi_endpoints;
 use request::expand_derive_request;
 use response::expand_derive_response;
 
@@ -30,6 +32,15 @@ pub fn ruma_api(input: TokenStream) -> TokenStream {
     api.expand_all().into()
 }
 
+/// Collects the `openapi` functions of all endpoints defined with `ruma_api!` in the current crate.
+///
+/// Documented in ruma-api as `ruma_api::openapi::endpoints!`.
+#[proc_macro]
+pub fn openapi_endpoints(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as syn::parse::Nothing);
+    expand_openapi_endpoints().unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
 /// Internal helper taking care of the request-specific parts of `ruma_api!`.
 #[proc_macro_derive(Request, attributes(ruma_api))]
 pub fn derive_request(input: TokenStream) -> TokenStream {
@@ -0,0 +1,154 @@
+//! Details of the `openapi_endpoints` procedural macro.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, Ident, Item, Lit, Meta};
+
+use crate::util;
+
+/// An endpoint defined with `ruma_api!`.
+struct Endpoint {
+    /// The path of the module of the endpoint, relative to the crate root.
+    module: Vec<Ident>,
+
+    /// The `#[cfg]` attributes of the module and its ancestors.
+    cfgs: Vec<TokenStream>,
+}
+
+/// Expands to a `Vec` of the `openapi` functions of all the endpoints defined with `ruma_api!` in
+/// the crate that is being compiled.
+pub fn expand_openapi_endpoints() -> syn::Result<TokenStream> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        error("`CARGO_MANIFEST_DIR` must be set to find the endpoints of the crate")
+    })?;
+    let lib_path = Path::new(&manifest_dir).join("src").join("lib.rs");
+    let file = parse_file(&lib_path)?;
+    let src_dir = lib_path.parent().expect("lib.rs is in a directory");
+
+    let mut endpoints = Vec::new();
+    collect_endpoints(&file.items, &[], &cfg_attrs(&file.attrs), src_dir, src_dir, &mut endpoints)?;
+
+    let ruma_api = util::import_ruma_api();
+    let pushes = endpoints.iter().map(|Endpoint { module, cfgs }| {
+        quote! {
+            #( #cfgs )*
+            endpoints.push(crate #( :: #module )* ::openapi());
+        }
+    });
+
+    Ok(quote! {
+        {
+            let mut endpoints = ::std::vec::Vec::<#ruma_api::openapi::Endpoint>::new();
+            #( #pushes )*
+            endpoints
+        }
+    })
+}
+
+/// Collects the endpoints in `items` and their submodules, following the module resolution rules
+/// of the compiler.
+///
+/// Non-inline modules declared in `items` are searched in `module_dir`, except if they have a
+/// `#[path]` attribute, which is relative to `path_dir`.
+fn collect_endpoints(
+    items: &[Item],
+    module: &[Ident],
+    cfgs: &[TokenStream],
+    module_dir: &Path,
+    path_dir: &Path,
+    endpoints: &mut Vec<Endpoint>,
+) -> syn::Result<()> {
+    for item in items {
+        match item {
+            Item::Macro(item) if item.mac.path.segments.last().unwrap().ident == "ruma_api" => {
+                endpoints.push(Endpoint { module: module.to_owned(), cfgs: cfgs.to_owned() });
+            }
+            Item::Mod(item) => {
+                let name = &item.ident;
+                let mut module = module.to_owned();
+                module.push(name.clone());
+                let mut cfgs = cfgs.to_owned();
+                cfgs.extend(cfg_attrs(&item.attrs));
+                let inline_dir = module_dir.join(name.to_string());
+
+                if let Some((_, items)) = &item.content {
+                    collect_endpoints(items, &module, &cfgs, &inline_dir, &inline_dir, endpoints)?;
+                    continue;
+                }
+
+                let (file_path, is_mod_rs) = match path_attr(&item.attrs)? {
+                    Some(path) => (path_dir.join(path), true),
+                    None => {
+                        let file_path = module_dir.join(format!("{}.rs", name));
+                        if file_path.exists() {
+                            (file_path, false)
+                        } else {
+                            (inline_dir.join("mod.rs"), true)
+                        }
+                    }
+                };
+
+                let file = parse_file(&file_path)?;
+                cfgs.extend(cfg_attrs(&file.attrs));
+                let file_dir = file_path.parent().expect("module files are in a directory");
+                let submodule_dir = if is_mod_rs { file_dir.to_owned() } else { inline_dir };
+
+                collect_endpoints(
+                    &file.items,
+                    &module,
+                    &cfgs,
+                    &submodule_dir,
+                    file_dir,
+                    endpoints,
+                )?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_file(path: &Path) -> syn::Result<syn::File> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| error(&format!("failed to read {}: {}", path.display(), err)))?;
+    syn::parse_file(&content)
+        .map_err(|err| error(&format!("failed to parse {}: {}", path.display(), err)))
+}
+
+/// The `#[cfg]` attributes among `attrs`, converted to outer attributes.
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<TokenStream> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .map(|attr| {
+            let tokens = &attr.tokens;
+            quote! { #[cfg #tokens] }
+        })
+        .collect()
+}
+
+/// The value of the `#[path]` attribute among `attrs`, if any.
+fn path_attr(attrs: &[Attribute]) -> syn::Result<Option<PathBuf>> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("path")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+
+    match attr.parse_meta()? {
+        Meta::NameValue(meta) => match meta.lit {
+            Lit::Str(path) => Ok(Some(path.value().into())),
+            lit => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+        },
+        meta => Err(syn::Error::new_spanned(meta, "expected `#[path = \"...\"]`")),
+    }
+}
+
+fn error(message: &str) -> syn::Error {
+    syn::Error::new(Span::call_site(), message)
+}
@@ -53,6 +53,7 @@ pub fn expand_derive_request(input: DeriveInput) -> syn::Result<TokenStream> {
     let mut error_ty = None;
     let mut method = None;
     let mut path = None;
+    let mut path_variants = Vec::new();
 
     for attr in input.attrs {
         if !attr.path.is_ident("ruma_api") {
@@ -74,6 +75,9 @@ pub fn expand_derive_request(input: DeriveInput) -> syn::Result<TokenStream> {
                 MetaValue::Lit(Lit::Str(s)) if name == "path" => {
                     path = Some(s);
                 }
+                MetaValue::Lit(Lit::Str(s)) if name == "path_variant" => {
+                    path_variants.push(s);
+                }
                 _ => unreachable!("invalid ruma_api({}) attribute", name),
             }
         }
@@ -87,6 +91,7 @@ pub fn expand_derive_request(input: DeriveInput) -> syn::Result<TokenStream> {
         authentication: authentication.expect("missing authentication attribute"),
         method: method.expect("missing method attribute"),
         path: path.expect("missing path attribute"),
+        path_variants,
         error_ty: error_ty.expect("missing error_ty attribute"),
     };
 
@@ -111,6 +116,7 @@ struct Request {
     authentication: AuthScheme,
     method: Ident,
     path: LitStr,
+    path_variants: Vec<LitStr>,
     error_ty: Type,
 }
 
@@ -152,6 +158,19 @@ impl Request {
         self.fields.iter().filter(|f| matches!(f, RequestField::Header(..)))
     }
 
+    /// The path and all path variants that are different from it.
+    fn paths(&self) -> Vec<String> {
+        let mut paths = vec![self.path.value()];
+        for path in &self.path_variants {
+            let path = path.value();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        paths
+    }
+
     fn path_field_count(&self) -> usize {
         self.fields.iter().filter(|f| matches!(f, RequestField::Path(..))).count()
     }
@@ -33,26 +33,38 @@ impl Request {
                 "number of declared path parameters needs to match amount of placeholders in path"
             );
 
-            let path_var_decls = path_string[1..]
-                .split('/')
-                .enumerate()
-                .filter(|(_, seg)| seg.starts_with(':'))
-                .map(|(i, seg)| {
-                    let path_var = Ident::new(&seg[1..], Span::call_site());
-                    quote! {
-                        let #path_var = {
-                            let segment = path_segments[#i].as_bytes();
-                            let decoded =
-                                #percent_encoding::percent_decode(segment).decode_utf8()?;
-
-                            ::std::convert::TryFrom::try_from(&*decoded)?
-                        };
-                    }
-                });
+            let path_var_decls =
+                path_string[1..].split('/').filter(|seg| seg.starts_with(':')).enumerate().map(
+                    |(i, seg)| {
+                        let name = &seg[1..];
+                        let path_var = Ident::new(name, Span::call_site());
+                        quote! {
+                            let #path_var = {
+                                let segment = path_segments[#i].as_bytes();
+                                #percent_encoding::percent_decode(segment)
+                                    .decode_utf8()
+                                    .map_err(#ruma_api::error::DeserializationError::from)
+                                    .and_then(|decoded| {
+                                        ::std::convert::TryFrom::try_from(&*decoded)
+                                            .map_err(#ruma_api::error::DeserializationError::from)
+                                    })
+                                    .map_err(|error| {
+                                        #ruma_api::error::FromHttpRequestError::InvalidPathParameter {
+                                            name: #name,
+                                            error,
+                                        }
+                                    })?
+                            };
+                        }
+                    },
+                );
 
             let parse_request_path = quote! {
-                let path_segments: ::std::vec::Vec<&::std::primitive::str> =
-                    request.uri().path()[1..].split('/').collect();
+                let path_segments = self::METADATA.match_path(request.uri().path()).ok_or_else(
+                    || #ruma_api::error::FromHttpRequestError::PathMismatch {
+                        received: request.uri().path().to_owned(),
+                    },
+                )?;
 
                 #(#path_var_decls)*
             };
@@ -16,36 +16,45 @@ impl Request {
         let method = &self.method;
         let error_ty = &self.error_ty;
         let request_path_string = if self.has_path_fields() {
-            let mut format_string = self.path.value();
-            let mut format_args = Vec::new();
-
-            while let Some(start_of_segment) = format_string.find(':') {
-                // ':' should only ever appear at the start of a segment
-                assert_eq!(&format_string[start_of_segment - 1..start_of_segment], "/");
-
-                let end_of_segment = match format_string[start_of_segment..].find('/') {
-                    Some(rel_pos) => start_of_segment + rel_pos,
-                    None => format_string.len(),
-                };
+            let path_arms = self.paths().into_iter().map(|path| {
+                let mut format_string = path.clone();
+                let mut format_args = Vec::new();
+
+                while let Some(start_of_segment) = format_string.find(':') {
+                    // ':' should only ever appear at the start of a segment
+                    assert_eq!(&format_string[start_of_segment - 1..start_of_segment], "/");
+
+                    let end_of_segment = match format_string[start_of_segment..].find('/') {
+                        Some(rel_pos) => start_of_segment + rel_pos,
+                        None => format_string.len(),
+                    };
+
+                    let path_var = Ident::new(
+                        &format_string[start_of_segment + 1..end_of_segment],
+                        Span::call_site(),
+                    );
+                    format_args.push(quote! {
+                        #percent_encoding::utf8_percent_encode(
+                            &::std::string::ToString::to_string(&self.#path_var),
+                            #percent_encoding::NON_ALPHANUMERIC,
+                        )
+                    });
+                    format_string.replace_range(start_of_segment..end_of_segment, "{}");
+                }
 
-                let path_var = Ident::new(
-                    &format_string[start_of_segment + 1..end_of_segment],
-                    Span::call_site(),
-                );
-                format_args.push(quote! {
-                    #percent_encoding::utf8_percent_encode(
-                        &::std::string::ToString::to_string(&self.#path_var),
-                        #percent_encoding::NON_ALPHANUMERIC,
-                    )
-                });
-                format_string.replace_range(start_of_segment..end_of_segment, "{}");
-            }
+                quote! {
+                    #path => ::std::format!(#format_string, #(#format_args),*),
+                }
+            });
 
             quote! {
-                format_args!(#format_string, #(#format_args),*)
+                match metadata.select_path(considering_versions)? {
+                    #(#path_arms)*
+                    _ => ::std::unreachable!("selected path should be one of the endpoint's paths"),
+                }
             }
         } else {
-            quote! { metadata.path.to_owned() }
+            quote! { metadata.select_path(considering_versions)?.to_owned() }
         };
 
         let request_query_string = if let Some(field) = self.query_map_field() {
@@ -191,6 +200,15 @@ impl Request {
             }
         });
 
+        let raw_body_impl = self.raw_body_field().is_some().then(|| {
+            quote! {
+                #[automatically_derived]
+                #[cfg(feature = "client")]
+                impl #impl_generics #ruma_api::OutgoingRawBodyRequest
+                    for Request #ty_generics #where_clause {}
+            }
+        });
+
         quote! {
             #[automatically_derived]
             #[cfg(feature = "client")]
@@ -204,6 +222,7 @@ impl Request {
                     self,
                     base_url: &::std::primitive::str,
                     access_token: #ruma_api::SendAccessToken<'_>,
+                    considering_versions: &'_ [#ruma_api::MatrixVersion],
                 ) -> ::std::result::Result<#http::Request<T>, #ruma_api::error::IntoHttpError> {
                     let metadata = self::METADATA;
 
@@ -227,6 +246,7 @@ impl Request {
             }
 
             #non_auth_impl
+            #raw_body_impl
         }
     }
 }
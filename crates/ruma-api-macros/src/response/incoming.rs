@@ -108,6 +108,14 @@ impl Response {
             }
         };
 
+        let raw_body_impl = self.has_raw_body().then(|| {
+            quote! {
+                #[automatically_derived]
+                #[cfg(feature = "client")]
+                impl #ruma_api::IncomingRawBodyResponse for Response {}
+            }
+        });
+
         quote! {
             #[automatically_derived]
             #[cfg(feature = "client")]
@@ -141,6 +149,8 @@ impl Response {
                     }
                 }
             }
+
+            #raw_body_impl
         }
     }
 }
@@ -8,7 +8,7 @@ use bytes::BufMut;
 use serde_json::{from_slice as from_json_slice, Value as JsonValue};
 use thiserror::Error;
 
-use crate::{EndpointError, OutgoingResponse};
+use crate::{EndpointError, MatrixVersion, OutgoingResponse};
 
 /// A general-purpose Matrix error type consisting of an HTTP status code and a JSON body.
 ///
@@ -82,6 +82,11 @@ pub enum IntoHttpError {
     /// HTTP request construction failed.
     #[error("HTTP request construction failed: {0}")]
     Http(#[from] http::Error),
+
+    /// The endpoint was removed in a Matrix version that is older than or equal to all versions
+    /// supported by the homeserver.
+    #[error("This endpoint was removed in Matrix {0}")]
+    EndpointRemoved(MatrixVersion),
 }
 
 /// An error when converting a http request to one of ruma's endpoint-specific request types.
@@ -100,6 +105,22 @@ pub enum FromHttpRequestError {
         /// received http method
         received: http::method::Method,
     },
+
+    /// HTTP path mismatch
+    #[error("http path mismatch: {received} matches none of the endpoint's paths")]
+    PathMismatch {
+        /// received http path
+        received: String,
+    },
+
+    /// Invalid path parameter
+    #[error("invalid path parameter `{name}`: {error}")]
+    InvalidPathParameter {
+        /// name of the path parameter
+        name: &'static str,
+        /// deserialization error of the path parameter
+        error: DeserializationError,
+    },
 }
 
 impl<T> From<T> for FromHttpRequestError
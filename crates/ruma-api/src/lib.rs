@@ -59,6 +59,9 @@ use ruma_identifiers::UserId;
 /// implementations to convert the request into a `http::Request` and to create a response from
 /// a `http::Response` and vice versa.
 ///
+/// If the `openapi` feature of the crate that uses the macro is enabled, it also generates an
+/// `openapi` function returning the `ruma_api::openapi::Endpoint` that describes the endpoint.
+///
 /// The details of each of the three sections of the macros are documented below.
 ///
 /// ## Metadata
@@ -73,8 +76,15 @@ use ruma_identifiers::UserId;
 ///   the path that are parameterized can indicate a variable by using a Rust identifier
 ///   prefixed with a colon, e.g. `/foo/:some_parameter`. A corresponding query string
 ///   parameter will be expected in the request struct (see below for details).
+/// * `unstable_path`, `r0_path`, `stable_path`: Optional variants of the path that were used
+///   by different versions of the specification. They must have the same path parameters, in
+///   the same order. If `path` is not set, it defaults to the first of `r0_path`,
+///   `stable_path` and `unstable_path` that is set.
 /// * `rate_limited`: Whether or not the endpoint enforces rate limiting on requests.
 /// * `authentication`: What authentication scheme the endpoint uses.
+/// * `added`, `deprecated`, `removed`: Optional Matrix versions in which the endpoint was
+///   added, deprecated or removed, written as the name of a `MatrixVersion` variant, e.g.
+///   `V1_1`.
 ///
 /// ## Request
 ///
@@ -195,8 +205,14 @@ use ruma_identifiers::UserId;
 /// }
 /// ```
 pub use ruma_api_macros::ruma_api;
+pub use ruma_common::MatrixVersion;
 
 pub mod error;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod router;
+#[cfg(feature = "tower")]
+pub mod service;
 /// This module is used to support the generated code from ruma-api-macros.
 /// It is not considered part of ruma-api's public API.
 #[doc(hidden)]
@@ -270,10 +286,15 @@ pub trait OutgoingRequest: Sized {
     /// The endpoints path will be appended to the given `base_url`, for example
     /// `https://matrix.org`. Since all paths begin with a slash, it is not necessary for the
     /// `base_url` to have a trailing slash. If it has one however, it will be ignored.
+    ///
+    /// The path is selected with [`Metadata::select_path`] from the Matrix versions that the
+    /// homeserver supports, as returned by `GET /_matrix/client/versions`. If they are unknown,
+    /// `considering_versions` can be empty.
     fn try_into_http_request<T: Default + BufMut>(
         self,
         base_url: &str,
         access_token: SendAccessToken<'_>,
+        considering_versions: &[MatrixVersion],
     ) -> Result<http::Request<T>, IntoHttpError>;
 }
 
@@ -299,8 +320,10 @@ pub trait OutgoingRequestAppserviceExt: OutgoingRequest {
         base_url: &str,
         access_token: SendAccessToken<'_>,
         user_id: &UserId,
+        considering_versions: &[MatrixVersion],
     ) -> Result<http::Request<T>, IntoHttpError> {
-        let mut http_request = self.try_into_http_request(base_url, access_token)?;
+        let mut http_request =
+            self.try_into_http_request(base_url, access_token, considering_versions)?;
         let user_id_query = ruma_serde::urlencoded::to_string(&[("user_id", user_id)])?;
 
         let uri = http_request.uri().to_owned();
@@ -370,6 +393,20 @@ pub trait OutgoingNonAuthRequest: OutgoingRequest {}
 /// Marker trait for requests that don't require authentication, for the server side.
 pub trait IncomingNonAuthRequest: IncomingRequest {}
 
+/// Marker trait for requests whose body is a single `#[ruma_api(raw_body)]` field, for the client
+/// side.
+///
+/// The body of these requests can be streamed: convert the request with the raw body field left
+/// empty, then replace the body of the resulting `http::Request`.
+pub trait OutgoingRawBodyRequest: OutgoingRequest {}
+
+/// Marker trait for responses whose body is a single `#[ruma_api(raw_body)]` field, for the client
+/// side.
+///
+/// The body of these responses can be streamed: converting an `http::Response` with an empty body
+/// only fills the other fields, and the body can be read separately.
+pub trait IncomingRawBodyResponse: IncomingResponse {}
+
 /// Authentication scheme used by the endpoint.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[allow(clippy::exhaustive_enums)]
@@ -406,11 +443,132 @@ pub struct Metadata {
 
     /// The path of this endpoint's URL, with variable names where path parameters should be filled
     /// in during a request.
+    ///
+    /// This is the path that is used when the Matrix versions supported by the homeserver are
+    /// unknown.
     pub path: &'static str,
 
+    /// The unstable path of this endpoint, used before it was added to the specification.
+    pub unstable_path: Option<&'static str>,
+
+    /// The `r0` path of this endpoint.
+    pub r0_path: Option<&'static str>,
+
+    /// The stable path of this endpoint, used since Matrix 1.1.
+    pub stable_path: Option<&'static str>,
+
     /// Whether or not this endpoint is rate limited by the server.
     pub rate_limited: bool,
 
     /// What authentication scheme the server uses for this endpoint.
     pub authentication: AuthScheme,
+
+    /// The Matrix version that added this endpoint.
+    pub added: Option<MatrixVersion>,
+
+    /// The Matrix version that deprecated this endpoint.
+    pub deprecated: Option<MatrixVersion>,
+
+    /// The Matrix version that removed this endpoint.
+    pub removed: Option<MatrixVersion>,
+}
+
+impl Metadata {
+    /// All paths of this endpoint: `path`, followed by the unstable, `r0` and stable paths that are
+    /// different from it.
+    pub fn paths(&self) -> Vec<&'static str> {
+        let mut paths = vec![self.path];
+        for path in [self.unstable_path, self.r0_path, self.stable_path].iter().flatten() {
+            if !paths.contains(path) {
+                paths.push(path);
+            }
+        }
+
+        paths
+    }
+
+    /// Selects the path to use for a request to a homeserver that supports the given Matrix
+    /// versions.
+    ///
+    /// The stable path is preferred if the homeserver supports a version that has it, then the
+    /// `r0` path, then the unstable path. If `versions` is empty, `path` is returned.
+    ///
+    /// Returns an error if the endpoint was removed in a version that is older than or equal to
+    /// all of `versions`.
+    pub fn select_path(&self, versions: &[MatrixVersion]) -> Result<&'static str, IntoHttpError> {
+        if versions.is_empty() {
+            return Ok(self.path);
+        }
+
+        if let Some(removed) = self.removed {
+            if versions.iter().all(|version| *version >= removed) {
+                return Err(IntoHttpError::EndpointRemoved(removed));
+            }
+        }
+
+        let supports = |since: MatrixVersion| versions.iter().any(|version| *version >= since);
+
+        if let Some(stable_path) = self.stable_path {
+            let since =
+                self.added.map_or(MatrixVersion::V1_1, |added| added.max(MatrixVersion::V1_1));
+            if supports(since) {
+                return Ok(stable_path);
+            }
+        }
+
+        if let Some(r0_path) = self.r0_path {
+            if self.added.map_or(true, supports) {
+                return Ok(r0_path);
+            }
+        }
+
+        Ok(self.unstable_path.unwrap_or(self.path))
+    }
+
+    /// Whether a homeserver that supports the given Matrix versions is expected to support this
+    /// endpoint.
+    ///
+    /// Returns `false` for endpoints without an `added` version, since those are not part of any
+    /// stable Matrix version yet.
+    pub fn is_supported(&self, versions: &[MatrixVersion]) -> bool {
+        let added = match self.added {
+            Some(added) => added,
+            None => return false,
+        };
+
+        versions.iter().any(|version| {
+            *version >= added && self.removed.map_or(true, |removed| *version < removed)
+        })
+    }
+
+    /// Matches `path` against the paths of this endpoint.
+    ///
+    /// If one of them matches, returns the segments of `path` at the positions of the path
+    /// parameters, still percent-encoded.
+    pub fn match_path<'a>(&self, path: &'a str) -> Option<Vec<&'a str>> {
+        self.paths().into_iter().find_map(|template| match_path_template(template, path))
+    }
+}
+
+/// Matches `path` against the path template `template`.
+///
+/// If it matches, returns the segments of `path` at the positions of the path parameters, still
+/// percent-encoded.
+pub(crate) fn match_path_template<'a>(template: &str, path: &'a str) -> Option<Vec<&'a str>> {
+    let template_segments = template.trim_start_matches('/').split('/');
+    let segments = path.trim_start_matches('/').split('/');
+    if template_segments.clone().count() != segments.clone().count() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for (template_segment, segment) in template_segments.zip(segments) {
+        if template_segment.starts_with(':') {
+            params.push(segment);
+        } else if template_segment != segment {
+            return None;
+        }
+    }
+
+    Some(params)
 }
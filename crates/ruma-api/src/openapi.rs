@@ -0,0 +1,246 @@
+//! Generation of [OpenAPI] documents from endpoint definitions.
+//!
+//! With the `openapi` feature of the crate that defines an endpoint, `ruma_api!` generates an
+//! `openapi` function next to the endpoint's `METADATA`, returning an [`Endpoint`]. Endpoints can
+//! then be collected in a [`Document`] to get a full OpenAPI document, and [`endpoints!`] lists
+//! all of them for the crate it is used in.
+//!
+//! The parameters, request body and response of an endpoint are derived from the fields of its
+//! request and response types. Their schemas are derived from the syntax of the field types:
+//! strings, numbers, booleans, identifiers, collections and wrappers like `Option` or `Raw` are
+//! described by the corresponding JSON schema, other types only by their name in an
+//! `x-rust-type` field.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.0.3
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use crate::{AuthScheme, Metadata};
+
+/// Expands to a `Vec<Endpoint>` of all endpoints defined with `ruma_api!` in the current
+/// crate.
+///
+/// The endpoints are found by following the `mod` declarations from `src/lib.rs`. Endpoints in
+/// modules with `#[cfg]` attributes are only included if the conditions hold.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut document = Document::new("My API", env!("CARGO_PKG_VERSION"));
+/// document.extend(ruma_api::openapi::endpoints!());
+/// ```
+pub use ruma_api_macros::openapi_endpoints as endpoints;
+
+/// The version of the OpenAPI specification that generated documents conform to.
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+/// The OpenAPI description of an API endpoint.
+#[derive(Clone, Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct Endpoint {
+    /// The metadata of the endpoint.
+    pub metadata: Metadata,
+
+    /// The OpenAPI operation object of the endpoint.
+    pub operation: JsonValue,
+}
+
+impl Endpoint {
+    /// Creates a new `Endpoint` from the metadata of an endpoint and an OpenAPI operation object
+    /// describing its parameters, request body and responses.
+    ///
+    /// The summary, security requirements and deprecation status of the operation are filled in
+    /// from `metadata`, as well as a `429` response for rate limited endpoints and the
+    /// `x-matrix-added`, `x-matrix-deprecated` and `x-matrix-removed` versions of the endpoint.
+    /// The operation ID defaults to the name of the endpoint. `ruma_api!` sets it to the path of
+    /// the endpoint's module instead, since names are not unique within a crate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `operation` is not a JSON object.
+    pub fn new(metadata: Metadata, mut operation: JsonValue) -> Self {
+        let object = operation.as_object_mut().expect("OpenAPI operation must be a JSON object");
+
+        object.entry("operationId").or_insert_with(|| metadata.name.into());
+        object.insert("summary".into(), metadata.description.into());
+
+        let security = match metadata.authentication {
+            AuthScheme::None => json!([]),
+            AuthScheme::AccessToken => json!([{ "accessToken": [] }]),
+            AuthScheme::ServerSignatures => json!([{ "serverSignatures": [] }]),
+            AuthScheme::QueryOnlyAccessToken => json!([{ "accessTokenQuery": [] }]),
+        };
+        object.insert("security".into(), security);
+
+        if metadata.deprecated.is_some() {
+            object.insert("deprecated".into(), true.into());
+        }
+
+        let versions = [
+            ("x-matrix-added", metadata.added),
+            ("x-matrix-deprecated", metadata.deprecated),
+            ("x-matrix-removed", metadata.removed),
+        ];
+        for (key, version) in versions.iter() {
+            if let Some(version) = version {
+                object.insert((*key).into(), version.as_str().into());
+            }
+        }
+
+        if metadata.rate_limited {
+            let responses = object
+                .entry("responses")
+                .or_insert_with(|| JsonValue::Object(JsonMap::new()))
+                .as_object_mut()
+                .expect("OpenAPI responses must be a JSON object");
+
+            responses.insert(
+                "429".into(),
+                json!({
+                    "description": "This request was rate-limited.",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "errcode": { "type": "string" },
+                                    "error": { "type": "string" },
+                                    "retry_after_ms": { "type": "integer" },
+                                },
+                                "required": ["errcode"],
+                            },
+                        },
+                    },
+                }),
+            );
+        }
+
+        Self { metadata, operation }
+    }
+
+    /// The OpenAPI path item objects of this endpoint, keyed by path.
+    ///
+    /// The operation is added under each path of the endpoint. Only the operation under
+    /// `metadata.path` has an operation ID, so that it stays unique in a document.
+    pub fn path_items(&self) -> BTreeMap<String, JsonValue> {
+        let method = self.metadata.method.as_str().to_ascii_lowercase();
+
+        self.metadata
+            .paths()
+            .into_iter()
+            .map(|path| {
+                let mut operation = self.operation.clone();
+                if path != self.metadata.path {
+                    if let Some(object) = operation.as_object_mut() {
+                        object.remove("operationId");
+                    }
+                }
+
+                let mut path_item = JsonMap::new();
+                path_item.insert(method.clone(), operation);
+
+                (openapi_path(path), JsonValue::Object(path_item))
+            })
+            .collect()
+    }
+}
+
+/// An OpenAPI document, assembled from a list of endpoints.
+#[derive(Clone, Debug)]
+pub struct Document {
+    title: String,
+    version: String,
+    endpoints: Vec<Endpoint>,
+}
+
+impl Document {
+    /// Creates a new empty `Document` with the given title and version of the API.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self { title: title.into(), version: version.into(), endpoints: Vec::new() }
+    }
+
+    /// Adds an endpoint to this document.
+    pub fn add_endpoint(&mut self, endpoint: Endpoint) {
+        self.endpoints.push(endpoint);
+    }
+
+    /// The endpoints of this document.
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Converts this document to JSON.
+    ///
+    /// If several endpoints have the same method and path, only the last one is included.
+    pub fn to_json(&self) -> JsonValue {
+        let mut paths = BTreeMap::<String, JsonMap<String, JsonValue>>::new();
+        for endpoint in &self.endpoints {
+            for (path, path_item) in endpoint.path_items() {
+                if let JsonValue::Object(operations) = path_item {
+                    paths.entry(path).or_default().extend(operations);
+                }
+            }
+        }
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": self.title,
+                "version": self.version,
+            },
+            "paths": paths,
+            "components": {
+                "securitySchemes": {
+                    "accessToken": {
+                        "type": "http",
+                        "scheme": "bearer",
+                    },
+                    "accessTokenQuery": {
+                        "type": "apiKey",
+                        "in": "query",
+                        "name": "access_token",
+                    },
+                    "serverSignatures": {
+                        "type": "apiKey",
+                        "in": "header",
+                        "name": "Authorization",
+                        "description": "X-Matrix signatures, as defined in the federation API.",
+                    },
+                },
+            },
+        })
+    }
+}
+
+impl Extend<Endpoint> for Document {
+    fn extend<T: IntoIterator<Item = Endpoint>>(&mut self, iter: T) {
+        self.endpoints.extend(iter);
+    }
+}
+
+/// Converts a path with `:name` parameters to an OpenAPI path with `{name}` parameters.
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::openapi_path;
+
+    #[test]
+    fn path_parameters() {
+        assert_eq!(
+            openapi_path("/_matrix/client/r0/rooms/:room_id/state/:event_type/:state_key"),
+            "/_matrix/client/r0/rooms/{room_id}/state/{event_type}/{state_key}"
+        );
+        assert_eq!(openapi_path("/_matrix/client/versions"), "/_matrix/client/versions");
+    }
+}
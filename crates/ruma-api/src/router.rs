@@ -0,0 +1,240 @@
+//! A router that dispatches incoming `http::Request`s to typed endpoint handlers.
+//!
+//! Routes are matched against the `method` and `path` of each endpoint's [`Metadata`], so any
+//! endpoint that implements [`IncomingRequest`] can be registered without writing path matching
+//! by hand.
+
+use std::{fmt, future::Future, pin::Pin};
+
+use http::{Method, StatusCode};
+use percent_encoding::percent_decode_str;
+use serde_json::json;
+
+use crate::{
+    error::{FromHttpRequestError, MatrixError},
+    match_path_template, IncomingRequest, Metadata, OutgoingResponse,
+};
+
+/// The future returned by [`Router::handle`].
+pub type ResponseFuture = Pin<Box<dyn Future<Output = http::Response<Vec<u8>>> + Send>>;
+
+type Handler<B> = Box<dyn Fn(http::Request<B>) -> ResponseFuture + Send + Sync>;
+
+type PathParams = Vec<(&'static str, String)>;
+
+/// A table of endpoint handlers, keyed by the `Metadata` of their endpoint.
+///
+/// Requests that don't match any registered route are answered with an `M_UNRECOGNIZED` error:
+/// `404 Not Found` if no route has a matching path, `405 Method Not Allowed` if only the method
+/// doesn't match.
+///
+/// The router doesn't check the authentication of requests. Use [`Router::recognize`] to find
+/// the [`AuthScheme`](crate::AuthScheme) of the targeted endpoint before calling
+/// [`Router::handle`].
+pub struct Router<B = Vec<u8>> {
+    routes: Vec<Route<B>>,
+}
+
+struct Route<B> {
+    metadata: Metadata,
+    paths: Vec<&'static str>,
+    handler: Handler<B>,
+}
+
+/// An endpoint that was recognized by [`Router::recognize`].
+#[derive(Clone, Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct RouteMatch<'a> {
+    /// The metadata of the endpoint.
+    pub metadata: &'a Metadata,
+
+    /// The percent-decoded path parameters, in the order in which they appear in the path.
+    pub path_params: Vec<(&'static str, String)>,
+}
+
+impl<B: AsRef<[u8]> + 'static> Router<B> {
+    /// Creates an empty `Router`.
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Registers `handler` for the endpoint of `R`.
+    ///
+    /// The route matches all paths of the endpoint, as returned by [`Metadata::paths`].
+    ///
+    /// Requests for this endpoint are converted with [`IncomingRequest::try_from_http_request`].
+    /// If that fails, the request is answered with a `400 Bad Request` error without calling the
+    /// handler: `M_INVALID_PARAM` for an invalid path parameter, `M_BAD_JSON` otherwise. Both the
+    /// response and the endpoint error of the handler are converted with
+    /// [`OutgoingResponse::try_into_http_response`].
+    ///
+    /// If an earlier route has the same method and one of the paths of this route, this route
+    /// replaces it for that path. Paths only differing in the names of their parameters are the
+    /// same. The earlier route keeps its other paths, and is removed if it has none left.
+    pub fn route<R, F, Fut>(mut self, handler: F) -> Self
+    where
+        R: IncomingRequest + 'static,
+        F: Fn(R) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R::OutgoingResponse, R::EndpointError>> + Send + 'static,
+    {
+        let handler: Handler<B> = Box::new(move |request| call_handler(request, &handler));
+
+        let metadata = R::METADATA;
+        let paths = metadata.paths();
+        let route = Route { metadata, paths, handler };
+
+        for existing in &mut self.routes {
+            if existing.metadata.method == route.metadata.method {
+                existing.paths.retain(|path| !route.paths.iter().any(|p| same_path(p, path)));
+            }
+        }
+        self.routes.retain(|existing| !existing.paths.is_empty());
+        self.routes.push(route);
+
+        self
+    }
+
+    /// Finds the endpoint that a request with the given method and path targets.
+    ///
+    /// `path` must not contain the query string. If several routes match, literal segments take
+    /// precedence over path parameters, from left to right.
+    pub fn recognize(&self, method: &Method, path: &str) -> Option<RouteMatch<'_>> {
+        self.find(method, path)
+            .ok()
+            .map(|(route, path_params)| RouteMatch { metadata: &route.metadata, path_params })
+    }
+
+    /// Dispatches `request` to the handler of the endpoint it targets.
+    pub fn handle(&self, request: http::Request<B>) -> ResponseFuture {
+        match self.find(request.method(), request.uri().path()) {
+            Ok((route, _)) => (route.handler)(request),
+            Err(status_code) => {
+                let response =
+                    error_response(status_code, "M_UNRECOGNIZED", "Unrecognized request");
+                Box::pin(async move { response })
+            }
+        }
+    }
+
+    fn find(&self, method: &Method, path: &str) -> Result<(&Route<B>, PathParams), StatusCode> {
+        let mut path_matched = false;
+        let mut best: Option<(&Route<B>, &'static str, PathParams)> = None;
+
+        for route in &self.routes {
+            for &template in &route.paths {
+                let path_params = match match_path_template(template, path)
+                    .and_then(|values| decode_path_params(template, values))
+                {
+                    Some(path_params) => path_params,
+                    None => continue,
+                };
+                path_matched = true;
+
+                if route.metadata.method != *method {
+                    continue;
+                }
+
+                let is_better =
+                    best.as_ref().map_or(true, |(_, best, _)| is_more_specific(template, best));
+                if is_better {
+                    best = Some((route, template, path_params));
+                }
+            }
+        }
+
+        match best {
+            Some((route, _, path_params)) => Ok((route, path_params)),
+            None if path_matched => Err(StatusCode::METHOD_NOT_ALLOWED),
+            None => Err(StatusCode::NOT_FOUND),
+        }
+    }
+}
+
+impl<B: AsRef<[u8]> + 'static> Default for Router<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> fmt::Debug for Router<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.routes.iter().map(|route| &route.metadata)).finish()
+    }
+}
+
+/// Converts `request` to `R`, calls `handler` with it and converts its result to a response.
+pub(crate) fn call_handler<R, B, F, Fut>(request: http::Request<B>, handler: F) -> ResponseFuture
+where
+    R: IncomingRequest,
+    B: AsRef<[u8]>,
+    F: FnOnce(R) -> Fut,
+    Fut: Future<Output = Result<R::OutgoingResponse, R::EndpointError>> + Send + 'static,
+{
+    let request = match R::try_from_http_request(request) {
+        Ok(request) => request,
+        Err(error) => {
+            let errcode = match error {
+                FromHttpRequestError::InvalidPathParameter { .. } => "M_INVALID_PARAM",
+                _ => "M_BAD_JSON",
+            };
+            let response = error_response(
+                StatusCode::BAD_REQUEST,
+                errcode,
+                &format!("Failed to parse request: {}", error),
+            );
+            return Box::pin(async move { response });
+        }
+    };
+
+    let future = handler(request);
+    Box::pin(async move {
+        let response = match future.await {
+            Ok(response) => response.try_into_http_response(),
+            Err(error) => error.try_into_http_response(),
+        };
+
+        response.unwrap_or_else(|error| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "M_UNKNOWN",
+                &format!("Failed to serialize response: {}", error),
+            )
+        })
+    })
+}
+
+fn decode_path_params(template: &'static str, values: Vec<&str>) -> Option<PathParams> {
+    template
+        .trim_start_matches('/')
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix(':'))
+        .zip(values)
+        .map(|(name, value)| {
+            Some((name, percent_decode_str(value).decode_utf8().ok()?.into_owned()))
+        })
+        .collect()
+}
+
+/// Whether the path templates `a` and `b` match the same paths.
+fn same_path(a: &str, b: &str) -> bool {
+    match_path_template(a, b).map_or(false, |params| params.iter().all(|p| p.starts_with(':')))
+}
+
+fn is_more_specific(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches('/').split('/');
+    let b = b.trim_start_matches('/').split('/');
+    for (a, b) in a.zip(b) {
+        match (a.starts_with(':'), b.starts_with(':')) {
+            (false, true) => return true,
+            (true, false) => return false,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+fn error_response(status_code: StatusCode, errcode: &str, error: &str) -> http::Response<Vec<u8>> {
+    let error = MatrixError { status_code, body: json!({ "errcode": errcode, "error": error }) };
+    error.try_into_http_response().expect("MatrixError should always be serializable")
+}
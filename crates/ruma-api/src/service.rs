@@ -0,0 +1,234 @@
+//! [`tower`] integration for Matrix API endpoints.
+//!
+//! On the server side, [`HandlerService`] turns an async function handling one endpoint into a
+//! `Service<http::Request<B>>`, and [`Router`] is a `Service` for all of its routes. On the client
+//! side, [`ClientService`] sends typed requests through an inner HTTP `Service`, so tower
+//! middleware like timeouts or concurrency limits can be used with both.
+//!
+//! [`tower`]: https://docs.rs/tower
+
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use thiserror::Error;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    error::{FromHttpResponseError, IntoHttpError},
+    router::{call_handler, Router},
+    IncomingRequest, IncomingResponse, MatrixVersion, OutgoingRequest, SendAccessToken,
+};
+
+/// The future returned by the server-side services of this module.
+pub type ServerFuture =
+    Pin<Box<dyn Future<Output = Result<http::Response<Vec<u8>>, Infallible>> + Send>>;
+
+/// The future returned by [`ClientService`].
+pub type ClientFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+/// A `Service` that handles the requests of a single endpoint with an async function.
+///
+/// Incoming requests are converted with [`IncomingRequest::try_from_http_request`]; if that
+/// fails, the request is answered with a `400 Bad Request` `M_BAD_JSON` error without calling the
+/// handler. The response or endpoint error of the handler is converted with
+/// [`OutgoingResponse::try_into_http_response`](crate::OutgoingResponse::try_into_http_response).
+///
+/// The service doesn't check the path or method of requests, so it is meant to be used behind a
+/// router.
+pub struct HandlerService<R, F> {
+    handler: F,
+    _request: PhantomData<fn(R)>,
+}
+
+impl<R, F> HandlerService<R, F> {
+    /// Creates a `HandlerService` calling `handler` for every request.
+    pub fn new(handler: F) -> Self {
+        Self { handler, _request: PhantomData }
+    }
+}
+
+impl<R, F: Clone> Clone for HandlerService<R, F> {
+    fn clone(&self) -> Self {
+        Self::new(self.handler.clone())
+    }
+}
+
+impl<R, F> fmt::Debug for HandlerService<R, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandlerService").finish()
+    }
+}
+
+impl<R, B, F, Fut> Service<http::Request<B>> for HandlerService<R, F>
+where
+    R: IncomingRequest,
+    B: AsRef<[u8]>,
+    F: FnMut(R) -> Fut,
+    Fut: Future<Output = Result<R::OutgoingResponse, R::EndpointError>> + Send + 'static,
+{
+    type Response = http::Response<Vec<u8>>;
+    type Error = Infallible;
+    type Future = ServerFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let future = call_handler(request, &mut self.handler);
+        Box::pin(async move { Ok(future.await) })
+    }
+}
+
+impl<B: AsRef<[u8]> + 'static> Service<http::Request<B>> for Router<B> {
+    type Response = http::Response<Vec<u8>>;
+    type Error = Infallible;
+    type Future = ServerFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let future = self.handle(request);
+        Box::pin(async move { Ok(future.await) })
+    }
+}
+
+/// A `Layer` that wraps an HTTP `Service` in a [`ClientService`].
+#[derive(Clone, Debug)]
+pub struct ClientLayer {
+    homeserver_url: String,
+    access_token: Option<String>,
+    supported_versions: Vec<MatrixVersion>,
+}
+
+impl ClientLayer {
+    /// Creates a `ClientLayer` for the given homeserver URL, access token and Matrix versions
+    /// supported by the homeserver.
+    pub fn new(
+        homeserver_url: String,
+        access_token: Option<String>,
+        supported_versions: Vec<MatrixVersion>,
+    ) -> Self {
+        Self { homeserver_url, access_token, supported_versions }
+    }
+}
+
+impl<S> Layer<S> for ClientLayer {
+    type Service = ClientService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientService {
+            inner,
+            homeserver_url: self.homeserver_url.clone(),
+            access_token: self.access_token.clone(),
+            supported_versions: self.supported_versions.clone(),
+        }
+    }
+}
+
+/// A `Service` that sends typed requests through an inner HTTP `Service`.
+///
+/// Requests are converted with [`OutgoingRequest::try_into_http_request`], using the path that
+/// best matches the Matrix versions supported by the homeserver. The access token is only added if
+/// the endpoint requires one. Responses are converted with
+/// [`IncomingResponse::try_from_http_response`].
+#[derive(Clone, Debug)]
+pub struct ClientService<S> {
+    inner: S,
+    homeserver_url: String,
+    access_token: Option<String>,
+    supported_versions: Vec<MatrixVersion>,
+}
+
+impl<S> ClientService<S> {
+    /// Creates a `ClientService` sending requests to the given homeserver through `inner`.
+    pub fn new(
+        inner: S,
+        homeserver_url: String,
+        access_token: Option<String>,
+        supported_versions: Vec<MatrixVersion>,
+    ) -> Self {
+        Self { inner, homeserver_url, access_token, supported_versions }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, R, ResBody> Service<R> for ClientService<S>
+where
+    S: Service<http::Request<Vec<u8>>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    R: OutgoingRequest,
+    R::IncomingResponse: 'static,
+    ResBody: AsRef<[u8]>,
+{
+    type Response = R::IncomingResponse;
+    type Error = ClientServiceError<S::Error, R::EndpointError>;
+    type Future = ClientFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ClientServiceError::Service)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let access_token = match &self.access_token {
+            Some(access_token) => SendAccessToken::IfRequired(access_token),
+            None => SendAccessToken::None,
+        };
+
+        let http_request = match request.try_into_http_request(
+            &self.homeserver_url,
+            access_token,
+            &self.supported_versions,
+        ) {
+            Ok(http_request) => http_request,
+            Err(error) => return Box::pin(async move { Err(ClientServiceError::IntoHttp(error)) }),
+        };
+
+        let future = self.inner.call(http_request);
+        Box::pin(async move {
+            let http_response = future.await.map_err(ClientServiceError::Service)?;
+            R::IncomingResponse::try_from_http_response(http_response)
+                .map_err(ClientServiceError::FromHttpResponse)
+        })
+    }
+}
+
+/// An error that can occur in [`ClientService`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ClientServiceError<S, E> {
+    /// Converting the request to an HTTP request failed.
+    #[error("failed to convert request: {0}")]
+    IntoHttp(IntoHttpError),
+
+    /// The inner service returned an error.
+    #[error("the inner service returned an error: {0}")]
+    Service(S),
+
+    /// Converting the HTTP response failed, or the server returned an error.
+    #[error("{0}")]
+    FromHttpResponse(FromHttpResponseError<E>),
+}
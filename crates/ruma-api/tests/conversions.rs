@@ -52,7 +52,7 @@ fn request_serde() {
 
     let http_req = req
         .clone()
-        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None)
+        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None, &[])
         .unwrap();
     let req2 = Request::try_from_http_request(http_req).unwrap();
 
@@ -75,7 +75,7 @@ fn invalid_uri_should_not_panic() {
         user: user_id!("@bazme:ruma.io").to_owned(),
     };
 
-    let result = req.try_into_http_request::<Vec<u8>>("invalid uri", SendAccessToken::None);
+    let result = req.try_into_http_request::<Vec<u8>>("invalid uri", SendAccessToken::None, &[]);
     assert!(result.is_err());
 }
 
@@ -96,6 +96,7 @@ fn request_with_user_id_serde() {
             "https://homeserver.tld",
             SendAccessToken::None,
             user_id,
+            &[],
         )
         .unwrap();
 
@@ -154,6 +155,7 @@ mod without_query {
                 "https://homeserver.tld",
                 SendAccessToken::None,
                 user_id,
+                &[],
             )
             .unwrap();
 
@@ -48,7 +48,7 @@ fn response_content_type_override() {
 fn request_content_type_override() {
     let req = Request { location: None, stuff: "magic".into() };
     let mut http_req = req
-        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None)
+        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None, &[])
         .unwrap();
 
     assert_eq!(
@@ -8,8 +8,8 @@ use bytes::BufMut;
 use http::{header::CONTENT_TYPE, method::Method};
 use ruma_api::{
     error::{FromHttpRequestError, FromHttpResponseError, IntoHttpError, MatrixError, ServerError},
-    AuthScheme, EndpointError, IncomingRequest, IncomingResponse, Metadata, OutgoingRequest,
-    OutgoingResponse, SendAccessToken,
+    AuthScheme, EndpointError, IncomingRequest, IncomingResponse, MatrixVersion, Metadata,
+    OutgoingRequest, OutgoingResponse, SendAccessToken,
 };
 use ruma_identifiers::{RoomAliasId, RoomId};
 use ruma_serde::Outgoing;
@@ -31,8 +31,14 @@ const METADATA: Metadata = Metadata {
     method: Method::PUT,
     name: "create_alias",
     path: "/_matrix/client/r0/directory/room/:room_alias",
+    unstable_path: None,
+    r0_path: Some("/_matrix/client/r0/directory/room/:room_alias"),
+    stable_path: Some("/_matrix/client/v3/directory/room/:room_alias"),
     rate_limited: false,
     authentication: AuthScheme::None,
+    added: None,
+    deprecated: None,
+    removed: None,
 };
 
 impl OutgoingRequest for Request {
@@ -45,8 +51,9 @@ impl OutgoingRequest for Request {
         self,
         base_url: &str,
         _access_token: SendAccessToken<'_>,
+        considering_versions: &[MatrixVersion],
     ) -> Result<http::Request<T>, IntoHttpError> {
-        let url = (base_url.to_owned() + METADATA.path)
+        let url = (base_url.to_owned() + METADATA.select_path(considering_versions)?)
             .replace(":room_alias", &self.room_alias.to_string());
 
         let request_body = RequestBody { room_id: self.room_id };
@@ -18,7 +18,7 @@ ruma_api! {
 fn empty_request_http_repr() {
     let req = Request {};
     let http_req = req
-        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None)
+        .try_into_http_request::<Vec<u8>>("https://homeserver.tld", SendAccessToken::None, &[])
         .unwrap();
 
     assert!(http_req.body().is_empty());
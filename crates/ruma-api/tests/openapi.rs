@@ -0,0 +1,211 @@
+#![cfg(feature = "openapi")]
+#![allow(clippy::exhaustive_enums, clippy::exhaustive_structs)]
+
+use ruma_api::openapi::Document;
+use serde_json::json;
+
+pub mod get_thing {
+    use ruma_api::ruma_api;
+    use ruma_identifiers::{RoomId, UserId};
+
+    ruma_api! {
+        metadata: {
+            description: "Gets a thing.",
+            method: GET,
+            name: "get_thing",
+            r0_path: "/_matrix/client/r0/rooms/:room_id/thing",
+            stable_path: "/_matrix/client/v3/rooms/:room_id/thing",
+            rate_limited: true,
+            authentication: AccessToken,
+            added: R0_6_0,
+        }
+
+        request: {
+            /// The room to get the thing from.
+            #[ruma_api(path)]
+            pub room_id: Box<RoomId>,
+
+            /// The maximum number of things to return.
+            #[ruma_api(query)]
+            pub limit: Option<u32>,
+        }
+
+        response: {
+            /// The things.
+            pub things: Vec<String>,
+
+            /// The owner of the things.
+            #[serde(rename = "owned_by")]
+            pub owner: Option<Box<UserId>>,
+
+            pub thing_kind: ThingKind,
+        }
+    }
+
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    pub enum ThingKind {
+        Big,
+        Small,
+    }
+}
+
+pub mod upload_thing {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Uploads a thing.",
+            method: POST,
+            name: "upload_thing",
+            path: "/_matrix/media/r0/thing",
+            rate_limited: false,
+            authentication: None,
+        }
+
+        request: {
+            #[ruma_api(raw_body)]
+            pub file: Vec<u8>,
+
+            #[ruma_api(header = CONTENT_TYPE)]
+            pub content_type: String,
+        }
+
+        response: {}
+    }
+}
+
+#[test]
+fn endpoint_operation() {
+    let endpoint = get_thing::openapi();
+
+    assert_eq!(
+        endpoint.operation,
+        json!({
+            "operationId": "openapi::get_thing",
+            "summary": "Gets a thing.",
+            "security": [{ "accessToken": [] }],
+            "x-matrix-added": "r0.6.0",
+            "parameters": [
+                {
+                    "name": "room_id",
+                    "in": "path",
+                    "required": true,
+                    "description": "The room to get the thing from.",
+                    "schema": { "type": "string", "x-rust-type": "RoomId" },
+                },
+                {
+                    "name": "limit",
+                    "in": "query",
+                    "required": false,
+                    "description": "The maximum number of things to return.",
+                    "schema": { "type": "integer", "minimum": 0 },
+                },
+            ],
+            "responses": {
+                "200": {
+                    "description": "The request was successful.",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "things": {
+                                        "type": "array",
+                                        "items": { "type": "string" },
+                                        "description": "The things.",
+                                    },
+                                    "owned_by": {
+                                        "type": "string",
+                                        "x-rust-type": "UserId",
+                                        "description": "The owner of the things.",
+                                    },
+                                    "thing_kind": { "x-rust-type": "ThingKind" },
+                                },
+                                "required": ["things", "thing_kind"],
+                            },
+                        },
+                    },
+                },
+                "default": {
+                    "description": "An error occurred.",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "errcode": { "type": "string" },
+                                    "error": { "type": "string" },
+                                },
+                                "required": ["errcode"],
+                            },
+                        },
+                    },
+                },
+                "429": {
+                    "description": "This request was rate-limited.",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "errcode": { "type": "string" },
+                                    "error": { "type": "string" },
+                                    "retry_after_ms": { "type": "integer" },
+                                },
+                                "required": ["errcode"],
+                            },
+                        },
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn raw_body_operation() {
+    let endpoint = upload_thing::openapi();
+
+    assert_eq!(endpoint.operation["security"], json!([]));
+    assert_eq!(
+        endpoint.operation["parameters"],
+        json!([{
+            "name": "content-type",
+            "in": "header",
+            "required": true,
+            "schema": { "type": "string" },
+        }])
+    );
+    assert_eq!(
+        endpoint.operation["requestBody"],
+        json!({
+            "required": true,
+            "content": {
+                "application/octet-stream": {
+                    "schema": { "type": "string", "format": "binary" },
+                },
+            },
+        })
+    );
+    assert!(endpoint.operation["responses"]["200"].get("content").is_none());
+}
+
+#[test]
+fn document() {
+    let mut document = Document::new("Things API", "1.0");
+    document.extend(vec![get_thing::openapi(), upload_thing::openapi()]);
+    let json = document.to_json();
+
+    assert_eq!(json["openapi"], "3.0.3");
+    assert_eq!(json["info"], json!({ "title": "Things API", "version": "1.0" }));
+
+    let paths = json["paths"].as_object().unwrap();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(
+        paths["/_matrix/client/r0/rooms/{room_id}/thing"]["get"]["operationId"],
+        "openapi::get_thing"
+    );
+    assert!(paths["/_matrix/client/v3/rooms/{room_id}/thing"]["get"].get("operationId").is_none());
+    assert_eq!(paths["/_matrix/media/r0/thing"]["post"]["operationId"], "openapi::upload_thing");
+    assert!(json["components"]["securitySchemes"].get("accessToken").is_some());
+}
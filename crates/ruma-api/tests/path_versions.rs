@@ -0,0 +1,105 @@
+#![allow(clippy::exhaustive_structs)]
+
+use ruma_api::{
+    error::{FromHttpRequestError, IntoHttpError},
+    ruma_api, IncomingRequest as _, MatrixVersion, OutgoingRequest as _, SendAccessToken,
+};
+
+ruma_api! {
+    metadata: {
+        description: "Does something.",
+        method: GET,
+        name: "versioned",
+        unstable_path: "/_matrix/client/unstable/org.example/rooms/:room/thing",
+        r0_path: "/_matrix/client/r0/rooms/:room/thing",
+        stable_path: "/_matrix/client/v3/rooms/:room/thing",
+        rate_limited: false,
+        authentication: None,
+        added: R0_6_0,
+        deprecated: V1_1,
+        removed: V1_2,
+    }
+
+    request: {
+        #[ruma_api(path)]
+        pub room: String,
+    }
+
+    response: {}
+}
+
+fn request_path(versions: &[MatrixVersion]) -> Result<String, IntoHttpError> {
+    let request = Request { room: "!room:example.org".to_owned() };
+    let http_request = request.try_into_http_request::<Vec<u8>>(
+        "https://homeserver.tld",
+        SendAccessToken::None,
+        versions,
+    )?;
+
+    Ok(http_request.uri().path().to_owned())
+}
+
+#[test]
+fn metadata() {
+    assert_eq!(METADATA.path, "/_matrix/client/r0/rooms/:room/thing");
+    assert_eq!(METADATA.added, Some(MatrixVersion::R0_6_0));
+    assert_eq!(METADATA.deprecated, Some(MatrixVersion::V1_1));
+    assert_eq!(METADATA.removed, Some(MatrixVersion::V1_2));
+    assert_eq!(METADATA.paths().len(), 3);
+}
+
+#[test]
+fn select_path() {
+    assert_eq!(
+        request_path(&[]).unwrap(),
+        "/_matrix/client/r0/rooms/%21room%3Aexample%2Eorg/thing"
+    );
+    assert_eq!(
+        request_path(&[MatrixVersion::R0_5_0]).unwrap(),
+        "/_matrix/client/unstable/org.example/rooms/%21room%3Aexample%2Eorg/thing"
+    );
+    assert_eq!(
+        request_path(&[MatrixVersion::R0_6_1]).unwrap(),
+        "/_matrix/client/r0/rooms/%21room%3Aexample%2Eorg/thing"
+    );
+    assert_eq!(
+        request_path(&[MatrixVersion::R0_6_1, MatrixVersion::V1_1]).unwrap(),
+        "/_matrix/client/v3/rooms/%21room%3Aexample%2Eorg/thing"
+    );
+    assert!(matches!(
+        request_path(&[MatrixVersion::V1_2]),
+        Err(IntoHttpError::EndpointRemoved(MatrixVersion::V1_2))
+    ));
+}
+
+#[test]
+fn is_supported() {
+    assert!(!METADATA.is_supported(&[]));
+    assert!(!METADATA.is_supported(&[MatrixVersion::R0_5_0]));
+    assert!(METADATA.is_supported(&[MatrixVersion::R0_6_0]));
+    assert!(METADATA.is_supported(&[MatrixVersion::R0_6_1, MatrixVersion::V1_2]));
+    assert!(!METADATA.is_supported(&[MatrixVersion::V1_2]));
+}
+
+#[test]
+fn accept_all_paths() {
+    for path in &[
+        "/_matrix/client/unstable/org.example/rooms/%21room%3Aexample.org/thing",
+        "/_matrix/client/r0/rooms/%21room%3Aexample.org/thing",
+        "/_matrix/client/v3/rooms/%21room%3Aexample.org/thing",
+    ] {
+        let http_request = http::Request::builder().method("GET").uri(*path).body(&[][..]).unwrap();
+        let request = Request::try_from_http_request(http_request).unwrap();
+        assert_eq!(request.room, "!room:example.org");
+    }
+
+    let http_request = http::Request::builder()
+        .method("GET")
+        .uri("/_matrix/client/v4/rooms/%21room%3Aexample.org/thing")
+        .body(&[][..])
+        .unwrap();
+    assert!(matches!(
+        Request::try_from_http_request(http_request),
+        Err(FromHttpRequestError::PathMismatch { .. })
+    ));
+}
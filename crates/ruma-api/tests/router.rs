@@ -0,0 +1,270 @@
+#![allow(clippy::exhaustive_structs)]
+
+use http::{Method, StatusCode};
+use ruma_api::router::Router;
+use serde_json::{from_slice as from_json_slice, json, Value as JsonValue};
+
+mod get_profile {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Gets the profile of a user.",
+            method: GET,
+            name: "get_profile",
+            path: "/_matrix/test/profile/:user_id",
+            rate_limited: false,
+            authentication: None,
+        }
+
+        request: {
+            #[ruma_api(path)]
+            pub user_id: String,
+        }
+
+        response: {
+            pub displayname: String,
+        }
+    }
+}
+
+mod get_own_profile {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Gets the profile of the current user.",
+            method: GET,
+            name: "get_own_profile",
+            path: "/_matrix/test/profile/me",
+            rate_limited: false,
+            authentication: AccessToken,
+        }
+
+        request: {}
+
+        response: {
+            pub displayname: String,
+        }
+    }
+}
+
+mod set_profile {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Sets the profile of a user.",
+            method: PUT,
+            name: "set_profile",
+            path: "/_matrix/test/profile/:user_id",
+            rate_limited: false,
+            authentication: AccessToken,
+        }
+
+        request: {
+            #[ruma_api(path)]
+            pub user_id: String,
+
+            pub displayname: String,
+        }
+
+        response: {}
+    }
+}
+
+mod get_status {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Gets the status of the server.",
+            method: GET,
+            name: "get_status",
+            r0_path: "/_matrix/test/r0/status",
+            stable_path: "/_matrix/test/v3/status",
+            rate_limited: false,
+            authentication: None,
+            added: R0_6_0,
+        }
+
+        request: {}
+
+        response: {
+            pub status: String,
+        }
+    }
+}
+
+mod get_stable_status {
+    use ruma_api::ruma_api;
+
+    ruma_api! {
+        metadata: {
+            description: "Gets the status of the server on the stable path.",
+            method: GET,
+            name: "get_stable_status",
+            stable_path: "/_matrix/test/v3/status",
+            rate_limited: false,
+            authentication: None,
+            added: V1_1,
+        }
+
+        request: {}
+
+        response: {
+            pub status: String,
+        }
+    }
+}
+
+mod get_user {
+    use ruma_api::ruma_api;
+    use ruma_identifiers::UserId;
+
+    ruma_api! {
+        metadata: {
+            description: "Gets a user.",
+            method: GET,
+            name: "get_user",
+            path: "/_matrix/test/user/:user_id",
+            rate_limited: false,
+            authentication: None,
+        }
+
+        request: {
+            #[ruma_api(path)]
+            pub user_id: Box<UserId>,
+        }
+
+        response: {}
+    }
+}
+
+fn router() -> Router {
+    Router::new()
+        .route(|request: get_profile::Request| async move {
+            Ok(get_profile::Response { displayname: format!("profile of {}", request.user_id) })
+        })
+        .route(|_: get_own_profile::Request| async move {
+            Ok(get_own_profile::Response { displayname: "me".to_owned() })
+        })
+        .route(|_: set_profile::Request| async move { Ok(set_profile::Response {}) })
+        .route(|_: get_user::Request| async move { Ok(get_user::Response {}) })
+}
+
+async fn send(router: &Router, method: Method, uri: &str, body: &[u8]) -> (StatusCode, JsonValue) {
+    let request = http::Request::builder().method(method).uri(uri).body(body.to_vec()).unwrap();
+    let response = router.handle(request).await;
+    (response.status(), from_json_slice(response.body()).unwrap())
+}
+
+#[test]
+fn recognize() {
+    let router = router();
+
+    let route = router.recognize(&Method::GET, "/_matrix/test/profile/%40alice%3Aexample.org");
+    let route = route.unwrap();
+    assert_eq!(route.metadata.name, "get_profile");
+    assert_eq!(route.path_params, vec![("user_id", "@alice:example.org".to_owned())]);
+
+    let route = router.recognize(&Method::GET, "/_matrix/test/profile/me").unwrap();
+    assert_eq!(route.metadata.name, "get_own_profile");
+    assert!(route.path_params.is_empty());
+
+    let route = router.recognize(&Method::PUT, "/_matrix/test/profile/me").unwrap();
+    assert_eq!(route.metadata.name, "set_profile");
+
+    assert!(router.recognize(&Method::GET, "/_matrix/test/profile").is_none());
+    assert!(router.recognize(&Method::DELETE, "/_matrix/test/profile/me").is_none());
+}
+
+#[tokio::test]
+async fn dispatch() {
+    let router = router();
+
+    let (status, body) =
+        send(&router, Method::GET, "/_matrix/test/profile/%40bob%3Ab.c", b"").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "displayname": "profile of @bob:b.c" }));
+
+    let (status, body) = send(&router, Method::GET, "/_matrix/test/profile/me", b"").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "displayname": "me" }));
+
+    let (status, body) = send(
+        &router,
+        Method::PUT,
+        "/_matrix/test/profile/%40bob%3Ab.c",
+        br#"{ "displayname": "Bob" }"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({}));
+}
+
+#[tokio::test]
+async fn unrecognized() {
+    let router = router();
+
+    let (status, body) = send(&router, Method::GET, "/_matrix/test/unknown", b"").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["errcode"], "M_UNRECOGNIZED");
+
+    let (status, body) = send(&router, Method::DELETE, "/_matrix/test/profile/me", b"").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body["errcode"], "M_UNRECOGNIZED");
+}
+
+#[tokio::test]
+async fn bad_request() {
+    let router = router();
+
+    let (status, body) =
+        send(&router, Method::PUT, "/_matrix/test/profile/%40bob%3Ab.c", b"{").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errcode"], "M_BAD_JSON");
+}
+
+#[tokio::test]
+async fn invalid_path_parameter() {
+    let router = router();
+
+    let (status, body) = send(&router, Method::GET, "/_matrix/test/user/alice", b"").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errcode"], "M_INVALID_PARAM");
+
+    let (status, _) =
+        send(&router, Method::GET, "/_matrix/test/user/%40alice%3Aexample.org", b"").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn overlapping_paths() {
+    // A later route takes over the paths it shares with an earlier one.
+    let router = Router::new()
+        .route(|_: get_status::Request| async move {
+            Ok(get_status::Response { status: "versioned".to_owned() })
+        })
+        .route(|_: get_stable_status::Request| async move {
+            Ok(get_stable_status::Response { status: "stable".to_owned() })
+        });
+
+    let (_, body) = send(&router, Method::GET, "/_matrix/test/r0/status", b"").await;
+    assert_eq!(body, json!({ "status": "versioned" }));
+    let (_, body) = send(&router, Method::GET, "/_matrix/test/v3/status", b"").await;
+    assert_eq!(body, json!({ "status": "stable" }));
+
+    // An earlier route without paths left is removed.
+    let router = Router::new()
+        .route(|_: get_stable_status::Request| async move {
+            Ok(get_stable_status::Response { status: "stable".to_owned() })
+        })
+        .route(|_: get_status::Request| async move {
+            Ok(get_status::Response { status: "versioned".to_owned() })
+        });
+
+    let (_, body) = send(&router, Method::GET, "/_matrix/test/v3/status", b"").await;
+    assert_eq!(body, json!({ "status": "versioned" }));
+    assert_eq!(format!("{:?}", router).matches("get_stable_status").count(), 0);
+}
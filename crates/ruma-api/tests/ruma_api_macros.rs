@@ -121,6 +121,15 @@ pub mod raw_body_endpoint {
             pub file: Vec<u8>,
         }
     }
+
+    #[test]
+    fn raw_body_marker_traits() {
+        fn assert_raw_body_request<T: ruma_api::OutgoingRawBodyRequest>() {}
+        fn assert_raw_body_response<T: ruma_api::IncomingRawBodyResponse>() {}
+
+        assert_raw_body_request::<Request<'_>>();
+        assert_raw_body_response::<Response>();
+    }
 }
 
 pub mod query_map_endpoint {
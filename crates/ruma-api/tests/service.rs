@@ -0,0 +1,99 @@
+#![cfg(feature = "tower")]
+#![allow(clippy::exhaustive_structs)]
+
+use std::convert::Infallible;
+
+use http::StatusCode;
+use ruma_api::{
+    error::{FromHttpResponseError, ServerError},
+    router::Router,
+    ruma_api,
+    service::{ClientLayer, ClientService, ClientServiceError, HandlerService},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+ruma_api! {
+    metadata: {
+        description: "Greets a user.",
+        method: POST,
+        name: "greet",
+        path: "/_matrix/test/greet/:name",
+        rate_limited: false,
+        authentication: AccessToken,
+    }
+
+    request: {
+        #[ruma_api(path)]
+        pub name: String,
+
+        pub greeting: String,
+    }
+
+    response: {
+        pub message: String,
+    }
+}
+
+async fn greet(request: Request) -> Result<Response, ruma_api::error::MatrixError> {
+    Ok(Response { message: format!("{}, {}!", request.greeting, request.name) })
+}
+
+#[tokio::test]
+async fn handler_service() {
+    let mut service = HandlerService::new(greet);
+
+    let http_request = http::Request::builder()
+        .method("POST")
+        .uri("/_matrix/test/greet/alice")
+        .body(br#"{ "greeting": "Hello" }"#.to_vec())
+        .unwrap();
+    let http_response = service.call(http_request).await.unwrap();
+    assert_eq!(http_response.status(), StatusCode::OK);
+    assert_eq!(http_response.body(), br#"{"message":"Hello, alice!"}"#);
+
+    let http_request = http::Request::builder()
+        .method("POST")
+        .uri("/_matrix/test/greet/alice")
+        .body(b"[]".to_vec())
+        .unwrap();
+    let http_response = service.call(http_request).await.unwrap();
+    assert_eq!(http_response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn client_service() {
+    let router: Router = Router::new().route(greet);
+    let mut client =
+        ClientLayer::new("https://example.org".to_owned(), Some("token".to_owned()), vec![])
+            .layer(router);
+
+    let request = Request { name: "bob".to_owned(), greeting: "Hi".to_owned() };
+    let response = client.call(request).await.unwrap();
+    assert_eq!(response.message, "Hi, bob!");
+}
+
+#[tokio::test]
+async fn client_service_errors() {
+    let router: Router = Router::new();
+    let mut client = ClientService::new(router, "https://example.org".to_owned(), None, vec![]);
+
+    let request = Request { name: "bob".to_owned(), greeting: "Hi".to_owned() };
+    let error: ClientServiceError<Infallible, _> = client.call(request).await.unwrap_err();
+    assert!(matches!(error, ClientServiceError::IntoHttp(_)));
+
+    let mut client = ClientService::new(
+        client.into_inner(),
+        "https://example.org".to_owned(),
+        Some("token".to_owned()),
+        vec![],
+    );
+    let request = Request { name: "bob".to_owned(), greeting: "Hi".to_owned() };
+    let error = client.call(request).await.unwrap_err();
+    match error {
+        ClientServiceError::FromHttpResponse(FromHttpResponseError::Http(ServerError::Known(
+            error,
+        ))) => assert_eq!(error.body["errcode"], "M_UNRECOGNIZED"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/app/v1/transactions/:txn_id",
         rate_limited: false,
         authentication: QueryOnlyAccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -175,6 +176,7 @@ mod tests {
             .try_into_http_request(
                 "https://homeserver.tld",
                 SendAccessToken::IfRequired("auth_tok"),
+                &[],
             )
             .unwrap();
         let json_body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
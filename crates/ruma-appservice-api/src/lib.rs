@@ -10,6 +10,8 @@
 use serde::{Deserialize, Serialize};
 
 pub mod event;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod query;
 pub mod thirdparty;
 
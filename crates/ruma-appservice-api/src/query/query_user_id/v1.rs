@@ -11,6 +11,7 @@ ruma_api! {
         path: "/_matrix/app/v1/users/:user_id",
         rate_limited: false,
         authentication: QueryOnlyAccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -13,6 +13,7 @@ ruma_api! {
         path: "/_matrix/app/v1/thirdparty/location/:protocol",
         rate_limited: false,
         authentication: QueryOnlyAccessToken,
+        added: V1_1,
     }
 
     request: {
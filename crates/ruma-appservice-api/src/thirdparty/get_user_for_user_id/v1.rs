@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/app/v1/thirdparty/user",
         rate_limited: false,
         authentication: QueryOnlyAccessToken,
+        added: V1_1,
     }
 
     request: {
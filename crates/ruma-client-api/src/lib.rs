@@ -9,6 +9,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod error;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod r0;
 pub mod unversioned;
 
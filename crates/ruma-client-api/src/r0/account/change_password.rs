@@ -9,9 +9,11 @@ ruma_api! {
         description: "Change the password of the current user's account.",
         method: POST,
         name: "change_password",
-        path: "/_matrix/client/r0/account/password",
+        r0_path: "/_matrix/client/r0/account/password",
+        stable_path: "/_matrix/client/v3/account/password",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -11,9 +11,11 @@ ruma_api! {
         description: "Request that a password change token is sent to the given email address.",
         method: POST,
         name: "request_password_change_token_via_email",
-        path: "/_matrix/client/r0/account/password/email/requestToken",
+        r0_path: "/_matrix/client/r0/account/password/email/requestToken",
+        stable_path: "/_matrix/client/v3/account/password/email/requestToken",
         rate_limited: false,
         authentication: None,
+        added: R0_4_0,
     }
 
     request: {
@@ -13,9 +13,11 @@ ruma_api! {
         description: "Store several keys in the backup.",
         method: PUT,
         name: "add_backup_keys",
-        path: "/_matrix/client/r0/room_keys/keys",
+        r0_path: "/_matrix/client/r0/room_keys/keys",
+        stable_path: "/_matrix/client/v3/room_keys/keys",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -9,9 +9,11 @@ ruma_api! {
         description: "Creates a new backup.",
         method: POST,
         name: "create_backup",
-        path: "/_matrix/client/r0/room_keys/version",
+        r0_path: "/_matrix/client/r0/room_keys/version",
+        stable_path: "/_matrix/client/v3/room_keys/version",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -9,9 +9,11 @@ ruma_api! {
         description: "Delete a key from the backup",
         method: GET,
         name: "delete_backup_key_session",
-        path: "/_matrix/client/r0/room_keys/keys/:room_id/:session_id",
+        r0_path: "/_matrix/client/r0/room_keys/keys/:room_id/:session_id",
+        stable_path: "/_matrix/client/v3/room_keys/keys/:room_id/:session_id",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
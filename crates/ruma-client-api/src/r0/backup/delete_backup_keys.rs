@@ -8,9 +8,11 @@ ruma_api! {
         description: "Delete all keys in a backup.",
         method: PUT,
         name: "delete_backup_keys",
-        path: "/_matrix/client/r0/room_keys/keys",
+        r0_path: "/_matrix/client/r0/room_keys/keys",
+        stable_path: "/_matrix/client/v3/room_keys/keys",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
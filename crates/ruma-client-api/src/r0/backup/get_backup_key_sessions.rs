@@ -12,9 +12,11 @@ ruma_api! {
         description: "Retrieve sessions from the backup for a given room.",
         method: GET,
         name: "get_backup_key_sessions",
-        path: "/_matrix/client/r0/room_keys/keys/:room_id",
+        r0_path: "/_matrix/client/r0/room_keys/keys/:room_id",
+        stable_path: "/_matrix/client/v3/room_keys/keys/:room_id",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
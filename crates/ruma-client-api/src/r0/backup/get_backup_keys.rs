@@ -12,9 +12,11 @@ ruma_api! {
         description: "Retrieve all keys from a backup.",
         method: GET,
         name: "get_backup_keys",
-        path: "/_matrix/client/r0/room_keys/keys",
+        r0_path: "/_matrix/client/r0/room_keys/keys",
+        stable_path: "/_matrix/client/v3/room_keys/keys",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Get information about the latest backup.",
         method: GET,
         name: "get_latest_backup",
-        path: "/_matrix/client/r0/room_keys/version",
+        r0_path: "/_matrix/client/r0/room_keys/version",
+        stable_path: "/_matrix/client/v3/room_keys/version",
         rate_limited: true,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     #[derive(Default)]
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Gets global account data for a user.",
         name: "get_global_account_data",
         method: GET,
-        path: "/_matrix/client/r0/user/:user_id/account_data/:event_type",
+        r0_path: "/_matrix/client/r0/user/:user_id/account_data/:event_type",
+        stable_path: "/_matrix/client/v3/user/:user_id/account_data/:event_type",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Get the list of rooms in this homeserver's public directory.",
         method: GET,
         name: "get_public_rooms",
-        path: "/_matrix/client/r0/publicRooms",
+        r0_path: "/_matrix/client/r0/publicRooms",
+        stable_path: "/_matrix/client/v3/publicRooms",
         rate_limited: false,
         authentication: None,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -87,6 +89,7 @@ mod tests {
         .try_into_http_request::<Vec<u8>>(
             "https://homeserver.tld",
             SendAccessToken::IfRequired("auth_tok"),
+            &[],
         )
         .unwrap();
 
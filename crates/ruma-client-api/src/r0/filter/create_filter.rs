@@ -10,9 +10,11 @@ ruma_api! {
         description: "Create a new filter for event retrieval.",
         method: POST,
         name: "create_filter",
-        path: "/_matrix/client/r0/user/:user_id/filter",
+        r0_path: "/_matrix/client/r0/user/:user_id/filter",
+        stable_path: "/_matrix/client/v3/user/:user_id/filter",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -86,6 +88,7 @@ mod tests {
                 .try_into_http_request::<Vec<u8>>(
                     "https://matrix.org",
                     SendAccessToken::IfRequired("tok"),
+                    &[],
                 ),
             Ok(res) if res.body() == b"{}"
         );
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Retrieve a previously created filter.",
         method: GET,
         name: "get_filter",
-        path: "/_matrix/client/r0/user/:user_id/filter/:filter_id",
+        r0_path: "/_matrix/client/r0/user/:user_id/filter/:filter_id",
+        stable_path: "/_matrix/client/v3/user/:user_id/filter/:filter_id",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
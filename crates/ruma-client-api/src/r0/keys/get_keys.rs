@@ -15,9 +15,11 @@ ruma_api! {
         description: "Returns the current devices and identity keys for the given users.",
         method: POST,
         name: "get_keys",
-        path: "/_matrix/client/r0/keys/query",
+        r0_path: "/_matrix/client/r0/keys/query",
+        stable_path: "/_matrix/client/v3/keys/query",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_3_0,
     }
 
     #[derive(Default)]
@@ -13,9 +13,11 @@ ruma_api! {
         description: "Publishes cross-signing signatures for the user.",
         method: POST,
         name: "upload_signatures",
-        path: "/_matrix/client/r0/keys/signatures/upload",
+        r0_path: "/_matrix/client/r0/keys/signatures/upload",
+        stable_path: "/_matrix/client/v3/keys/signatures/upload",
         rate_limited: false,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -8,9 +8,11 @@ ruma_api! {
         description: "Forget a room.",
         method: POST,
         name: "forget_room",
-        path: "/_matrix/client/r0/rooms/:room_id/forget",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/forget",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/forget",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Get membership events for a room.",
         method: GET,
         name: "get_member_events",
-        path: "/_matrix/client/r0/rooms/:room_id/members",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/members",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/members",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
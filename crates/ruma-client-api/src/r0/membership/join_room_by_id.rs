@@ -10,9 +10,11 @@ ruma_api! {
         description: "Join a room using its ID.",
         method: POST,
         name: "join_room_by_id",
-        path: "/_matrix/client/r0/rooms/:room_id/join",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/join",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/join",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
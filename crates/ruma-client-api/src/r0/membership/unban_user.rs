@@ -8,9 +8,11 @@ ruma_api! {
         description: "Unban a user from a room.",
         method: POST,
         name: "unban_user",
-        path: "/_matrix/client/r0/rooms/:room_id/unban",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/unban",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/unban",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -14,9 +14,11 @@ ruma_api! {
         description: "Get message events for a room.",
         method: GET,
         name: "get_message_events",
-        path: "/_matrix/client/r0/rooms/:room_id/messages",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/messages",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/messages",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -169,6 +171,7 @@ mod tests {
             .try_into_http_request(
                 "https://homeserver.tld",
                 SendAccessToken::IfRequired("auth_tok"),
+                &[],
             )
             .unwrap();
         assert_eq!(
@@ -197,6 +200,7 @@ mod tests {
             .try_into_http_request::<Vec<u8>>(
                 "https://homeserver.tld",
                 SendAccessToken::IfRequired("auth_tok"),
+                &[],
             )
             .unwrap();
         assert_eq!("from=token&to=token2&dir=b&limit=0", request.uri().query().unwrap(),);
@@ -218,6 +222,7 @@ mod tests {
             .try_into_http_request(
                 "https://homeserver.tld",
                 SendAccessToken::IfRequired("auth_tok"),
+                &[],
             )
             .unwrap();
         assert_eq!(
@@ -9,9 +9,11 @@ ruma_api! {
         description: "Set presence status for this user.",
         method: PUT,
         name: "set_presence",
-        path: "/_matrix/client/r0/presence/:user_id/status",
+        r0_path: "/_matrix/client/r0/presence/:user_id/status",
+        stable_path: "/_matrix/client/v3/presence/:user_id/status",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
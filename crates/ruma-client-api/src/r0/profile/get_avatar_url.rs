@@ -8,9 +8,11 @@ ruma_api! {
         description: "Get the avatar URL of a user.",
         method: GET,
         name: "get_avatar_url",
-        path: "/_matrix/client/r0/profile/:user_id/avatar_url",
+        r0_path: "/_matrix/client/r0/profile/:user_id/avatar_url",
+        stable_path: "/_matrix/client/v3/profile/:user_id/avatar_url",
         rate_limited: false,
         authentication: None,
+        added: R0_0_0,
     }
 
     request: {
@@ -8,9 +8,11 @@ ruma_api! {
         description: "Set the avatar URL of the user.",
         method: PUT,
         name: "set_avatar_url",
-        path: "/_matrix/client/r0/profile/:user_id/avatar_url",
+        r0_path: "/_matrix/client/r0/profile/:user_id/avatar_url",
+        stable_path: "/_matrix/client/v3/profile/:user_id/avatar_url",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
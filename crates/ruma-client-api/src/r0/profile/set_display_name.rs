@@ -8,9 +8,11 @@ ruma_api! {
         description: "Set the display name of the user.",
         method: PUT,
         name: "set_display_name",
-        path: "/_matrix/client/r0/profile/:user_id/displayname",
+        r0_path: "/_matrix/client/r0/profile/:user_id/displayname",
+        stable_path: "/_matrix/client/v3/profile/:user_id/displayname",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
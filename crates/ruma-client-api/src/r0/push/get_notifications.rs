@@ -13,9 +13,11 @@ ruma_api! {
         description: "Paginate through the list of events that the user has been, or would have been notified about.",
         method: GET,
         name: "get_notifications",
-        path: "/_matrix/client/r0/notifications",
+        r0_path: "/_matrix/client/r0/notifications",
+        stable_path: "/_matrix/client/v3/notifications",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_3_0,
     }
 
     #[derive(Default)]
@@ -10,9 +10,11 @@ ruma_api! {
         description: "This endpoint get the actions for the specified push rule.",
         method: GET,
         name: "get_pushrule_actions",
-        path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/actions",
+        r0_path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/actions",
+        stable_path: "/_matrix/client/v3/pushrules/:scope/:kind/:rule_id/actions",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
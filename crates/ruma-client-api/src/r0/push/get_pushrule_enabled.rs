@@ -9,9 +9,11 @@ ruma_api! {
         description: "This endpoint gets whether the specified push rule is enabled.",
         method: GET,
         name: "get_pushrule_enabled",
-        path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/enabled",
+        r0_path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/enabled",
+        stable_path: "/_matrix/client/v3/pushrules/:scope/:kind/:rule_id/enabled",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -8,9 +8,11 @@ ruma_api! {
         description: "Retrieve all push rulesets for this user.",
         method: GET,
         name: "get_pushrules_all",
-        path: "/_matrix/client/r0/pushrules/",
+        r0_path: "/_matrix/client/r0/pushrules/",
+        stable_path: "/_matrix/client/v3/pushrules/",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -10,9 +10,11 @@ ruma_api! {
         description: "This endpoint allows the creation, modification and deletion of pushers for this user ID.",
         method: PUT,
         name: "set_pushrule",
-        path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id",
+        r0_path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id",
+        stable_path: "/_matrix/client/v3/pushrules/:scope/:kind/:rule_id",
         rate_limited: true,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
@@ -9,9 +9,11 @@ ruma_api! {
         description: "This endpoint allows clients to enable or disable the specified push rule.",
         method: PUT,
         name: "set_pushrule_enabled",
-        path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/enabled",
+        r0_path: "/_matrix/client/r0/pushrules/:scope/:kind/:rule_id/enabled",
+        stable_path: "/_matrix/client/v3/pushrules/:scope/:kind/:rule_id/enabled",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
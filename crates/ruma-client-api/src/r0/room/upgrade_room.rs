@@ -8,9 +8,11 @@ ruma_api! {
         description: "Upgrades a room to a particular version.",
         method: POST,
         name: "upgrade_room",
-        path: "/_matrix/client/r0/rooms/:room_id/upgrade",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/upgrade",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/upgrade",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_5_0,
     }
 
     request: {
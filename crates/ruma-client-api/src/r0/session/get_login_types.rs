@@ -16,9 +16,11 @@ ruma_api! {
         description: "Gets the homeserver's supported login types to authenticate users. Clients should pick one of these and supply it as the type when logging in.",
         method: GET,
         name: "get_login_types",
-        path: "/_matrix/client/r0/login",
+        r0_path: "/_matrix/client/r0/login",
+        stable_path: "/_matrix/client/v3/login",
         rate_limited: true,
         authentication: None,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -16,9 +16,11 @@ ruma_api! {
         description: "Login to the homeserver.",
         method: POST,
         name: "login",
-        path: "/_matrix/client/r0/login",
+        r0_path: "/_matrix/client/r0/login",
+        stable_path: "/_matrix/client/v3/login",
         rate_limited: true,
         authentication: None,
+        added: R0_0_0,
     }
 
     request: {
@@ -329,7 +331,7 @@ mod tests {
             device_id: None,
             initial_device_display_name: Some("test"),
         }
-        .try_into_http_request("https://homeserver.tld", SendAccessToken::None)
+        .try_into_http_request("https://homeserver.tld", SendAccessToken::None, &[])
         .unwrap();
 
         let req_body_value: JsonValue = serde_json::from_slice(req.body()).unwrap();
@@ -353,7 +355,7 @@ mod tests {
             device_id: None,
             initial_device_display_name: Some("test"),
         }
-        .try_into_http_request("https://homeserver.tld", SendAccessToken::None)
+        .try_into_http_request("https://homeserver.tld", SendAccessToken::None, &[])
         .unwrap();
 
         let req_body_value: JsonValue = serde_json::from_slice(req.body()).unwrap();
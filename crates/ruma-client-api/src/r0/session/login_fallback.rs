@@ -11,6 +11,7 @@ ruma_api! {
         path: "/_matrix/static/client/login/",
         rate_limited: false,
         authentication: None,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -7,9 +7,11 @@ ruma_api! {
         description: "Log out of the homeserver.",
         method: POST,
         name: "logout",
-        path: "/_matrix/client/r0/logout",
+        r0_path: "/_matrix/client/r0/logout",
+        stable_path: "/_matrix/client/v3/logout",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
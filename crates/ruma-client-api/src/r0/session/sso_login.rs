@@ -7,9 +7,11 @@ ruma_api! {
         description: "",
         method: GET,
         name: "sso_login",
-        path: "/_matrix/client/r0/login/sso/redirect",
+        r0_path: "/_matrix/client/r0/login/sso/redirect",
+        stable_path: "/_matrix/client/v3/login/sso/redirect",
         rate_limited: false,
         authentication: None,
+        added: R0_4_0,
     }
 
     request: {
@@ -52,7 +54,7 @@ mod tests {
     #[test]
     fn serialize_sso_login_request_uri() {
         let req: http::Request<Vec<u8>> = Request { redirect_url: "https://example.com/sso" }
-            .try_into_http_request("https://homeserver.tld", SendAccessToken::None)
+            .try_into_http_request("https://homeserver.tld", SendAccessToken::None, &[])
             .unwrap();
 
         assert_eq!(
@@ -10,9 +10,11 @@ ruma_api! {
         description: "Get state events for a room.",
         method: GET,
         name: "get_state_events",
-        path: "/_matrix/client/r0/rooms/:room_id/state",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/state",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/state",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     request: {
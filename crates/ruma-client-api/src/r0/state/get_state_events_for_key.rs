@@ -10,9 +10,11 @@ ruma_api! {
         description: "Get state events associated with a given key.",
         method: GET,
         name: "get_state_events_for_key",
-        path: "/_matrix/client/r0/rooms/:room_id/state/:event_type/:state_key",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/state/:event_type/:state_key",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/state/:event_type/:state_key",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     response: {
@@ -69,18 +71,22 @@ impl<'a> ruma_api::OutgoingRequest for Request<'a> {
         self,
         base_url: &str,
         access_token: ruma_api::SendAccessToken<'_>,
+        considering_versions: &[ruma_api::MatrixVersion],
     ) -> Result<http::Request<T>, ruma_api::error::IntoHttpError> {
         use std::borrow::Cow;
 
         use http::header;
         use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
-        let mut url = format!(
-            "{}/_matrix/client/r0/rooms/{}/state/{}",
-            base_url.strip_suffix('/').unwrap_or(base_url),
-            utf8_percent_encode(&self.room_id.to_string(), NON_ALPHANUMERIC),
-            utf8_percent_encode(&self.event_type.to_string(), NON_ALPHANUMERIC)
-        );
+        let room_id = utf8_percent_encode(self.room_id.as_str(), NON_ALPHANUMERIC).to_string();
+        let event_type =
+            utf8_percent_encode(self.event_type.as_ref(), NON_ALPHANUMERIC).to_string();
+        let path = METADATA
+            .select_path(considering_versions)?
+            .trim_end_matches("/:state_key")
+            .replace(":room_id", &room_id)
+            .replace(":event_type", &event_type);
+        let mut url = format!("{}{}", base_url.strip_suffix('/').unwrap_or(base_url), path);
 
         if !self.state_key.is_empty() {
             url.push('/');
@@ -21,9 +21,11 @@ ruma_api! {
         description: "Get all new events from all rooms since the last sync or a given point of time.",
         method: GET,
         name: "sync",
-        path: "/_matrix/client/r0/sync",
+        r0_path: "/_matrix/client/r0/sync",
+        stable_path: "/_matrix/client/v3/sync",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -618,7 +620,11 @@ mod client_tests {
             set_presence: &PresenceState::Offline,
             timeout: Some(Duration::from_millis(30000)),
         }
-        .try_into_http_request("https://homeserver.tld", SendAccessToken::IfRequired("auth_tok"))
+        .try_into_http_request(
+            "https://homeserver.tld",
+            SendAccessToken::IfRequired("auth_tok"),
+            &[],
+        )
         .unwrap();
 
         let uri = req.uri();
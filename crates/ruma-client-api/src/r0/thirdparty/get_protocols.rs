@@ -10,9 +10,11 @@ ruma_api! {
         description: "Fetches the overall metadata about protocols supported by the homeserver.",
         method: GET,
         name: "get_protocols",
-        path: "/_matrix/client/r0/thirdparty/protocols",
+        r0_path: "/_matrix/client/r0/thirdparty/protocols",
+        stable_path: "/_matrix/client/v3/thirdparty/protocols",
         rate_limited: false,
         authentication: AccessToken,
+        added: R0_4_0,
     }
 
     #[derive(Default)]
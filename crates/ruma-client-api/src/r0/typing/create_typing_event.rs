@@ -9,10 +9,12 @@ use serde::{de::Error, Deserialize, Deserializer, Serialize};
 ruma_api! {
     metadata: {
         method: PUT,
-        path: "/_matrix/client/r0/rooms/:room_id/typing/:user_id",
+        r0_path: "/_matrix/client/r0/rooms/:room_id/typing/:user_id",
+        stable_path: "/_matrix/client/v3/rooms/:room_id/typing/:user_id",
         name: "create_typing_event",
         description: "Send a typing event to a room.",
         authentication: AccessToken,
+        added: R0_0_0,
         rate_limited: true,
     }
 
@@ -11,6 +11,7 @@ ruma_api! {
         path: "/.well-known/matrix/client",
         rate_limited: false,
         authentication: None,
+        added: R0_4_0,
     }
 
     #[derive(Default)]
@@ -2,7 +2,7 @@
 
 use std::collections::BTreeMap;
 
-use ruma_api::ruma_api;
+use ruma_api::{ruma_api, MatrixVersion};
 
 ruma_api! {
     metadata: {
@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/client/versions",
         rate_limited: false,
         authentication: None,
+        added: R0_0_0,
     }
 
     #[derive(Default)]
@@ -41,4 +42,33 @@ impl Response {
     pub fn new(versions: Vec<String>) -> Self {
         Self { versions, unstable_features: BTreeMap::new() }
     }
+
+    /// Extracts the known Matrix versions from the `versions` of this response.
+    ///
+    /// Versions that are not known to ruma are skipped.
+    pub fn known_versions(&self) -> impl Iterator<Item = MatrixVersion> + '_ {
+        self.versions.iter().filter_map(|version| version.parse().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_api::MatrixVersion;
+
+    use super::Response;
+
+    #[test]
+    fn known_versions() {
+        let response = Response::new(vec![
+            "r0.5.0".to_owned(),
+            "r0.6.1".to_owned(),
+            "v1.1".to_owned(),
+            "v99.0".to_owned(),
+        ]);
+
+        assert_eq!(
+            response.known_versions().collect::<Vec<_>>(),
+            vec![MatrixVersion::R0_5_0, MatrixVersion::R0_6_1, MatrixVersion::V1_1]
+        );
+    }
 }
@@ -0,0 +1,36 @@
+#![cfg(feature = "openapi")]
+
+use std::collections::BTreeSet;
+
+use ruma_client_api::openapi;
+
+#[test]
+fn document() {
+    let json = openapi::document().to_json();
+
+    let mut operation_ids = BTreeSet::new();
+    for path_item in json["paths"].as_object().unwrap().values() {
+        for operation in path_item.as_object().unwrap().values() {
+            if let Some(operation_id) = operation.get("operationId") {
+                let operation_id = operation_id.as_str().unwrap();
+                assert!(operation_ids.insert(operation_id), "duplicate operation {}", operation_id);
+            }
+        }
+    }
+
+    assert!(!operation_ids.is_empty());
+    assert!(json["paths"]["/_matrix/client/r0/directory/room/{room_alias}"].get("get").is_some());
+}
+
+#[test]
+fn all_endpoints() {
+    let document = openapi::document();
+    let has_endpoint = |name| document.endpoints().iter().any(|e| e.metadata.name == name);
+
+    assert!(has_endpoint("whoami"));
+    assert!(has_endpoint("api_versions"));
+    assert_eq!(
+        has_endpoint("check_registration_token_validity"),
+        cfg!(feature = "unstable-pre-spec")
+    );
+}
@@ -5,21 +5,28 @@ use async_stream::try_stream;
 use futures_core::stream::Stream;
 use ruma_client_api::r0::{
     account::register::{self, RegistrationKind},
-    session::login::{self, LoginInfo},
+    session::{
+        login::{self, LoginInfo},
+        logout, logout_all,
+    },
     sync::sync_events,
     uiaa::UserIdentifier,
 };
 use ruma_common::presence::PresenceState;
 use ruma_identifiers::DeviceId;
 
-use super::{Client, Error, HttpClient};
+use super::{Client, Error, EventHandlers, HttpClient};
 
 /// Client-API specific functionality of `Client`.
 impl<C: HttpClient> Client<C> {
     /// Log in with a username and password.
     ///
-    /// In contrast to [`send_request`][Self::send_request], this method stores the access token
-    /// returned by the endpoint in this client, in addition to returning it.
+    /// In contrast to [`send_request`][Self::send_request], this method stores the access token,
+    /// user ID and device ID returned by the endpoint in this client, in addition to returning
+    /// them. They can be retrieved as a [`Session`][crate::Session] with
+    /// [`session`][Self::session].
+    ///
+    /// To resume a session after a soft logout, pass the device ID of the session.
     pub async fn log_in(
         &self,
         user: &str,
@@ -37,14 +44,16 @@ impl<C: HttpClient> Client<C> {
             .await?;
 
         *self.0.access_token.lock().unwrap() = Some(response.access_token.clone());
+        self.set_session_ids(Some(response.user_id.clone()), Some(response.device_id.clone()));
 
         Ok(response)
     }
 
     /// Register as a guest.
     ///
-    /// In contrast to [`send_request`][Self::send_request], this method stores the access token
-    /// returned by the endpoint in this client, in addition to returning it.
+    /// In contrast to [`send_request`][Self::send_request], this method stores the access token,
+    /// user ID and device ID returned by the endpoint in this client, in addition to returning
+    /// them.
     pub async fn register_guest(
         &self,
     ) -> Result<register::Response, Error<C::Error, ruma_client_api::r0::uiaa::UiaaResponse>> {
@@ -53,14 +62,16 @@ impl<C: HttpClient> Client<C> {
             .await?;
 
         *self.0.access_token.lock().unwrap() = response.access_token.clone();
+        self.set_session_ids(Some(response.user_id.clone()), response.device_id.clone());
 
         Ok(response)
     }
 
     /// Register as a new user on this server.
     ///
-    /// In contrast to [`send_request`][Self::send_request], this method stores the access token
-    /// returned by the endpoint in this client, in addition to returning it.
+    /// In contrast to [`send_request`][Self::send_request], this method stores the access token,
+    /// user ID and device ID returned by the endpoint in this client, in addition to returning
+    /// them.
     ///
     /// The username is the local part of the returned user_id. If it is omitted from this request,
     /// the server will generate one.
@@ -74,6 +85,33 @@ impl<C: HttpClient> Client<C> {
             .await?;
 
         *self.0.access_token.lock().unwrap() = response.access_token.clone();
+        self.set_session_ids(Some(response.user_id.clone()), response.device_id.clone());
+
+        Ok(response)
+    }
+
+    /// Log out, invalidating the access token of the client.
+    ///
+    /// In contrast to [`send_request`][Self::send_request], this method removes the access token,
+    /// user ID and device ID from this client.
+    pub async fn logout(
+        &self,
+    ) -> Result<logout::Response, Error<C::Error, ruma_client_api::Error>> {
+        let response = self.send_request(logout::Request::new()).await?;
+        self.clear_session();
+
+        Ok(response)
+    }
+
+    /// Log out of all devices of the user, invalidating all of their access tokens.
+    ///
+    /// In contrast to [`send_request`][Self::send_request], this method removes the access token,
+    /// user ID and device ID from this client.
+    pub async fn logout_all(
+        &self,
+    ) -> Result<logout_all::Response, Error<C::Error, ruma_client_api::Error>> {
+        let response = self.send_request(logout_all::Request::new()).await?;
+        self.clear_session();
 
         Ok(response)
     }
@@ -128,4 +166,71 @@ impl<C: HttpClient> Client<C> {
             }
         }
     }
+
+    /// Like [`sync`][Self::sync], but passes the events of each response to the given event
+    /// handlers before yielding it.
+    ///
+    /// Events that fail to deserialize are passed to the error hook of `handlers` and don't stop
+    /// the stream.
+    pub fn sync_with_handlers<'a>(
+        &'a self,
+        handlers: &'a EventHandlers,
+        filter: Option<&'a sync_events::Filter<'a>>,
+        since: String,
+        set_presence: &'a PresenceState,
+        timeout: Option<Duration>,
+    ) -> impl Stream<Item = Result<sync_events::Response, Error<C::Error, ruma_client_api::Error>>> + 'a
+    {
+        try_stream! {
+            for await response in self.sync(filter, since, set_presence, timeout) {
+                let response = response?;
+                handlers.dispatch(&response).await;
+                yield response;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::AUTHORIZATION, StatusCode};
+    use ruma_identifiers::{device_id, user_id};
+
+    use crate::{http_client::stub::StubServer, Client, Session};
+
+    #[tokio::test]
+    async fn log_in_and_out() {
+        let server = StubServer::new()
+            .respond(
+                StatusCode::OK,
+                r#"{"user_id":"@alice:example.org","access_token":"abc","device_id":"DEVICE"}"#,
+            )
+            .respond(StatusCode::OK, "{}");
+        let client = Client::with_http_client(server, "https://example.org".to_owned(), None);
+
+        client.log_in("alice", "hunter2", None, None).await.unwrap();
+
+        let session = client.session().unwrap();
+        assert_eq!(session.homeserver_url, "https://example.org");
+        assert_eq!(session.user_id, user_id!("@alice:example.org"));
+        assert_eq!(session.device_id, device_id!("DEVICE"));
+        assert_eq!(session.access_token, "abc");
+
+        // The session can be persisted and restored.
+        let session: Session =
+            serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+        let restored = Client::with_http_client_and_session(StubServer::new(), session);
+        let restored = restored.session().unwrap();
+        assert_eq!(restored.user_id, user_id!("@alice:example.org"));
+        assert_eq!(restored.device_id, device_id!("DEVICE"));
+        assert_eq!(restored.access_token, "abc");
+
+        client.logout().await.unwrap();
+
+        assert_eq!(client.0.http_client.requests()[1].headers()[AUTHORIZATION], "Bearer abc");
+        assert!(client.session().is_none());
+        assert_eq!(client.access_token(), None);
+        assert_eq!(client.user_id(), None);
+        assert_eq!(client.device_id(), None);
+    }
 }
@@ -0,0 +1,334 @@
+//! [Server discovery] from the server name of a user ID.
+//!
+//! [Server discovery]: https://matrix.org/docs/spec/client_server/r0.6.1#server-discovery
+
+use std::fmt::{self, Display, Formatter};
+
+use http::{uri::Scheme, StatusCode, Uri};
+use ruma_api::{
+    error::{FromHttpResponseError, MatrixError, ServerError},
+    EndpointError, IncomingResponse, SendAccessToken,
+};
+use ruma_client_api::unversioned::{discover_homeserver, get_supported_versions};
+use ruma_identifiers::UserId;
+
+use super::{
+    send_customized_http_request, Client, DefaultConstructibleHttpClient, Error, HttpClient,
+    RetryPolicy,
+};
+
+/// The error type of [`Client::discover`] and [`Client::discover_with_http_client`].
+///
+/// The spec distinguishes two kinds of failures: after a `FAIL_PROMPT`, the user should be asked
+/// for the homeserver URL; after a `FAIL_ERROR`, the user should be told that server discovery
+/// failed. Use [`is_fail_prompt`][Self::is_fail_prompt] to tell them apart.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum DiscoveryError<E> {
+    /// Fetching `/.well-known/matrix/client` failed or returned invalid data (`FAIL_PROMPT`).
+    WellKnown(Error<E, ruma_client_api::Error>),
+
+    /// A base URL of the discovery information is not a valid URL (`FAIL_ERROR`).
+    InvalidUrl(String),
+
+    /// The homeserver at the discovered base URL didn't respond to `get_supported_versions`
+    /// (`FAIL_ERROR`).
+    Versions {
+        /// The base URL of the homeserver.
+        base_url: String,
+
+        /// The error returned when requesting the supported versions.
+        error: Error<E, ruma_client_api::Error>,
+    },
+
+    /// The identity server at the discovered base URL didn't respond successfully to
+    /// `GET /_matrix/identity/api/v1` (`FAIL_ERROR`).
+    IdentityServer {
+        /// The base URL of the identity server.
+        base_url: String,
+
+        /// The error returned when validating the identity server.
+        error: Error<E, MatrixError>,
+    },
+}
+
+impl<E> DiscoveryError<E> {
+    /// Whether this is a `FAIL_PROMPT` error, after which the user should be asked for the
+    /// homeserver URL.
+    pub fn is_fail_prompt(&self) -> bool {
+        matches!(self, Self::WellKnown(_))
+    }
+}
+
+impl<E: Display> Display for DiscoveryError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WellKnown(err) => write!(f, "Fetching the discovery information failed: {}", err),
+            Self::InvalidUrl(url) => write!(f, "Invalid base URL: {}", url),
+            Self::Versions { base_url, error } => {
+                write!(f, "Validating the homeserver at {} failed: {}", base_url, error)
+            }
+            Self::IdentityServer { base_url, error } => {
+                write!(f, "Validating the identity server at {} failed: {}", base_url, error)
+            }
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for DiscoveryError<E> {}
+
+impl<C: HttpClient> Client<C> {
+    /// Creates a new client using the given underlying HTTP client, with the homeserver of the
+    /// given user ID.
+    ///
+    /// The homeserver is discovered with `/.well-known/matrix/client` on the server name of the
+    /// user ID, falling back to that server name if it returns `404 Not Found`. The discovered
+    /// homeserver is validated with `get_supported_versions`, and the returned versions are used as
+    /// the supported Matrix versions of the client. If the discovery information contains an
+    /// identity server, it is validated with `GET /_matrix/identity/api/v1`, and its base URL is
+    /// set as the identity server URL of the client.
+    pub async fn discover_with_http_client(
+        http_client: C,
+        user_id: &UserId,
+    ) -> Result<Self, DiscoveryError<C::Error>> {
+        let server_url = format!("https://{}", user_id.server_name());
+        let http_res = send_customized_http_request(
+            &http_client,
+            &server_url,
+            SendAccessToken::None,
+            &[],
+            discover_homeserver::Request::new(),
+            |_| Ok(()),
+            RetryPolicy::none(),
+        )
+        .await
+        .map_err(DiscoveryError::WellKnown)?;
+
+        let (base_url, identity_server_url) = if http_res.status() == StatusCode::NOT_FOUND {
+            (server_url, None)
+        } else {
+            let response = discover_homeserver::Response::try_from_http_response(http_res)
+                .map_err(|err| DiscoveryError::WellKnown(err.into()))?;
+            let identity_server_url =
+                response.identity_server.map(|info| validate_url(info.base_url)).transpose()?;
+
+            (validate_url(response.homeserver.base_url)?, identity_server_url)
+        };
+
+        let client = Self::with_http_client(http_client, base_url, None);
+        let versions =
+            client.send_request(get_supported_versions::Request::new()).await.map_err(|error| {
+                DiscoveryError::Versions { base_url: client.0.homeserver_url.clone(), error }
+            })?;
+
+        if let Some(base_url) = &identity_server_url {
+            validate_identity_server(&client.0.http_client, base_url).await.map_err(|error| {
+                DiscoveryError::IdentityServer { base_url: base_url.clone(), error }
+            })?;
+        }
+
+        client.set_supported_matrix_versions(versions.known_versions().collect());
+        client.set_identity_server_url(identity_server_url);
+
+        Ok(client)
+    }
+}
+
+impl<C: DefaultConstructibleHttpClient> Client<C> {
+    /// Creates a new client based on a default-constructed hyper HTTP client, with the homeserver
+    /// of the given user ID.
+    ///
+    /// See [`discover_with_http_client`][Self::discover_with_http_client] for details.
+    pub async fn discover(user_id: &UserId) -> Result<Self, DiscoveryError<C::Error>> {
+        Self::discover_with_http_client(DefaultConstructibleHttpClient::default(), user_id).await
+    }
+}
+
+/// Checks that `url` is an absolute HTTP(S) URL, and removes its trailing slashes.
+fn validate_url<E>(url: String) -> Result<String, DiscoveryError<E>> {
+    let is_valid = url.parse::<Uri>().map_or(false, |uri| {
+        matches!(uri.scheme(), Some(scheme) if *scheme == Scheme::HTTP || *scheme == Scheme::HTTPS)
+            && uri.authority().is_some()
+    });
+
+    if is_valid {
+        Ok(url.trim_end_matches('/').to_owned())
+    } else {
+        Err(DiscoveryError::InvalidUrl(url))
+    }
+}
+
+/// Checks that an identity server is available at `base_url`.
+async fn validate_identity_server<C: HttpClient>(
+    http_client: &C,
+    base_url: &str,
+) -> Result<(), Error<C::Error, MatrixError>> {
+    let http_req = http::Request::get(format!("{}/_matrix/identity/api/v1", base_url))
+        .body(C::RequestBody::default())
+        .map_err(Error::Url)?;
+    let http_res = http_client.send_http_request(http_req).await.map_err(Error::Response)?;
+
+    if http_res.status().is_success() {
+        return Ok(());
+    }
+
+    let error = match MatrixError::try_from_http_response(http_res) {
+        Ok(error) => ServerError::Known(error),
+        Err(error) => ServerError::Unknown(error),
+    };
+    Err(Error::FromHttpResponse(FromHttpResponseError::Http(error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use ruma_api::{
+        error::{FromHttpResponseError, ServerError},
+        MatrixVersion,
+    };
+    use ruma_identifiers::user_id;
+
+    use super::DiscoveryError;
+    use crate::{http_client::stub::StubServer, Client, Error};
+
+    const VERSIONS: &str = r#"{"versions":["r0.5.0","r0.6.1"]}"#;
+
+    #[tokio::test]
+    async fn well_known() {
+        let server = StubServer::new()
+            .respond_to(
+                "https://example.org/.well-known/matrix/client",
+                StatusCode::OK,
+                r#"{
+                    "m.homeserver": { "base_url": "https://matrix.example.org/" },
+                    "m.identity_server": { "base_url": "https://identity.example.org" }
+                }"#,
+            )
+            .respond_to(
+                "https://matrix.example.org/_matrix/client/versions",
+                StatusCode::OK,
+                VERSIONS,
+            )
+            .respond_to(
+                "https://identity.example.org/_matrix/identity/api/v1",
+                StatusCode::OK,
+                "{}",
+            );
+
+        let client = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap();
+
+        assert_eq!(client.0.homeserver_url, "https://matrix.example.org");
+        assert_eq!(client.identity_server_url().as_deref(), Some("https://identity.example.org"));
+        assert_eq!(
+            client.supported_matrix_versions(),
+            vec![MatrixVersion::R0_5_0, MatrixVersion::R0_6_1]
+        );
+    }
+
+    #[tokio::test]
+    async fn well_known_not_found() {
+        let server = StubServer::new()
+            .respond_to("https://example.org/.well-known/matrix/client", StatusCode::NOT_FOUND, "")
+            .respond_to("https://example.org/_matrix/client/versions", StatusCode::OK, VERSIONS);
+
+        let client = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap();
+
+        assert_eq!(client.0.homeserver_url, "https://example.org");
+        assert_eq!(client.identity_server_url(), None);
+    }
+
+    #[tokio::test]
+    async fn fail_prompt() {
+        let server = StubServer::new().respond_to(
+            "https://example.org/.well-known/matrix/client",
+            StatusCode::OK,
+            r#"{ "m.homeserver": {} }"#,
+        );
+
+        let error = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap_err();
+        assert!(error.is_fail_prompt());
+    }
+
+    #[tokio::test]
+    async fn fail_error() {
+        let server = StubServer::new().respond_to(
+            "https://example.org/.well-known/matrix/client",
+            StatusCode::OK,
+            r#"{ "m.homeserver": { "base_url": "matrix.example.org" } }"#,
+        );
+
+        let error = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DiscoveryError::InvalidUrl(url) if url == "matrix.example.org"));
+
+        let server = StubServer::new().respond_to(
+            "https://example.org/.well-known/matrix/client",
+            StatusCode::OK,
+            r#"{ "m.homeserver": { "base_url": "https://matrix.example.org" } }"#,
+        );
+
+        let error = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap_err();
+        assert!(!error.is_fail_prompt());
+        assert!(matches!(
+            error,
+            DiscoveryError::Versions { base_url, .. } if base_url == "https://matrix.example.org"
+        ));
+    }
+
+    #[tokio::test]
+    async fn identity_server_fail_error() {
+        let server = || {
+            StubServer::new()
+                .respond_to(
+                    "https://example.org/.well-known/matrix/client",
+                    StatusCode::OK,
+                    r#"{
+                        "m.homeserver": { "base_url": "https://matrix.example.org" },
+                        "m.identity_server": { "base_url": "https://identity.example.org" }
+                    }"#,
+                )
+                .respond_to(
+                    "https://matrix.example.org/_matrix/client/versions",
+                    StatusCode::OK,
+                    VERSIONS,
+                )
+        };
+
+        // The identity server doesn't respond
+        let error = Client::discover_with_http_client(server(), user_id!("@alice:example.org"))
+            .await
+            .unwrap_err();
+        assert!(!error.is_fail_prompt());
+        assert!(matches!(
+            error,
+            DiscoveryError::IdentityServer { base_url, error: Error::Response(_) }
+                if base_url == "https://identity.example.org"
+        ));
+
+        // The identity server responds with an error
+        let server = server().respond_to(
+            "https://identity.example.org/_matrix/identity/api/v1",
+            StatusCode::NOT_FOUND,
+            r#"{"errcode":"M_UNRECOGNIZED","error":"Unrecognized request"}"#,
+        );
+        let error = Client::discover_with_http_client(server, user_id!("@alice:example.org"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            DiscoveryError::IdentityServer {
+                error: Error::FromHttpResponse(FromHttpResponseError::Http(ServerError::Known(error))),
+                ..
+            } if error.status_code == StatusCode::NOT_FOUND
+        ));
+    }
+}
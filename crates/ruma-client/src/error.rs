@@ -17,11 +17,19 @@ pub enum Error<E, F> {
     /// The request's URL is invalid (this should never happen).
     Url(http::Error),
 
+    /// The raw body field of a request sent with `Client::send_request_with_body_stream` is not
+    /// empty.
+    NonEmptyRawBody,
+
     /// Couldn't obtain an HTTP response (e.g. due to network or DNS issues).
     Response(E),
 
     /// Converting the HTTP response to one of ruma's types failed.
     FromHttpResponse(FromHttpResponseError<F>),
+
+    /// Signing the federation request failed.
+    #[cfg(feature = "server-signatures")]
+    Signatures(ruma_signatures::Error),
 }
 
 impl<E: Display, F: Display> Display for Error<E, F> {
@@ -32,8 +40,13 @@ impl<E: Display, F: Display> Display for Error<E, F> {
             }
             Self::IntoHttp(err) => write!(f, "HTTP request construction failed: {}", err),
             Self::Url(err) => write!(f, "Invalid URL: {}", err),
+            Self::NonEmptyRawBody => {
+                write!(f, "The raw body of a request sent with a body stream must be empty.")
+            }
             Self::Response(err) => write!(f, "Couldn't obtain a response: {}", err),
             Self::FromHttpResponse(err) => write!(f, "HTTP response conversion failed: {}", err),
+            #[cfg(feature = "server-signatures")]
+            Self::Signatures(err) => write!(f, "Signing the request failed: {}", err),
         }
     }
 }
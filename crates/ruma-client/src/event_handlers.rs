@@ -0,0 +1,363 @@
+//! Dispatching of the events of sync responses to handlers registered by event content type.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+};
+
+use futures_core::future::BoxFuture;
+use ruma_client_api::r0::sync::sync_events;
+use ruma_events::{
+    MessageEventContent, StateEventContent, StaticEventContent, SyncMessageEvent, SyncStateEvent,
+    ToDeviceEvent, ToDeviceEventContent,
+};
+use ruma_identifiers::RoomId;
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize,
+};
+use serde_json::value::RawValue as RawJsonValue;
+
+type RoomEventHandler = Box<
+    dyn Fn(&RawJsonValue, &RoomContext) -> serde_json::Result<BoxFuture<'static, ()>> + Send + Sync,
+>;
+
+type ToDeviceEventHandler =
+    Box<dyn Fn(&RawJsonValue) -> serde_json::Result<BoxFuture<'static, ()>> + Send + Sync>;
+
+type ErrorHook = Box<dyn Fn(&EventHandlerError) + Send + Sync>;
+
+/// A registry of event handlers, keyed by event content type.
+///
+/// Events of sync responses are passed to the handlers with [`dispatch`][Self::dispatch], or
+/// with `Client::sync_with_handlers`. Only the events of types that have a handler are
+/// deserialized. Handlers are called one after the other, in the order of the events in the
+/// response and in the order they were added.
+#[derive(Default)]
+pub struct EventHandlers {
+    message: BTreeMap<&'static str, Vec<RoomEventHandler>>,
+    state: BTreeMap<&'static str, Vec<RoomEventHandler>>,
+    to_device: BTreeMap<&'static str, Vec<ToDeviceEventHandler>>,
+    on_error: Option<ErrorHook>,
+}
+
+impl EventHandlers {
+    /// Creates an empty `EventHandlers`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a handler for the message events with the content type `C` in the timelines of
+    /// joined and left rooms.
+    ///
+    /// Redacted events are skipped.
+    pub fn add_message_handler<C, F, Fut>(&mut self, handler: F)
+    where
+        C: MessageEventContent + StaticEventContent,
+        SyncMessageEvent<C>: DeserializeOwned,
+        F: Fn(SyncMessageEvent<C>, RoomContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.message.entry(C::TYPE).or_default().push(Box::new(move |json, context| {
+            let event = serde_json::from_str(json.get())?;
+            Ok(Box::pin(handler(event, context.clone())))
+        }));
+    }
+
+    /// Adds a handler for the state events with the content type `C` in the state and timelines
+    /// of joined and left rooms.
+    ///
+    /// Redacted events are skipped.
+    pub fn add_state_handler<C, F, Fut>(&mut self, handler: F)
+    where
+        C: StateEventContent + StaticEventContent,
+        SyncStateEvent<C>: DeserializeOwned,
+        F: Fn(SyncStateEvent<C>, RoomContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.state.entry(C::TYPE).or_default().push(Box::new(move |json, context| {
+            let event = serde_json::from_str(json.get())?;
+            Ok(Box::pin(handler(event, context.clone())))
+        }));
+    }
+
+    /// Adds a handler for the to-device events with the content type `C`.
+    pub fn add_to_device_handler<C, F, Fut>(&mut self, handler: F)
+    where
+        C: ToDeviceEventContent + StaticEventContent,
+        ToDeviceEvent<C>: DeserializeOwned,
+        F: Fn(ToDeviceEvent<C>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.to_device.entry(C::TYPE).or_default().push(Box::new(move |json| {
+            let event = serde_json::from_str(json.get())?;
+            Ok(Box::pin(handler(event)))
+        }));
+    }
+
+    /// Sets the hook that is called when an event that has a handler fails to deserialize.
+    ///
+    /// Without a hook, such events are skipped silently.
+    pub fn set_error_hook(&mut self, hook: impl Fn(&EventHandlerError) + Send + Sync + 'static) {
+        self.on_error = Some(Box::new(hook));
+    }
+
+    /// Passes the events of the given sync response to the registered handlers.
+    ///
+    /// The events of joined rooms are dispatched first, then those of left rooms, then the
+    /// to-device events. Invites are not dispatched.
+    pub async fn dispatch(&self, response: &sync_events::Response) {
+        let rooms = response
+            .rooms
+            .join
+            .iter()
+            .map(|(room_id, room)| (room_id, &room.state, &room.timeline))
+            .chain(
+                response
+                    .rooms
+                    .leave
+                    .iter()
+                    .map(|(room_id, room)| (room_id, &room.state, &room.timeline)),
+            );
+
+        for (room_id, state, timeline) in rooms {
+            let context = RoomContext { room_id: room_id.clone() };
+            let events = state
+                .events
+                .iter()
+                .map(|event| event.json())
+                .chain(timeline.events.iter().map(|event| event.json()));
+
+            for json in events {
+                let header = match serde_json::from_str::<EventHeader>(json.get()) {
+                    Ok(header) if !header.unsigned.is_redacted() => header,
+                    _ => continue,
+                };
+
+                let handlers = if header.state_key.is_some() { &self.state } else { &self.message };
+                for handler in handlers.get(header.event_type.as_str()).into_iter().flatten() {
+                    match handler(json, &context) {
+                        Ok(future) => future.await,
+                        Err(error) => self.report(&header.event_type, Some(room_id), error),
+                    }
+                }
+            }
+        }
+
+        for event in &response.to_device.events {
+            let json = event.json();
+            let event_type = match serde_json::from_str::<EventHeader>(json.get()) {
+                Ok(header) => header.event_type,
+                Err(_) => continue,
+            };
+
+            for handler in self.to_device.get(event_type.as_str()).into_iter().flatten() {
+                match handler(json) {
+                    Ok(future) => future.await,
+                    Err(error) => self.report(&event_type, None, error),
+                }
+            }
+        }
+    }
+
+    fn report(&self, event_type: &str, room_id: Option<&RoomId>, error: serde_json::Error) {
+        if let Some(on_error) = &self.on_error {
+            on_error(&EventHandlerError {
+                event_type: event_type.to_owned(),
+                room_id: room_id.map(ToOwned::to_owned),
+                error,
+            });
+        }
+    }
+}
+
+impl Debug for EventHandlers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventHandlers")
+            .field("message", &self.message.keys().collect::<Vec<_>>())
+            .field("state", &self.state.keys().collect::<Vec<_>>())
+            .field("to_device", &self.to_device.keys().collect::<Vec<_>>())
+            .field("on_error", &self.on_error.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// The room an event passed to a handler was received in.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RoomContext {
+    /// The ID of the room.
+    pub room_id: Box<RoomId>,
+}
+
+/// An event that has a handler but failed to deserialize, passed to the error hook set with
+/// [`EventHandlers::set_error_hook`].
+#[derive(Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct EventHandlerError {
+    /// The type of the event.
+    pub event_type: String,
+
+    /// The room of the event, if it is not a to-device event.
+    pub room_id: Option<Box<RoomId>>,
+
+    /// The deserialization error.
+    pub error: serde_json::Error,
+}
+
+/// The fields of an event that are needed to find its handlers.
+#[derive(Deserialize)]
+struct EventHeader {
+    #[serde(rename = "type")]
+    event_type: String,
+
+    state_key: Option<IgnoredAny>,
+
+    #[serde(default)]
+    unsigned: UnsignedHeader,
+}
+
+#[derive(Default, Deserialize)]
+struct UnsignedHeader {
+    redacted_because: Option<IgnoredAny>,
+}
+
+impl UnsignedHeader {
+    fn is_redacted(&self) -> bool {
+        self.redacted_because.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use ruma_api::IncomingResponse;
+    use ruma_client_api::r0::sync::sync_events;
+    use ruma_events::{
+        dummy::ToDeviceDummyEventContent,
+        room::{member::RoomMemberEventContent, message::RoomMessageEventContent},
+        SyncMessageEvent, SyncStateEvent, ToDeviceEvent,
+    };
+    use serde_json::json;
+
+    use super::EventHandlers;
+
+    #[tokio::test]
+    async fn dispatch() {
+        let response = sync_events::Response::try_from_http_response(http::Response::new(
+            serde_json::to_vec(&json!({
+                "next_batch": "s1",
+                "rooms": {
+                    "join": {
+                        "!room:example.org": {
+                            "state": {
+                                "events": [{
+                                    "type": "m.room.member",
+                                    "state_key": "@alice:example.org",
+                                    "content": { "membership": "join" },
+                                    "event_id": "$member",
+                                    "sender": "@alice:example.org",
+                                    "origin_server_ts": 1,
+                                }],
+                            },
+                            "timeline": {
+                                "events": [
+                                    {
+                                        "type": "m.room.message",
+                                        "content": { "msgtype": "m.text", "body": "Hi" },
+                                        "event_id": "$message",
+                                        "sender": "@alice:example.org",
+                                        "origin_server_ts": 2,
+                                    },
+                                    {
+                                        "type": "m.room.message",
+                                        "content": { "msgtype": "m.text" },
+                                        "event_id": "$invalid",
+                                        "sender": "@alice:example.org",
+                                        "origin_server_ts": 3,
+                                    },
+                                    {
+                                        "type": "m.room.message",
+                                        "content": {},
+                                        "event_id": "$redacted",
+                                        "sender": "@alice:example.org",
+                                        "origin_server_ts": 4,
+                                        "unsigned": {
+                                            "redacted_because": {
+                                                "type": "m.room.redaction",
+                                                "redacts": "$redacted",
+                                                "content": {},
+                                                "event_id": "$redaction",
+                                                "sender": "@alice:example.org",
+                                                "origin_server_ts": 5,
+                                            },
+                                        },
+                                    },
+                                    {
+                                        "type": "m.room.topic",
+                                        "state_key": "",
+                                        "content": { "topic": "Topic" },
+                                        "event_id": "$topic",
+                                        "sender": "@alice:example.org",
+                                        "origin_server_ts": 6,
+                                    },
+                                ],
+                            },
+                        },
+                    },
+                },
+                "to_device": {
+                    "events": [{
+                        "type": "m.dummy",
+                        "sender": "@bob:example.org",
+                        "content": {},
+                    }],
+                },
+            }))
+            .unwrap(),
+        ))
+        .unwrap();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut handlers = EventHandlers::new();
+
+        let c = calls.clone();
+        handlers.add_message_handler(
+            move |event: SyncMessageEvent<RoomMessageEventContent>, context| {
+                c.lock().unwrap().push(format!("message {} {}", event.event_id, context.room_id));
+                async {}
+            },
+        );
+
+        let c = calls.clone();
+        handlers.add_state_handler(move |event: SyncStateEvent<RoomMemberEventContent>, _| {
+            c.lock().unwrap().push(format!("member {}", event.state_key));
+            async {}
+        });
+
+        let c = calls.clone();
+        handlers.add_to_device_handler(move |event: ToDeviceEvent<ToDeviceDummyEventContent>| {
+            c.lock().unwrap().push(format!("dummy {}", event.sender));
+            async {}
+        });
+
+        let c = calls.clone();
+        handlers.set_error_hook(move |error| {
+            c.lock().unwrap().push(format!("error {}", error.event_type));
+        });
+
+        handlers.dispatch(&response).await;
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "member @alice:example.org",
+                "message $message !room:example.org",
+                "error m.room.message",
+                "dummy @bob:example.org",
+            ]
+        );
+    }
+}
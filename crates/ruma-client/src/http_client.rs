@@ -4,11 +4,14 @@
 use std::{future::Future, pin::Pin};
 
 use async_trait::async_trait;
-use bytes::BufMut;
-use ruma_api::{OutgoingRequest, SendAccessToken};
+use bytes::{BufMut, Bytes};
+use futures_core::stream::Stream;
+use ruma_api::{MatrixVersion, OutgoingRequest, SendAccessToken};
+#[cfg(feature = "server-signatures")]
+use ruma_identifiers::ServerName;
 use ruma_identifiers::UserId;
 
-use crate::{add_user_id_to_query, ResponseError, ResponseResult};
+use crate::{add_user_id_to_query, ResponseError, ResponseResult, RetryPolicy};
 
 #[cfg(feature = "hyper")]
 mod hyper;
@@ -16,6 +19,10 @@ mod hyper;
 mod isahc;
 #[cfg(feature = "reqwest")]
 mod reqwest;
+#[cfg(test)]
+pub(crate) mod stub;
+#[cfg(any(feature = "isahc", feature = "reqwest"))]
+mod sync_byte_stream;
 
 #[cfg(feature = "hyper")]
 pub use self::hyper::Hyper;
@@ -32,7 +39,9 @@ pub use self::reqwest::Reqwest;
 #[async_trait]
 pub trait HttpClient: Sync {
     /// The type to use for `try_into_http_request`.
-    type RequestBody: Default + BufMut + Send;
+    ///
+    /// It has to be `Clone` so requests can be retried.
+    type RequestBody: Clone + Default + BufMut + Send;
 
     /// The type to use for `try_from_http_response`.
     type ResponseBody: AsRef<[u8]>;
@@ -47,6 +56,26 @@ pub trait HttpClient: Sync {
     ) -> Result<http::Response<Self::ResponseBody>, Self::Error>;
 }
 
+/// A boxed error, used for errors of streaming request bodies.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A stream of bytes, used for the bodies of streaming requests and responses.
+pub type ByteStream<E = BoxError> = Pin<Box<dyn Stream<Item = Result<Bytes, E>> + Send>>;
+
+/// An HTTP client that can stream the bodies of requests and responses.
+///
+/// This is used for endpoints with a raw body, like media uploads and downloads, so large files
+/// don't have to be held in memory.
+#[async_trait]
+pub trait StreamingHttpClient: HttpClient {
+    /// Send an `http::Request` with a streaming body to get back an `http::Response` with a
+    /// streaming body.
+    async fn send_streaming_http_request(
+        &self,
+        req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<Self::Error>>, Self::Error>;
+}
+
 /// An HTTP client that has a default configuration.
 pub trait DefaultConstructibleHttpClient: HttpClient {
     /// Creates a new HTTP client with default configuration.
@@ -59,14 +88,24 @@ pub trait DefaultConstructibleHttpClient: HttpClient {
 /// trait should make that relatively easy.
 pub trait HttpClientExt: HttpClient {
     /// Send a strongly-typed matrix request to get back a strongly-typed response.
+    ///
+    /// `for_versions` are the Matrix versions supported by the homeserver, used to select the path
+    /// of the endpoint. It can be empty if they are unknown.
     // TODO: `R: 'a` bound should not be needed
     fn send_matrix_request<'a, R: OutgoingRequest + 'a>(
         &'a self,
         homeserver_url: &str,
         access_token: SendAccessToken<'_>,
+        for_versions: &[MatrixVersion],
         request: R,
     ) -> Pin<Box<dyn Future<Output = ResponseResult<Self, R>> + 'a>> {
-        self.send_customized_matrix_request(homeserver_url, access_token, request, |_| Ok(()))
+        self.send_customized_matrix_request(
+            homeserver_url,
+            access_token,
+            for_versions,
+            request,
+            |_| Ok(()),
+        )
     }
 
     /// Turn a strongly-typed matrix request into an `http::Request`, customize it and send it to
@@ -76,6 +115,7 @@ pub trait HttpClientExt: HttpClient {
         &'a self,
         homeserver_url: &str,
         access_token: SendAccessToken<'_>,
+        for_versions: &[MatrixVersion],
         request: R,
         customize: F,
     ) -> Pin<Box<dyn Future<Output = ResponseResult<Self, R>> + 'a>>
@@ -87,8 +127,10 @@ pub trait HttpClientExt: HttpClient {
             self,
             homeserver_url,
             access_token,
+            for_versions,
             request,
             customize,
+            RetryPolicy::none(),
         ))
     }
 
@@ -101,16 +143,46 @@ pub trait HttpClientExt: HttpClient {
         &'a self,
         homeserver_url: &str,
         access_token: SendAccessToken<'_>,
+        for_versions: &[MatrixVersion],
         user_id: &'a UserId,
         request: R,
     ) -> Pin<Box<dyn Future<Output = ResponseResult<Self, R>> + 'a>> {
         self.send_customized_matrix_request(
             homeserver_url,
             access_token,
+            for_versions,
             request,
             add_user_id_to_query::<Self, R>(user_id),
         )
     }
+
+    /// Turn a strongly-typed federation request into an `http::Request`, sign it with the
+    /// `X-Matrix` authorization scheme and send it to get back a strongly-typed response.
+    ///
+    /// `destination_url` is the base URL of the destination server's federation API, `origin` is
+    /// the name of the sending server and `key_pair` one of its signing keys.
+    #[cfg(feature = "server-signatures")]
+    fn send_federation_request<'a, R, K>(
+        &'a self,
+        destination_url: &str,
+        origin: &'a ServerName,
+        destination: &'a ServerName,
+        key_pair: &'a K,
+        request: R,
+    ) -> Pin<Box<dyn Future<Output = ResponseResult<Self, R>> + 'a>>
+    where
+        Self::RequestBody: AsRef<[u8]>,
+        R: OutgoingRequest + 'a,
+        K: ruma_signatures::KeyPair,
+    {
+        self.send_customized_matrix_request(
+            destination_url,
+            SendAccessToken::None,
+            &[],
+            request,
+            crate::sign_federation_request::<Self, R, K>(origin, destination, key_pair),
+        )
+    }
 }
 
 #[async_trait]
@@ -135,6 +207,16 @@ impl HttpClient for Dummy {
     }
 }
 
+#[async_trait]
+impl StreamingHttpClient for Dummy {
+    async fn send_streaming_http_request(
+        &self,
+        _req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<Self::Error>>, Self::Error> {
+        unimplemented!("this client only exists to allow doctests to compile")
+    }
+}
+
 impl DefaultConstructibleHttpClient for Dummy {
     fn default() -> Self {
         Dummy
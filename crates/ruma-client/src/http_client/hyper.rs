@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use bytes::{Bytes, BytesMut};
 use hyper::client::{connect::Connect, HttpConnector};
 
-use super::{DefaultConstructibleHttpClient, HttpClient};
+use super::{ByteStream, DefaultConstructibleHttpClient, HttpClient, StreamingHttpClient};
 
 /// A basic hyper HTTP client.
 ///
@@ -45,6 +45,20 @@ where
     }
 }
 
+#[async_trait]
+impl<C> StreamingHttpClient for hyper::Client<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    async fn send_streaming_http_request(
+        &self,
+        req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<hyper::Error>>, hyper::Error> {
+        let res = self.request(req.map(hyper::body::Body::wrap_stream)).await?;
+        Ok(res.map(|body| -> ByteStream<hyper::Error> { Box::pin(body) }))
+    }
+}
+
 #[cfg(feature = "hyper")]
 impl DefaultConstructibleHttpClient for Hyper {
     fn default() -> Self {
@@ -1,7 +1,16 @@
-use super::HttpClient;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
+use async_stream::stream;
 use async_trait::async_trait;
-use futures_lite::AsyncReadExt;
+use bytes::{Buf, Bytes};
+use futures_core::{ready, Stream};
+use futures_lite::{AsyncRead, AsyncReadExt};
+
+use super::{sync_byte_stream::SyncByteStream, ByteStream, HttpClient, StreamingHttpClient};
 
 /// The `isahc` crate's `HttpClient`.
 pub type Isahc = isahc::HttpClient;
@@ -22,3 +31,114 @@ impl HttpClient for Isahc {
         Ok(http::Response::from_parts(head, full_body))
     }
 }
+
+#[async_trait]
+impl StreamingHttpClient for Isahc {
+    async fn send_streaming_http_request(
+        &self,
+        req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<isahc::Error>>, isahc::Error> {
+        let req = req.map(|body| isahc::AsyncBody::from_reader(StreamReader::new(body)));
+        let (head, mut body) = self.send_async(req).await?.into_parts();
+
+        let body: ByteStream<isahc::Error> = Box::pin(stream! {
+            let mut buf = vec![0; 8192];
+            loop {
+                match body.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(len) => yield Ok(Bytes::copy_from_slice(&buf[..len])),
+                    Err(error) => {
+                        yield Err(isahc::Error::from(error));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(http::Response::from_parts(head, body))
+    }
+}
+
+/// An `AsyncRead` implementation reading from a `ByteStream`.
+struct StreamReader {
+    stream: SyncByteStream,
+    chunk: Bytes,
+}
+
+impl StreamReader {
+    fn new(stream: ByteStream) -> Self {
+        Self { stream: SyncByteStream::new(stream), chunk: Bytes::new() }
+    }
+}
+
+impl AsyncRead for StreamReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        while this.chunk.is_empty() {
+            match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(Ok(chunk)) => this.chunk = chunk,
+                Some(Err(error)) => {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, error)))
+                }
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+
+        let len = buf.len().min(this.chunk.len());
+        buf[..len].copy_from_slice(&this.chunk[..len]);
+        this.chunk.advance(len);
+
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use async_stream::stream;
+    use bytes::Bytes;
+    use futures_lite::AsyncReadExt;
+
+    use super::StreamReader;
+    use crate::http_client::BoxError;
+
+    #[tokio::test]
+    async fn read_chunks() {
+        let mut reader = StreamReader::new(Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"abc"));
+            yield Ok(Bytes::new());
+            yield Ok(Bytes::from_static(b"de"));
+        }));
+
+        // The buffer is smaller than the first chunk
+        let mut buf = [0; 2];
+        assert_eq!(reader.read(&mut buf).await.unwrap(), 2);
+        assert_eq!(&buf, b"ab");
+        assert_eq!(reader.read(&mut buf).await.unwrap(), 1);
+        assert_eq!(&buf[..1], b"c");
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"de");
+    }
+
+    #[tokio::test]
+    async fn read_error() {
+        let mut reader = StreamReader::new(Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"abc"));
+            yield Err(BoxError::from("broken stream"));
+        }));
+
+        let mut buf = Vec::new();
+        let error = reader.read_to_end(&mut buf).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "broken stream");
+        assert_eq!(buf, b"abc");
+    }
+}
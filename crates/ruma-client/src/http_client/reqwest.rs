@@ -3,7 +3,10 @@ use std::{convert::TryInto, mem};
 use async_trait::async_trait;
 use bytes::{Bytes, BytesMut};
 
-use super::{DefaultConstructibleHttpClient, HttpClient};
+use super::{
+    sync_byte_stream::SyncByteStream, ByteStream, DefaultConstructibleHttpClient, HttpClient,
+    StreamingHttpClient,
+};
 
 /// The `reqwest` crate's `Client`.
 pub type Reqwest = reqwest::Client;
@@ -32,6 +35,28 @@ impl HttpClient for Reqwest {
     }
 }
 
+#[async_trait]
+impl StreamingHttpClient for Reqwest {
+    async fn send_streaming_http_request(
+        &self,
+        req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<reqwest::Error>>, reqwest::Error> {
+        let req =
+            req.map(|body| reqwest::Body::wrap_stream(SyncByteStream::new(body))).try_into()?;
+        let mut res = self.execute(req).await?;
+
+        let mut http_builder =
+            http::Response::builder().status(res.status()).version(res.version());
+        mem::swap(
+            http_builder.headers_mut().expect("http::response::Builder to be usable"),
+            res.headers_mut(),
+        );
+
+        let body: ByteStream<reqwest::Error> = Box::pin(res.bytes_stream());
+        Ok(http_builder.body(body).expect("http::Response construction to work"))
+    }
+}
+
 impl DefaultConstructibleHttpClient for Reqwest {
     fn default() -> Self {
         reqwest::Client::new()
@@ -0,0 +1,143 @@
+//! A stub HTTP client for the tests of this crate.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Mutex, MutexGuard},
+};
+
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, StatusCode};
+
+use super::{ByteStream, HttpClient, StreamingHttpClient};
+use crate::streaming::collect_body;
+
+/// An HTTP client that responds to requests with queued responses or errors, in order, and
+/// records the requests.
+///
+/// Responses can also be registered for a URL, they are then used for every request to that URL
+/// instead of the queue.
+#[derive(Debug, Default)]
+pub(crate) struct StubServer {
+    responses: Mutex<VecDeque<Result<StubResponse, String>>>,
+    url_responses: BTreeMap<String, StubResponse>,
+    requests: Mutex<Vec<http::Request<Vec<u8>>>>,
+}
+
+#[derive(Clone, Debug)]
+struct StubResponse {
+    status: StatusCode,
+    content_type: &'static str,
+    chunks: Vec<Bytes>,
+}
+
+impl StubResponse {
+    fn json(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self { status, content_type: "application/json", chunks: vec![body.into()] }
+    }
+
+    fn into_http_response<T>(self, body: T) -> http::Response<T> {
+        http::Response::builder()
+            .status(self.status)
+            .header(CONTENT_TYPE, self.content_type)
+            .body(body)
+            .unwrap()
+    }
+}
+
+impl StubServer {
+    /// Creates a `StubServer` without any responses.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a JSON response.
+    pub(crate) fn respond(self, status: StatusCode, body: impl Into<Bytes>) -> Self {
+        self.queue(Ok(StubResponse::json(status, body)))
+    }
+
+    /// Queues a response with the given content type, whose body is streamed in `chunks`.
+    pub(crate) fn respond_chunked(
+        self,
+        status: StatusCode,
+        content_type: &'static str,
+        chunks: &[&'static str],
+    ) -> Self {
+        let chunks = chunks.iter().map(|chunk| Bytes::from_static(chunk.as_bytes())).collect();
+        self.queue(Ok(StubResponse { status, content_type, chunks }))
+    }
+
+    /// Queues a transport error.
+    pub(crate) fn fail(self, error: &str) -> Self {
+        self.queue(Err(error.to_owned()))
+    }
+
+    /// Responds to every request to `url` with a JSON response.
+    #[cfg(feature = "client-api")]
+    pub(crate) fn respond_to(mut self, url: &str, status: StatusCode, body: &'static str) -> Self {
+        self.url_responses.insert(url.to_owned(), StubResponse::json(status, body));
+        self
+    }
+
+    /// The requests received so far.
+    pub(crate) fn requests(&self) -> MutexGuard<'_, Vec<http::Request<Vec<u8>>>> {
+        self.requests.lock().unwrap()
+    }
+
+    fn queue(mut self, response: Result<StubResponse, String>) -> Self {
+        self.responses.get_mut().unwrap().push_back(response);
+        self
+    }
+
+    fn response_to(&self, request: http::Request<Vec<u8>>) -> Result<StubResponse, String> {
+        let url = request.uri().to_string();
+        self.requests.lock().unwrap().push(request);
+
+        match self.url_responses.get(&url) {
+            Some(response) => Ok(response.clone()),
+            None => self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Err(format!("unexpected request to {}", url))),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClient for StubServer {
+    type RequestBody = Vec<u8>;
+    type ResponseBody = Vec<u8>;
+    type Error = String;
+
+    async fn send_http_request(
+        &self,
+        req: http::Request<Vec<u8>>,
+    ) -> Result<http::Response<Vec<u8>>, String> {
+        let response = self.response_to(req)?;
+        let body = response.chunks.concat();
+        Ok(response.into_http_response(body))
+    }
+}
+
+#[async_trait]
+impl StreamingHttpClient for StubServer {
+    async fn send_streaming_http_request(
+        &self,
+        req: http::Request<ByteStream>,
+    ) -> Result<http::Response<ByteStream<String>>, String> {
+        let (head, body) = req.into_parts();
+        let body = collect_body(body).await.map_err(|err| err.to_string())?;
+        let response = self.response_to(http::Request::from_parts(head, body))?;
+
+        let chunks = response.chunks.clone();
+        let body: ByteStream<String> = Box::pin(stream! {
+            for chunk in chunks {
+                yield Ok(chunk);
+            }
+        });
+        Ok(response.into_http_response(body))
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_core::stream::Stream;
+
+use super::{BoxError, ByteStream};
+
+/// Wrapper that makes a `ByteStream` `Sync`, for HTTP clients that require it for request bodies.
+///
+/// The stream is only ever accessed through `&mut self`, so the mutex is never locked.
+pub(super) struct SyncByteStream(Mutex<ByteStream>);
+
+impl SyncByteStream {
+    pub(super) fn new(stream: ByteStream) -> Self {
+        Self(Mutex::new(stream))
+    }
+}
+
+impl Stream for SyncByteStream {
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut().0.get_mut().expect("stream mutex to not be poisoned");
+        stream.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_stream::stream;
+    use bytes::Bytes;
+
+    use super::SyncByteStream;
+    use crate::{
+        http_client::{BoxError, ByteStream},
+        streaming::collect_body,
+    };
+
+    fn assert_sync<T: Sync>(_: &T) {}
+
+    #[tokio::test]
+    async fn passes_through_chunks_and_errors() {
+        let stream = SyncByteStream::new(Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"first "));
+            yield Ok(Bytes::from_static(b"second"));
+        }));
+        assert_sync(&stream);
+        let stream: ByteStream = Box::pin(stream);
+        assert_eq!(collect_body(stream).await.unwrap(), b"first second");
+
+        let stream: ByteStream = Box::pin(SyncByteStream::new(Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"first"));
+            yield Err(BoxError::from("broken stream"));
+        })));
+        assert_eq!(collect_body(stream).await.unwrap_err().to_string(), "broken stream");
+    }
+}
@@ -16,19 +16,21 @@
 //! let homeserver_url = "https://example.com".parse().unwrap();
 //! let client = MatrixClient::new(homeserver_url, None);
 //!
-//! let session = client
-//!     .log_in("@alice:example.com", "secret", None, None)
-//!     .await?;
+//! client.log_in("@alice:example.com", "secret", None, None).await?;
 //!
-//! // You're now logged in! Write the session to a file if you want to restore it later.
-//! // Then start using the API!
+//! // You're now logged in! Write the session to a file if you want to restore it later with
+//! // `Client::from_session`. Then start using the API!
+//! let session = client.session();
 //! # Result::<(), ruma_client::Error<_, _>>::Ok(())
 //! # };
 //! ```
 //!
-//! You can also pass an existing access token to the `Client` constructor to restore a previous
-//! session rather than calling `log_in`. This can also be used to create a session for an
-//! application service that does not need to log in, but uses the access_token directly:
+//! Instead of passing the URL of the homeserver, it can be discovered from the server name of a
+//! user ID with `Client::discover` (feature `client-api`).
+//!
+//! You can also pass an existing access token to the `Client` constructor rather than calling
+//! `log_in` or restoring a [`Session`]. This can be used to create a session for an application
+//! service that does not need to log in, but uses the access_token directly:
 //!
 //! ```no_run
 //! # type MatrixClient = ruma_client::Client<ruma_client::http_client::Dummy>;
@@ -88,17 +90,41 @@
 //!   * `reqwest-rustls-manual-roots`
 //!   * `reqwest-rustls-webpki-roots`
 //!   * `reqwest-rustls-native-roots`
+//!
+//! For endpoints with a raw body, like media uploads and downloads, the body can be streamed
+//! instead of being held in memory with `Client::send_request_with_body_stream` and
+//! `Client::send_request_with_response_stream`, if the http client implements
+//! [`StreamingHttpClient`]. All of the above http client types do.
+//!
+//! Requests that were rate limited or failed because of a transport error can be retried
+//! automatically, by setting a [`RetryPolicy`] with `Client::set_retry_policy`.
+//!
+//! The responses of `Client::sync` can be accumulated in a [`SyncState`], which keeps track of the
+//! rooms of the user with their current state, account data and unread notification counts, as
+//! well as received to-device events (feature `client-api`). To react to events instead, register
+//! handlers for their content types in [`EventHandlers`] and sync with
+//! `Client::sync_with_handlers`.
+//!
+//! Endpoints that use User-Interactive Authentication, like `delete_devices` or
+//! `upload_signing_keys`, can be sent with `Client::send_with_uiaa`, which completes the stages
+//! of the authentication with the data provided by a [`UiaaHandler`] (feature `client-api`).
+//!
+//! The `server-signatures` feature adds
+//! [`HttpClientExt::send_federation_request`][http_client::HttpClientExt::send_federation_request]
+//! to send requests of the server-server API, signed with the `X-Matrix` authorization scheme.
 
 #![warn(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 use std::{
+    fmt::{self, Debug, Formatter},
     future::Future,
     sync::{Arc, Mutex},
 };
 
-use ruma_api::{OutgoingRequest, SendAccessToken};
-use ruma_identifiers::UserId;
+use http::StatusCode;
+use ruma_api::{MatrixVersion, OutgoingRequest, SendAccessToken};
+use ruma_identifiers::{DeviceId, UserId};
 
 // "Undo" rename from `Cargo.toml` that only serves to make crate names available as a Cargo
 // feature names.
@@ -109,12 +135,32 @@ extern crate isahc_crate as isahc;
 
 #[cfg(feature = "client-api")]
 mod client_api;
+#[cfg(feature = "client-api")]
+mod discovery;
 mod error;
+#[cfg(feature = "client-api")]
+mod event_handlers;
 pub mod http_client;
+mod retry;
+mod session;
+mod streaming;
+#[cfg(feature = "client-api")]
+mod sync_state;
+#[cfg(feature = "client-api")]
+mod uiaa;
 
+#[cfg(feature = "client-api")]
+pub use self::{
+    discovery::DiscoveryError,
+    event_handlers::{EventHandlerError, EventHandlers, RoomContext},
+    sync_state::{InvitedRoomState, RoomState, SyncState},
+    uiaa::{StageAuth, UiaaError, UiaaHandler},
+};
 pub use self::{
     error::Error,
-    http_client::{DefaultConstructibleHttpClient, HttpClient, HttpClientExt},
+    http_client::{DefaultConstructibleHttpClient, HttpClient, HttpClientExt, StreamingHttpClient},
+    retry::{RetryEvent, RetryHook, RetryPolicy, RetryReason},
+    session::{Session, UnknownToken, UnknownTokenHook},
 };
 
 /// The error type for sending the request `R` with the http client `C`.
@@ -130,7 +176,6 @@ pub type ResponseResult<C, R> =
 pub struct Client<C>(Arc<ClientData<C>>);
 
 /// Data contained in Client's Rc
-#[derive(Debug)]
 struct ClientData<C> {
     /// The URL of the homeserver to connect to.
     homeserver_url: String,
@@ -140,6 +185,39 @@ struct ClientData<C> {
 
     /// User session data.
     access_token: Mutex<Option<String>>,
+
+    /// The ID of the logged in user.
+    user_id: Mutex<Option<Box<UserId>>>,
+
+    /// The ID of the device of the session.
+    device_id: Mutex<Option<Box<DeviceId>>>,
+
+    /// The URL of the identity server, if any.
+    identity_server_url: Mutex<Option<String>>,
+
+    /// The Matrix versions supported by the homeserver.
+    supported_matrix_versions: Mutex<Vec<MatrixVersion>>,
+
+    /// The policy to retry failed requests.
+    retry_policy: Mutex<RetryPolicy>,
+
+    /// The hook called when the access token is rejected by the homeserver.
+    unknown_token_hook: Mutex<Option<UnknownTokenHook>>,
+}
+
+impl<C: Debug> Debug for ClientData<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientData")
+            .field("homeserver_url", &self.homeserver_url)
+            .field("http_client", &self.http_client)
+            .field("access_token", &self.access_token)
+            .field("user_id", &self.user_id)
+            .field("device_id", &self.device_id)
+            .field("identity_server_url", &self.identity_server_url)
+            .field("supported_matrix_versions", &self.supported_matrix_versions)
+            .field("retry_policy", &self.retry_policy)
+            .finish()
+    }
 }
 
 impl<C> Client<C> {
@@ -155,25 +233,156 @@ impl<C> Client<C> {
             homeserver_url,
             http_client,
             access_token: Mutex::new(access_token),
+            user_id: Mutex::new(None),
+            device_id: Mutex::new(None),
+            identity_server_url: Mutex::new(None),
+            supported_matrix_versions: Mutex::new(Vec::new()),
+            retry_policy: Mutex::new(RetryPolicy::none()),
+            unknown_token_hook: Mutex::new(None),
         }))
     }
 
+    /// Creates a new client using the given underlying HTTP client, restoring a session that was
+    /// obtained with `session`.
+    pub fn with_http_client_and_session(http_client: C, session: Session) -> Self {
+        let client =
+            Self::with_http_client(http_client, session.homeserver_url, Some(session.access_token));
+        client.set_session_ids(Some(session.user_id), Some(session.device_id));
+        client
+    }
+
     /// Get a copy of the current `access_token`, if any.
-    ///
-    /// Useful for serializing and persisting the session to be restored later.
     pub fn access_token(&self) -> Option<String> {
         self.0.access_token.lock().expect("session mutex was poisoned").clone()
     }
+
+    /// Get a copy of the ID of the logged in user, if any.
+    pub fn user_id(&self) -> Option<Box<UserId>> {
+        self.0.user_id.lock().expect("session mutex was poisoned").clone()
+    }
+
+    /// Get a copy of the ID of the device of the session, if any.
+    pub fn device_id(&self) -> Option<Box<DeviceId>> {
+        self.0.device_id.lock().expect("session mutex was poisoned").clone()
+    }
+
+    /// Get a copy of the current session, if the client is logged in.
+    ///
+    /// Useful for serializing and persisting the session to be restored later with
+    /// `with_http_client_and_session` or `from_session`.
+    pub fn session(&self) -> Option<Session> {
+        Some(Session::new(
+            self.0.homeserver_url.clone(),
+            self.user_id()?,
+            self.device_id()?,
+            self.access_token()?,
+        ))
+    }
+
+    /// Get a copy of the URL of the identity server, if any.
+    ///
+    /// This is `None` unless it was set with `set_identity_server_url` or discovered with
+    /// `discover`.
+    pub fn identity_server_url(&self) -> Option<String> {
+        self.0.identity_server_url.lock().expect("identity server mutex was poisoned").clone()
+    }
+
+    /// Set the URL of the identity server.
+    pub fn set_identity_server_url(&self, url: Option<String>) {
+        *self.0.identity_server_url.lock().expect("identity server mutex was poisoned") = url;
+    }
+
+    /// Get a copy of the Matrix versions supported by the homeserver.
+    ///
+    /// This is empty unless they were set with `set_supported_matrix_versions`.
+    pub fn supported_matrix_versions(&self) -> Vec<MatrixVersion> {
+        self.0.supported_matrix_versions.lock().expect("versions mutex was poisoned").clone()
+    }
+
+    /// Set the Matrix versions supported by the homeserver, as returned by
+    /// `get_supported_versions`.
+    ///
+    /// They are used to select the path of each endpoint that requests are sent to.
+    pub fn set_supported_matrix_versions(&self, versions: Vec<MatrixVersion>) {
+        *self.0.supported_matrix_versions.lock().expect("versions mutex was poisoned") = versions;
+    }
+
+    /// Get a copy of the policy used to retry failed requests.
+    ///
+    /// By default, requests are not retried.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.0.retry_policy.lock().expect("retry policy mutex was poisoned").clone()
+    }
+
+    /// Set the policy used to retry failed requests.
+    ///
+    /// It applies to `send_request`, `send_customized_request` and `send_request_as`, and the
+    /// methods built on them. It doesn't apply to `send_request_with_body_stream` and
+    /// `send_request_with_response_stream`, which never retry requests.
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        *self.0.retry_policy.lock().expect("retry policy mutex was poisoned") = policy;
+    }
+
+    /// Get a copy of the hook called when the homeserver rejects the access token, if any.
+    pub fn unknown_token_hook(&self) -> Option<UnknownTokenHook> {
+        self.0.unknown_token_hook.lock().expect("unknown token hook mutex was poisoned").clone()
+    }
+
+    /// Set the hook called when the homeserver rejects the access token with `M_UNKNOWN_TOKEN`.
+    ///
+    /// Before the hook is called, the access token is removed from the client. Unless the error
+    /// is a soft logout, the user ID and device ID are removed as well. After a soft logout, the
+    /// session can be resumed by logging in again with the same device ID.
+    pub fn set_unknown_token_hook(&self, hook: Option<UnknownTokenHook>) {
+        *self.0.unknown_token_hook.lock().expect("unknown token hook mutex was poisoned") = hook;
+    }
+
+    /// Removes the access token, user ID and device ID from the client.
+    fn clear_session(&self) {
+        *self.0.access_token.lock().expect("session mutex was poisoned") = None;
+        self.set_session_ids(None, None);
+    }
+
+    fn set_session_ids(&self, user_id: Option<Box<UserId>>, device_id: Option<Box<DeviceId>>) {
+        *self.0.user_id.lock().expect("session mutex was poisoned") = user_id;
+        *self.0.device_id.lock().expect("session mutex was poisoned") = device_id;
+    }
+
+    /// Handles the rejection of the access token, if `http_response` is an `M_UNKNOWN_TOKEN`
+    /// error.
+    fn check_unknown_token<T: AsRef<[u8]>>(&self, http_response: &http::Response<T>) {
+        if let Some(unknown_token) = UnknownToken::from_http_response(http_response) {
+            self.handle_unknown_token(&unknown_token);
+        }
+    }
+
+    fn handle_unknown_token(&self, unknown_token: &UnknownToken) {
+        if unknown_token.soft_logout {
+            *self.0.access_token.lock().expect("session mutex was poisoned") = None;
+        } else {
+            self.clear_session();
+        }
+
+        if let Some(hook) = self.unknown_token_hook() {
+            hook(unknown_token);
+        }
+    }
 }
 
 impl<C: DefaultConstructibleHttpClient> Client<C> {
     /// Creates a new client based on a default-constructed hyper HTTP client.
     pub fn new(homeserver_url: String, access_token: Option<String>) -> Self {
-        Self(Arc::new(ClientData {
+        Self::with_http_client(
+            DefaultConstructibleHttpClient::default(),
             homeserver_url,
-            http_client: DefaultConstructibleHttpClient::default(),
-            access_token: Mutex::new(access_token),
-        }))
+            access_token,
+        )
+    }
+
+    /// Creates a new client based on a default-constructed hyper HTTP client, restoring a session
+    /// that was obtained with `session`.
+    pub fn from_session(session: Session) -> Self {
+        Self::with_http_client_and_session(DefaultConstructibleHttpClient::default(), session)
     }
 }
 
@@ -184,6 +393,9 @@ impl<C: HttpClient> Client<C> {
     }
 
     /// Makes a request to a Matrix API endpoint including additional URL parameters.
+    ///
+    /// If the homeserver rejects the access token of the client with `M_UNKNOWN_TOKEN`, the hook
+    /// set with `set_unknown_token_hook` is called before the error is returned.
     pub async fn send_customized_request<R, F>(
         &self,
         request: R,
@@ -199,14 +411,20 @@ impl<C: HttpClient> Client<C> {
             None => SendAccessToken::None,
         };
 
-        send_customized_request(
+        let http_res = send_customized_http_request(
             &self.0.http_client,
             &self.0.homeserver_url,
             send_access_token,
+            &self.supported_matrix_versions(),
             request,
             customize,
+            self.retry_policy(),
         )
-        .await
+        .await?;
+
+        self.check_unknown_token(&http_res);
+
+        Ok(ruma_api::IncomingResponse::try_from_http_response(http_res)?)
     }
 
     /// Makes a request to a Matrix API endpoint as a virtual user.
@@ -226,16 +444,46 @@ fn send_customized_request<'a, C, R, F>(
     http_client: &'a C,
     homeserver_url: &str,
     send_access_token: SendAccessToken<'_>,
+    for_versions: &[MatrixVersion],
     request: R,
     customize: F,
+    retry_policy: RetryPolicy,
 ) -> impl Future<Output = ResponseResult<C, R>> + Send + 'a
+where
+    C: HttpClient + ?Sized,
+    R: OutgoingRequest,
+    F: FnOnce(&mut http::Request<C::RequestBody>) -> Result<(), ResponseError<C, R>>,
+{
+    let http_res = send_customized_http_request(
+        http_client,
+        homeserver_url,
+        send_access_token,
+        for_versions,
+        request,
+        customize,
+        retry_policy,
+    );
+
+    async move { Ok(ruma_api::IncomingResponse::try_from_http_response(http_res.await?)?) }
+}
+
+/// Sends a request without converting the response, so it can be inspected first.
+fn send_customized_http_request<'a, C, R, F>(
+    http_client: &'a C,
+    homeserver_url: &str,
+    send_access_token: SendAccessToken<'_>,
+    for_versions: &[MatrixVersion],
+    request: R,
+    customize: F,
+    retry_policy: RetryPolicy,
+) -> impl Future<Output = Result<http::Response<C::ResponseBody>, ResponseError<C, R>>> + Send + 'a
 where
     C: HttpClient + ?Sized,
     R: OutgoingRequest,
     F: FnOnce(&mut http::Request<C::RequestBody>) -> Result<(), ResponseError<C, R>>,
 {
     let http_req = request
-        .try_into_http_request(homeserver_url, send_access_token)
+        .try_into_http_request(homeserver_url, send_access_token, for_versions)
         .map_err(ResponseError::<C, R>::from)
         .and_then(|mut req| {
             customize(&mut req)?;
@@ -243,11 +491,53 @@ where
         });
 
     async move {
-        let http_res = http_client.send_http_request(http_req?).await.map_err(Error::Response)?;
-        Ok(ruma_api::IncomingResponse::try_from_http_response(http_res)?)
+        let http_req = http_req?;
+        let mut attempt = 1;
+
+        let http_res = loop {
+            let event = {
+                if attempt >= retry_policy.max_attempts {
+                    break http_client
+                        .send_http_request(http_req)
+                        .await
+                        .map_err(Error::Response)?;
+                }
+
+                let result = http_client.send_http_request(clone_http_request(&http_req)).await;
+                let reason = match &result {
+                    Ok(res) if res.status() == StatusCode::TOO_MANY_REQUESTS => {
+                        RetryReason::rate_limited(res.body().as_ref())
+                    }
+                    Ok(_) => break result.map_err(Error::Response)?,
+                    Err(_) => RetryReason::Transport,
+                };
+
+                if !retry_policy.should_retry(attempt, &R::METADATA, &reason) {
+                    break result.map_err(Error::Response)?;
+                }
+
+                let delay = retry_policy.delay(attempt, &reason);
+                RetryEvent { endpoint: R::METADATA.name, attempt, delay, reason }
+            };
+
+            retry_policy.report(&event);
+            futures_timer::Delay::new(event.delay).await;
+            attempt += 1;
+        };
+
+        Ok(http_res)
     }
 }
 
+fn clone_http_request<B: Clone>(req: &http::Request<B>) -> http::Request<B> {
+    let mut clone = http::Request::new(req.body().clone());
+    *clone.method_mut() = req.method().clone();
+    *clone.uri_mut() = req.uri().clone();
+    *clone.version_mut() = req.version();
+    *clone.headers_mut() = req.headers().clone();
+    clone
+}
+
 fn add_user_id_to_query<C: HttpClient + ?Sized, R: OutgoingRequest>(
     user_id: &UserId,
 ) -> impl FnOnce(&mut http::Request<C::RequestBody>) -> Result<(), ResponseError<C, R>> + '_ {
@@ -269,3 +559,21 @@ fn add_user_id_to_query<C: HttpClient + ?Sized, R: OutgoingRequest>(
         Ok(())
     }
 }
+
+#[cfg(feature = "server-signatures")]
+fn sign_federation_request<'a, C, R, K>(
+    origin: &'a ruma_identifiers::ServerName,
+    destination: &'a ruma_identifiers::ServerName,
+    key_pair: &'a K,
+) -> impl FnOnce(&mut http::Request<C::RequestBody>) -> Result<(), ResponseError<C, R>> + 'a
+where
+    C: HttpClient + ?Sized,
+    C::RequestBody: AsRef<[u8]>,
+    R: OutgoingRequest,
+    K: ruma_signatures::KeyPair,
+{
+    move |http_request| {
+        ruma_signatures::sign_request(origin, destination, key_pair, http_request)
+            .map_err(Error::Signatures)
+    }
+}
@@ -0,0 +1,311 @@
+//! Retrying of requests that failed because of rate limiting or transport errors.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+/// A policy to retry requests that were rejected because of rate limiting, or that failed because
+/// of a transport error.
+///
+/// The default policy makes up to three attempts. Use [`RetryPolicy::none`] to disable retries.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts to send a request, including the first one.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, if the homeserver doesn't specify one.
+    ///
+    /// It is doubled for every following retry, up to `max_backoff`.
+    pub initial_backoff: Duration,
+
+    /// The maximum delay between two attempts, if the homeserver doesn't specify one.
+    pub max_backoff: Duration,
+
+    /// Whether to retry requests to rate limited endpoints when the homeserver responds with
+    /// `429 Too Many Requests`.
+    ///
+    /// The delay is the `retry_after_ms` of the error if it is set, up to `max_retry_after`, and
+    /// the exponential backoff otherwise.
+    pub retry_rate_limited: bool,
+
+    /// The maximum delay before retrying a rate limited request.
+    ///
+    /// A longer `retry_after_ms` requested by the homeserver is cut down to this delay, so a
+    /// misbehaving homeserver can't make the client wait indefinitely.
+    pub max_retry_after: Duration,
+
+    /// Whether to retry requests with an idempotent HTTP method when the HTTP client returns an
+    /// error.
+    pub retry_transport_errors: bool,
+
+    /// A hook that is called before every retry.
+    pub on_retry: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries requests.
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// The exponential backoff after the given failed attempt, starting at 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Whether the given failed attempt should be retried for the given reason.
+    pub(crate) fn should_retry(
+        &self,
+        attempt: u32,
+        metadata: &ruma_api::Metadata,
+        reason: &RetryReason,
+    ) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match reason {
+            RetryReason::RateLimited { .. } => self.retry_rate_limited && metadata.rate_limited,
+            RetryReason::Transport => {
+                self.retry_transport_errors && metadata.method.is_idempotent()
+            }
+        }
+    }
+
+    /// The delay before retrying the given failed attempt.
+    pub(crate) fn delay(&self, attempt: u32, reason: &RetryReason) -> Duration {
+        match reason {
+            RetryReason::RateLimited { retry_after: Some(retry_after) } => {
+                (*retry_after).min(self.max_retry_after)
+            }
+            _ => self.backoff(attempt),
+        }
+    }
+
+    pub(crate) fn report(&self, event: &RetryEvent) {
+        if let Some(on_retry) = &self.on_retry {
+            on_retry(event);
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            retry_rate_limited: true,
+            max_retry_after: Duration::from_secs(60),
+            retry_transport_errors: true,
+            on_retry: None,
+        }
+    }
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("retry_rate_limited", &self.retry_rate_limited)
+            .field("max_retry_after", &self.max_retry_after)
+            .field("retry_transport_errors", &self.retry_transport_errors)
+            .field("on_retry", &self.on_retry.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// A hook that is called before a request is retried.
+pub type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// Information about a request that is about to be retried, passed to
+/// [`RetryPolicy::on_retry`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct RetryEvent {
+    /// The name of the endpoint.
+    pub endpoint: &'static str,
+
+    /// The attempt that failed, starting at 1.
+    pub attempt: u32,
+
+    /// The delay before the next attempt.
+    pub delay: Duration,
+
+    /// Why the attempt failed.
+    pub reason: RetryReason,
+}
+
+/// The reason a request is retried.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum RetryReason {
+    /// The homeserver responded with `429 Too Many Requests`.
+    RateLimited {
+        /// The delay requested by the homeserver, if any.
+        retry_after: Option<Duration>,
+    },
+
+    /// The HTTP client returned an error.
+    Transport,
+}
+
+impl RetryReason {
+    /// Creates a `RateLimited` reason from the body of a `429 Too Many Requests` response.
+    pub(crate) fn rate_limited(body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct LimitExceeded {
+            retry_after_ms: Option<u64>,
+        }
+
+        let retry_after = serde_json::from_slice::<LimitExceeded>(body)
+            .ok()
+            .and_then(|error| error.retry_after_ms)
+            .map(Duration::from_millis);
+
+        Self::RateLimited { retry_after }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use http::StatusCode;
+    use ruma_api::{OutgoingRequest, SendAccessToken};
+    use ruma_client_api::{r0::media::create_content, unversioned::get_supported_versions};
+
+    use super::{RetryPolicy, RetryReason};
+    use crate::{
+        http_client::stub::StubServer, send_customized_http_request, Error, ResponseError,
+    };
+
+    /// A retry policy with short delays, that records the retries.
+    fn policy() -> (RetryPolicy, Arc<Mutex<Vec<String>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            on_retry: Some(Arc::new(move |event| {
+                recorded.lock().unwrap().push(format!(
+                    "{} attempt {} after {:?}: {:?}",
+                    event.endpoint, event.attempt, event.delay, event.reason
+                ));
+            })),
+            ..RetryPolicy::default()
+        };
+
+        (policy, events)
+    }
+
+    async fn send<R: OutgoingRequest>(
+        server: &StubServer,
+        request: R,
+        policy: RetryPolicy,
+    ) -> Result<http::Response<Vec<u8>>, ResponseError<StubServer, R>> {
+        send_customized_http_request(
+            server,
+            "https://example.org",
+            SendAccessToken::IfRequired("token"),
+            &[],
+            request,
+            |_| Ok(()),
+            policy,
+        )
+        .await
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), Duration::from_secs(30));
+        assert_eq!(policy.backoff(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn rate_limited_delay() {
+        let policy = RetryPolicy::default();
+
+        let reason =
+            RetryReason::rate_limited(br#"{"errcode":"M_LIMIT_EXCEEDED","retry_after_ms":2000}"#);
+        assert_eq!(policy.delay(1, &reason), Duration::from_secs(2));
+
+        let reason = RetryReason::rate_limited(br#"{"errcode":"M_LIMIT_EXCEEDED"}"#);
+        assert_eq!(policy.delay(2, &reason), Duration::from_secs(1));
+
+        let reason = RetryReason::rate_limited(
+            br#"{"errcode":"M_LIMIT_EXCEEDED","retry_after_ms":18446744073709551615}"#,
+        );
+        assert_eq!(policy.delay(1, &reason), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn retry_rate_limited() {
+        let server = StubServer::new()
+            .respond(
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"errcode":"M_LIMIT_EXCEEDED","retry_after_ms":1}"#,
+            )
+            .respond(StatusCode::OK, r#"{"content_uri":"mxc://example.org/abc"}"#);
+        let (policy, events) = policy();
+
+        let response = send(&server, create_content::Request::new(b"file"), policy).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["create_media_content attempt 1 after 1ms: RateLimited { retry_after: Some(1ms) }"]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_retry_of_transport_error_on_post() {
+        let server = StubServer::new().fail("connection reset");
+        let (policy, events) = policy();
+
+        let result = send(&server, create_content::Request::new(b"file"), policy).await;
+
+        assert!(matches!(result, Err(Error::Response(error)) if error == "connection reset"));
+        assert_eq!(server.requests().len(), 1);
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn max_attempts() {
+        let server = StubServer::new()
+            .fail("connection reset")
+            .fail("connection reset")
+            .fail("connection reset")
+            .respond(StatusCode::OK, r#"{"versions":["r0.6.1"]}"#);
+        let (policy, events) = policy();
+
+        let result = send(&server, get_supported_versions::Request::new(), policy).await;
+
+        assert!(matches!(result, Err(Error::Response(_))));
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "api_versions attempt 1 after 1ms: Transport",
+                "api_versions attempt 2 after 2ms: Transport",
+            ]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Persistence of the session of a logged in user, and handling of invalidated access tokens.
+
+use std::sync::Arc;
+
+use http::StatusCode;
+use ruma_identifiers::{DeviceId, UserId};
+use serde::{Deserialize, Serialize};
+
+/// The session of a logged in user.
+///
+/// It can be obtained with `Client::session` after logging in, persisted, and restored later with
+/// `Client::from_session` or `Client::with_http_client_and_session`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct Session {
+    /// The URL of the homeserver.
+    pub homeserver_url: String,
+
+    /// The ID of the logged in user.
+    pub user_id: Box<UserId>,
+
+    /// The ID of the device of the session.
+    pub device_id: Box<DeviceId>,
+
+    /// The access token of the session.
+    pub access_token: String,
+}
+
+impl Session {
+    /// Creates a new `Session` with the given homeserver URL, user ID, device ID and access token.
+    pub fn new(
+        homeserver_url: String,
+        user_id: Box<UserId>,
+        device_id: Box<DeviceId>,
+        access_token: String,
+    ) -> Self {
+        Self { homeserver_url, user_id, device_id, access_token }
+    }
+}
+
+/// A hook that is called when the homeserver rejects the access token of a client.
+pub type UnknownTokenHook = Arc<dyn Fn(&UnknownToken) + Send + Sync>;
+
+/// Information about an access token that was rejected by the homeserver with `M_UNKNOWN_TOKEN`,
+/// passed to the hook set with `Client::set_unknown_token_hook`.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct UnknownToken {
+    /// Whether this is a [soft logout].
+    ///
+    /// If it is `true`, the user ID and device ID of the session are kept, and a new access token
+    /// can be acquired by logging in again with the same device ID.
+    ///
+    /// [soft logout]: https://matrix.org/docs/spec/client_server/r0.6.1#soft-logout
+    pub soft_logout: bool,
+}
+
+impl UnknownToken {
+    /// Creates an `UnknownToken` from an HTTP response, if it is an `M_UNKNOWN_TOKEN` error.
+    pub(crate) fn from_http_response<T: AsRef<[u8]>>(
+        http_response: &http::Response<T>,
+    ) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            errcode: String,
+            #[serde(default)]
+            soft_logout: bool,
+        }
+
+        if http_response.status() != StatusCode::UNAUTHORIZED {
+            return None;
+        }
+
+        serde_json::from_slice::<ErrorBody>(http_response.body().as_ref())
+            .ok()
+            .filter(|error| error.errcode == "M_UNKNOWN_TOKEN")
+            .map(|error| Self { soft_logout: error.soft_logout })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use http::StatusCode;
+    use ruma_client_api::r0::session::get_login_types;
+    use ruma_identifiers::{device_id, user_id};
+
+    use super::{Session, UnknownToken, UnknownTokenHook};
+    use crate::{http_client::stub::StubServer, Client};
+
+    fn response(status: StatusCode, body: &'static str) -> http::Response<&'static [u8]> {
+        http::Response::builder().status(status).body(body.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn unknown_token() {
+        let unknown_token = UnknownToken::from_http_response(&response(
+            StatusCode::UNAUTHORIZED,
+            r#"{"errcode":"M_UNKNOWN_TOKEN","error":"Soft logged out","soft_logout":true}"#,
+        ))
+        .unwrap();
+        assert!(unknown_token.soft_logout);
+
+        let unknown_token = UnknownToken::from_http_response(&response(
+            StatusCode::UNAUTHORIZED,
+            r#"{"errcode":"M_UNKNOWN_TOKEN","error":"Invalid access token"}"#,
+        ))
+        .unwrap();
+        assert!(!unknown_token.soft_logout);
+    }
+
+    #[test]
+    fn other_errors() {
+        assert!(UnknownToken::from_http_response(&response(
+            StatusCode::UNAUTHORIZED,
+            r#"{"flows":[{"stages":["m.login.dummy"]}],"params":{}}"#,
+        ))
+        .is_none());
+        assert!(UnknownToken::from_http_response(&response(
+            StatusCode::FORBIDDEN,
+            r#"{"errcode":"M_FORBIDDEN","error":"Forbidden"}"#,
+        ))
+        .is_none());
+    }
+
+    /// Sends a request that the homeserver answers with `M_UNKNOWN_TOKEN` from a logged in client,
+    /// and returns the client and the `soft_logout` fields passed to the unknown token hook.
+    async fn reject_token(body: &'static str) -> (Client<StubServer>, Vec<bool>) {
+        let session = Session::new(
+            "https://example.org".to_owned(),
+            user_id!("@alice:example.org").to_owned(),
+            device_id!("DEVICE").to_owned(),
+            "abc".to_owned(),
+        );
+        let server = StubServer::new().respond(StatusCode::UNAUTHORIZED, body);
+        let client = Client::with_http_client_and_session(server, session);
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let hook: UnknownTokenHook = Arc::new(move |unknown_token| {
+            recorded.lock().unwrap().push(unknown_token.soft_logout);
+        });
+        client.set_unknown_token_hook(Some(hook));
+
+        assert!(client.send_request(get_login_types::Request::new()).await.is_err());
+
+        let calls = calls.lock().unwrap().clone();
+        (client, calls)
+    }
+
+    #[tokio::test]
+    async fn soft_logout() {
+        let (client, calls) = reject_token(
+            r#"{"errcode":"M_UNKNOWN_TOKEN","error":"Soft logged out","soft_logout":true}"#,
+        )
+        .await;
+
+        assert_eq!(calls, [true]);
+        assert_eq!(client.access_token(), None);
+        assert_eq!(client.user_id().as_deref(), Some(user_id!("@alice:example.org")));
+        assert_eq!(client.device_id().as_deref(), Some(device_id!("DEVICE")));
+        assert!(client.session().is_none());
+    }
+
+    #[tokio::test]
+    async fn invalid_token() {
+        let (client, calls) =
+            reject_token(r#"{"errcode":"M_UNKNOWN_TOKEN","error":"Invalid access token"}"#).await;
+
+        assert_eq!(calls, [false]);
+        assert_eq!(client.access_token(), None);
+        assert_eq!(client.user_id(), None);
+        assert_eq!(client.device_id(), None);
+    }
+}
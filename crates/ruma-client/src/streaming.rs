@@ -0,0 +1,312 @@
+use std::{
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_stream::stream;
+use bytes::Bytes;
+use futures_core::ready;
+use ruma_api::{
+    error::{FromHttpResponseError, ServerError},
+    EndpointError, IncomingRawBodyResponse, IncomingResponse, OutgoingRawBodyRequest,
+    OutgoingRequest, SendAccessToken,
+};
+
+use super::{
+    http_client::{BoxError, ByteStream, StreamingHttpClient},
+    Client, Error, ResponseError, ResponseResult,
+};
+
+/// Streaming functionality of `Client`, for endpoints with a raw body like media uploads and
+/// downloads.
+impl<C: StreamingHttpClient> Client<C> {
+    /// Makes a request to a Matrix API endpoint, streaming `body` as the request body.
+    ///
+    /// The body of the HTTP request is replaced by `body`, so the raw body field of `request` must
+    /// be left empty.
+    ///
+    /// Like with `send_request`, the hook set with `set_unknown_token_hook` is called if the
+    /// homeserver rejects the access token. Unlike `send_request`, the request is never retried,
+    /// regardless of the policy set with `set_retry_policy`, since `body` can only be sent once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NonEmptyRawBody`] if the raw body field of `request` is not empty.
+    pub async fn send_request_with_body_stream<R: OutgoingRawBodyRequest>(
+        &self,
+        request: R,
+        body: ByteStream,
+    ) -> ResponseResult<C, R> {
+        let http_request = self.http_request(request)?;
+        if !http_request.body().is_empty() {
+            return Err(Error::NonEmptyRawBody);
+        }
+
+        let http_request = http_request.map(|_| body);
+        let http_response = self
+            .0
+            .http_client
+            .send_streaming_http_request(http_request)
+            .await
+            .map_err(Error::Response)?;
+
+        let (head, body) = http_response.into_parts();
+        let body = collect_body(body).await.map_err(Error::Response)?;
+        let http_response = http::Response::from_parts(head, body);
+        self.check_unknown_token(&http_response);
+
+        Ok(R::IncomingResponse::try_from_http_response(http_response)?)
+    }
+
+    /// Makes a request to a Matrix API endpoint, returning the response body as a stream.
+    ///
+    /// The raw body field of the returned response is left empty, the body can be read from the
+    /// returned stream instead. Error responses are read completely and returned as errors.
+    ///
+    /// Like with `send_request`, the hook set with `set_unknown_token_hook` is called if the
+    /// homeserver rejects the access token. Unlike `send_request`, the request is never retried,
+    /// regardless of the policy set with `set_retry_policy`.
+    pub async fn send_request_with_response_stream<R>(
+        &self,
+        request: R,
+    ) -> Result<(R::IncomingResponse, ByteStream<C::Error>), ResponseError<C, R>>
+    where
+        R: OutgoingRequest,
+        R::IncomingResponse: IncomingRawBodyResponse,
+    {
+        let http_request = self.http_request(request)?.map(once_stream);
+        let http_response = self
+            .0
+            .http_client
+            .send_streaming_http_request(http_request)
+            .await
+            .map_err(Error::Response)?;
+
+        let (head, body) = http_response.into_parts();
+        if head.status.as_u16() < 400 {
+            let http_response = http::Response::from_parts(head, Vec::new());
+            Ok((R::IncomingResponse::try_from_http_response(http_response)?, body))
+        } else {
+            let body = collect_body(body).await.map_err(Error::Response)?;
+            let http_response = http::Response::from_parts(head, body);
+            self.check_unknown_token(&http_response);
+
+            let error = match R::EndpointError::try_from_http_response(http_response) {
+                Ok(error) => ServerError::Known(error),
+                Err(error) => ServerError::Unknown(error),
+            };
+
+            Err(Error::FromHttpResponse(FromHttpResponseError::Http(error)))
+        }
+    }
+
+    fn http_request<R: OutgoingRequest>(
+        &self,
+        request: R,
+    ) -> Result<http::Request<Vec<u8>>, ResponseError<C, R>> {
+        let access_token = self.access_token();
+        let send_access_token = match access_token.as_deref() {
+            Some(at) => SendAccessToken::IfRequired(at),
+            None => SendAccessToken::None,
+        };
+
+        Ok(request.try_into_http_request(
+            &self.0.homeserver_url,
+            send_access_token,
+            &self.supported_matrix_versions(),
+        )?)
+    }
+}
+
+fn once_stream(body: Vec<u8>) -> ByteStream {
+    Box::pin(stream! {
+        if !body.is_empty() {
+            yield Ok::<_, BoxError>(Bytes::from(body));
+        }
+    })
+}
+
+/// A future that reads a whole streaming body into memory.
+pub(crate) struct CollectBody<E> {
+    body: ByteStream<E>,
+    buf: Vec<u8>,
+}
+
+impl<E> Future for CollectBody<E> {
+    type Output = Result<Vec<u8>, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match ready!(self.body.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => self.buf.extend_from_slice(&chunk),
+                Some(Err(error)) => return Poll::Ready(Err(error)),
+                None => return Poll::Ready(Ok(mem::take(&mut self.buf))),
+            }
+        }
+    }
+}
+
+pub(crate) fn collect_body<E>(body: ByteStream<E>) -> CollectBody<E> {
+    CollectBody { body, buf: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use async_stream::stream;
+    use bytes::Bytes;
+    use http::StatusCode;
+    use ruma_api::error::{FromHttpResponseError, ServerError};
+    use ruma_client_api::{
+        error::ErrorKind,
+        r0::media::{create_content, get_content},
+    };
+    use ruma_identifiers::server_name;
+
+    use super::collect_body;
+    use crate::{
+        http_client::{stub::StubServer, BoxError, ByteStream},
+        Client, Error, UnknownTokenHook,
+    };
+
+    fn client(server: StubServer) -> Client<StubServer> {
+        Client::with_http_client(server, "https://example.org".to_owned(), Some("token".to_owned()))
+    }
+
+    fn request_bodies(client: &Client<StubServer>) -> Vec<Vec<u8>> {
+        client.0.http_client.requests().iter().map(|request| request.body().clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn upload() {
+        let client = client(StubServer::new().respond_chunked(
+            StatusCode::OK,
+            "application/json",
+            &[r#"{"content_uri":"#, r#""mxc://example.org/abc"}"#],
+        ));
+
+        let body: ByteStream = Box::pin(stream! {
+            for chunk in &["first ", "second ", "third"] {
+                yield Ok::<_, BoxError>(Bytes::from_static(chunk.as_bytes()));
+            }
+        });
+        let response = client
+            .send_request_with_body_stream(create_content::Request::new(&[]), body)
+            .await
+            .unwrap();
+
+        assert_eq!(response.content_uri, "mxc://example.org/abc");
+        assert_eq!(request_bodies(&client), [b"first second third"]);
+    }
+
+    #[tokio::test]
+    async fn upload_with_raw_body() {
+        let client =
+            client(StubServer::new().respond_chunked(StatusCode::OK, "application/json", &[]));
+
+        let body: ByteStream = Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"stream"));
+        });
+        let result =
+            client.send_request_with_body_stream(create_content::Request::new(b"file"), body).await;
+
+        assert!(matches!(result, Err(Error::NonEmptyRawBody)));
+        assert!(client.0.http_client.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn download() {
+        let client = client(StubServer::new().respond_chunked(
+            StatusCode::OK,
+            "text/plain",
+            &["first ", "second"],
+        ));
+
+        let (response, body) = client
+            .send_request_with_response_stream(get_content::Request::new(
+                "abc",
+                server_name!("example.org"),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.content_type.as_deref(), Some("text/plain"));
+        assert!(response.file.is_empty());
+        assert_eq!(collect_body(body).await.unwrap(), b"first second");
+        assert_eq!(request_bodies(&client), [b""]);
+    }
+
+    #[tokio::test]
+    async fn download_error() {
+        let client = client(StubServer::new().respond_chunked(
+            StatusCode::NOT_FOUND,
+            "application/json",
+            &[r#"{"errcode":"M_NOT_FOUND","#, r#""error":"Not found"}"#],
+        ));
+
+        let result = client
+            .send_request_with_response_stream(get_content::Request::new(
+                "abc",
+                server_name!("example.org"),
+            ))
+            .await;
+
+        match result {
+            Err(Error::FromHttpResponse(FromHttpResponseError::Http(ServerError::Known(
+                error,
+            )))) => {
+                assert_eq!(error.kind, ErrorKind::NotFound);
+                assert_eq!(error.message, "Not found");
+            }
+            result => panic!("unexpected result: {:?}", result.map(|(response, _)| response)),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_token() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let hook_calls = calls.clone();
+        let hook: UnknownTokenHook = Arc::new(move |_| {
+            hook_calls.fetch_add(1, Ordering::SeqCst);
+        });
+        let unauthorized_client = || {
+            let client = client(StubServer::new().respond_chunked(
+                StatusCode::UNAUTHORIZED,
+                "application/json",
+                &[r#"{"errcode":"M_UNKNOWN_TOKEN","error":"Unknown token"}"#],
+            ));
+            client.set_unknown_token_hook(Some(hook.clone()));
+            client
+        };
+
+        let upload_client = unauthorized_client();
+        let body: ByteStream = Box::pin(stream! {
+            yield Ok::<_, BoxError>(Bytes::from_static(b"file"));
+        });
+        let result = upload_client
+            .send_request_with_body_stream(create_content::Request::new(&[]), body)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(upload_client.access_token(), None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let download_client = unauthorized_client();
+        let result = download_client
+            .send_request_with_response_stream(get_content::Request::new(
+                "abc",
+                server_name!("example.org"),
+            ))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(download_client.access_token(), None);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,525 @@
+//! Accumulation of the responses of the `sync_events` endpoint.
+
+use std::collections::BTreeMap;
+
+use ruma_client_api::r0::sync::sync_events::{
+    self, InvitedRoom, JoinedRoom, LeftRoom, RoomSummary, State, Timeline, UnreadNotificationsCount,
+};
+use ruma_events::{
+    room::{
+        member::{MembershipState, RoomMemberEventContent},
+        name::RoomNameEventContent,
+        topic::RoomTopicEventContent,
+    },
+    AnyGlobalAccountDataEvent, AnyRoomAccountDataEvent, AnyStrippedStateEvent, AnySyncStateEvent,
+    AnyToDeviceEvent, StateEventContent, StaticEventContent, StrippedStateEvent, SyncStateEvent,
+};
+use ruma_identifiers::{RoomId, RoomName, UserId};
+use ruma_serde::Raw;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The state of a client, accumulated from the responses of the `sync_events` endpoint.
+///
+/// Responses are applied incrementally with [`apply`][Self::apply]. A `SyncState` can be
+/// serialized to persist it, together with the `next_batch` token to continue syncing from.
+///
+/// Events are stored as received, and only deserialized when they are accessed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SyncState {
+    next_batch: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    joined: BTreeMap<Box<RoomId>, RoomState>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    invited: BTreeMap<Box<RoomId>, InvitedRoomState>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    left: BTreeMap<Box<RoomId>, RoomState>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    account_data: BTreeMap<String, Raw<AnyGlobalAccountDataEvent>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    to_device: Vec<Raw<AnyToDeviceEvent>>,
+}
+
+impl SyncState {
+    /// Creates an empty `SyncState`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `next_batch` token of the last applied response, to pass as `since` to the next sync.
+    pub fn next_batch(&self) -> Option<&str> {
+        self.next_batch.as_deref()
+    }
+
+    /// Applies the given response of the `sync_events` endpoint.
+    ///
+    /// Rooms are moved between the joined, invited and left rooms according to the response.
+    /// Knocked rooms are ignored.
+    pub fn apply(&mut self, response: &sync_events::Response) {
+        for (room_id, room) in &response.rooms.join {
+            self.invited.remove(room_id);
+            let room_state = match self.left.remove(room_id) {
+                Some(room_state) => self.joined.entry(room_id.clone()).or_insert(room_state),
+                None => self.joined.entry(room_id.clone()).or_default(),
+            };
+            room_state.apply_joined(room);
+        }
+
+        for (room_id, room) in &response.rooms.invite {
+            self.left.remove(room_id);
+            self.invited.entry(room_id.clone()).or_default().apply(room);
+        }
+
+        for (room_id, room) in &response.rooms.leave {
+            self.invited.remove(room_id);
+            let room_state = match self.joined.remove(room_id) {
+                Some(room_state) => self.left.entry(room_id.clone()).or_insert(room_state),
+                None => self.left.entry(room_id.clone()).or_default(),
+            };
+            room_state.apply_left(room);
+        }
+
+        for event in &response.account_data.events {
+            if let Ok(Some(event_type)) = event.get_field::<String>("type") {
+                self.account_data.insert(event_type, event.clone());
+            }
+        }
+
+        self.to_device.extend(response.to_device.events.iter().cloned());
+        self.next_batch = Some(response.next_batch.clone());
+    }
+
+    /// The rooms the user has joined.
+    pub fn joined_rooms(&self) -> &BTreeMap<Box<RoomId>, RoomState> {
+        &self.joined
+    }
+
+    /// The joined room with the given ID, if any.
+    pub fn joined_room(&self, room_id: &RoomId) -> Option<&RoomState> {
+        self.joined.get(room_id)
+    }
+
+    /// The rooms the user has been invited to.
+    pub fn invited_rooms(&self) -> &BTreeMap<Box<RoomId>, InvitedRoomState> {
+        &self.invited
+    }
+
+    /// The room the user has been invited to with the given ID, if any.
+    pub fn invited_room(&self, room_id: &RoomId) -> Option<&InvitedRoomState> {
+        self.invited.get(room_id)
+    }
+
+    /// The rooms the user has left.
+    ///
+    /// Their state is the state at the time the user left.
+    pub fn left_rooms(&self) -> &BTreeMap<Box<RoomId>, RoomState> {
+        &self.left
+    }
+
+    /// The left room with the given ID, if any.
+    pub fn left_room(&self, room_id: &RoomId) -> Option<&RoomState> {
+        self.left.get(room_id)
+    }
+
+    /// The latest global account data event of the given type, if any.
+    pub fn account_data(&self, event_type: &str) -> Option<&Raw<AnyGlobalAccountDataEvent>> {
+        self.account_data.get(event_type)
+    }
+
+    /// The to-device events that were received since the last call to this method.
+    pub fn take_to_device_events(&mut self) -> Vec<Raw<AnyToDeviceEvent>> {
+        std::mem::take(&mut self.to_device)
+    }
+}
+
+/// The state of a joined or left room.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RoomState {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    state: BTreeMap<String, BTreeMap<String, Raw<AnySyncStateEvent>>>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    account_data: BTreeMap<String, Raw<AnyRoomAccountDataEvent>>,
+
+    #[serde(default)]
+    summary: RoomSummary,
+
+    #[serde(default)]
+    unread_notifications: UnreadNotificationsCount,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_batch: Option<String>,
+}
+
+impl RoomState {
+    fn apply_joined(&mut self, room: &JoinedRoom) {
+        self.apply_state(&room.state, &room.timeline);
+        self.apply_account_data(&room.account_data.events);
+
+        let summary = &room.summary;
+        if !summary.heroes.is_empty() {
+            self.summary.heroes = summary.heroes.clone();
+        }
+        if summary.joined_member_count.is_some() {
+            self.summary.joined_member_count = summary.joined_member_count;
+        }
+        if summary.invited_member_count.is_some() {
+            self.summary.invited_member_count = summary.invited_member_count;
+        }
+
+        let unread_notifications = &room.unread_notifications;
+        if unread_notifications.highlight_count.is_some() {
+            self.unread_notifications.highlight_count = unread_notifications.highlight_count;
+        }
+        if unread_notifications.notification_count.is_some() {
+            self.unread_notifications.notification_count = unread_notifications.notification_count;
+        }
+    }
+
+    fn apply_left(&mut self, room: &LeftRoom) {
+        self.apply_state(&room.state, &room.timeline);
+        self.apply_account_data(&room.account_data.events);
+        self.unread_notifications = UnreadNotificationsCount::new();
+    }
+
+    /// Applies the state events of the state and timeline of a room, in that order.
+    fn apply_state(&mut self, state: &State, timeline: &Timeline) {
+        let timeline_state =
+            timeline.events.iter().map(|event| Raw::from_json(event.json().to_owned()));
+        for event in state.events.iter().cloned().chain(timeline_state) {
+            let (event_type, state_key) =
+                match (event.get_field::<String>("type"), event.get_field::<String>("state_key")) {
+                    (Ok(Some(event_type)), Ok(Some(state_key))) => (event_type, state_key),
+                    _ => continue,
+                };
+
+            self.state.entry(event_type).or_default().insert(state_key, event);
+        }
+
+        if timeline.prev_batch.is_some() {
+            self.prev_batch = timeline.prev_batch.clone();
+        }
+    }
+
+    fn apply_account_data(&mut self, events: &[Raw<AnyRoomAccountDataEvent>]) {
+        for event in events {
+            if let Ok(Some(event_type)) = event.get_field::<String>("type") {
+                self.account_data.insert(event_type, event.clone());
+            }
+        }
+    }
+
+    /// The current state event with the given content type and state key, if any.
+    pub fn state_event<C>(&self, state_key: &str) -> Option<serde_json::Result<SyncStateEvent<C>>>
+    where
+        C: StateEventContent + StaticEventContent,
+        SyncStateEvent<C>: DeserializeOwned,
+    {
+        self.raw_state_event(C::TYPE, state_key).map(Raw::deserialize_as)
+    }
+
+    /// The current state events with the given content type, by state key.
+    ///
+    /// Events that fail to deserialize are skipped.
+    pub fn state_events<C>(&self) -> BTreeMap<&str, SyncStateEvent<C>>
+    where
+        C: StateEventContent + StaticEventContent,
+        SyncStateEvent<C>: DeserializeOwned,
+    {
+        self.state
+            .get(C::TYPE)
+            .into_iter()
+            .flatten()
+            .filter_map(|(state_key, event)| {
+                Some((state_key.as_str(), event.deserialize_as().ok()?))
+            })
+            .collect()
+    }
+
+    /// The current state event with the given type and state key, if any.
+    pub fn raw_state_event(
+        &self,
+        event_type: &str,
+        state_key: &str,
+    ) -> Option<&Raw<AnySyncStateEvent>> {
+        self.state.get(event_type)?.get(state_key)
+    }
+
+    /// The name of the room, if any.
+    pub fn name(&self) -> Option<Box<RoomName>> {
+        self.state_event::<RoomNameEventContent>("")?.ok()?.content.name
+    }
+
+    /// The topic of the room, if any.
+    pub fn topic(&self) -> Option<String> {
+        Some(self.state_event::<RoomTopicEventContent>("")?.ok()?.content.topic)
+    }
+
+    /// The membership event of the given user, if any.
+    pub fn member(&self, user_id: &UserId) -> Option<SyncStateEvent<RoomMemberEventContent>> {
+        self.state_event(user_id.as_str())?.ok()
+    }
+
+    /// The IDs of the users whose membership is `join`.
+    pub fn joined_members(&self) -> Vec<Box<UserId>> {
+        self.state_events::<RoomMemberEventContent>()
+            .into_values()
+            .filter(|event| event.content.membership == MembershipState::Join)
+            .filter_map(|event| UserId::parse(event.state_key).ok())
+            .collect()
+    }
+
+    /// The latest room account data event of the given type, if any.
+    pub fn account_data(&self, event_type: &str) -> Option<&Raw<AnyRoomAccountDataEvent>> {
+        self.account_data.get(event_type)
+    }
+
+    /// The summary of the room, as last updated by the homeserver.
+    pub fn summary(&self) -> &RoomSummary {
+        &self.summary
+    }
+
+    /// The counts of unread notifications in the room.
+    pub fn unread_notifications(&self) -> &UnreadNotificationsCount {
+        &self.unread_notifications
+    }
+
+    /// The `prev_batch` token of the latest timeline of the room, to paginate backwards from.
+    pub fn prev_batch(&self) -> Option<&str> {
+        self.prev_batch.as_deref()
+    }
+}
+
+/// The state of a room the user has been invited to.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct InvitedRoomState {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    state: BTreeMap<String, BTreeMap<String, Raw<AnyStrippedStateEvent>>>,
+}
+
+impl InvitedRoomState {
+    fn apply(&mut self, room: &InvitedRoom) {
+        for event in &room.invite_state.events {
+            let (event_type, state_key) =
+                match (event.get_field::<String>("type"), event.get_field::<String>("state_key")) {
+                    (Ok(Some(event_type)), Ok(Some(state_key))) => (event_type, state_key),
+                    _ => continue,
+                };
+
+            self.state.entry(event_type).or_default().insert(state_key, event.clone());
+        }
+    }
+
+    /// The stripped state event with the given content type and state key, if any.
+    pub fn state_event<C>(
+        &self,
+        state_key: &str,
+    ) -> Option<serde_json::Result<StrippedStateEvent<C>>>
+    where
+        C: StateEventContent + StaticEventContent,
+        StrippedStateEvent<C>: DeserializeOwned,
+    {
+        self.raw_state_event(C::TYPE, state_key).map(Raw::deserialize_as)
+    }
+
+    /// The stripped state event with the given type and state key, if any.
+    pub fn raw_state_event(
+        &self,
+        event_type: &str,
+        state_key: &str,
+    ) -> Option<&Raw<AnyStrippedStateEvent>> {
+        self.state.get(event_type)?.get(state_key)
+    }
+
+    /// The name of the room, if any.
+    pub fn name(&self) -> Option<Box<RoomName>> {
+        self.state_event::<RoomNameEventContent>("")?.ok()?.content.name
+    }
+
+    /// The membership event of the given user, if any.
+    ///
+    /// The membership event of the invited user contains the sender of the invite.
+    pub fn member(&self, user_id: &UserId) -> Option<StrippedStateEvent<RoomMemberEventContent>> {
+        self.state_event(user_id.as_str())?.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_api::IncomingResponse;
+    use ruma_client_api::r0::sync::sync_events;
+    use ruma_events::room::member::{MembershipState, RoomMemberEventContent};
+    use ruma_identifiers::{room_id, user_id};
+    use serde_json::{json, Value as JsonValue};
+
+    use super::SyncState;
+
+    fn response(body: JsonValue) -> sync_events::Response {
+        let http_response = http::Response::new(serde_json::to_vec(&body).unwrap());
+        sync_events::Response::try_from_http_response(http_response).unwrap()
+    }
+
+    fn state_event(event_type: &str, state_key: &str, content: JsonValue) -> JsonValue {
+        json!({
+            "type": event_type,
+            "state_key": state_key,
+            "content": content,
+            "event_id": format!("${}{}", event_type, state_key),
+            "sender": "@alice:example.org",
+            "origin_server_ts": 1,
+        })
+    }
+
+    #[test]
+    fn joined_room() {
+        let mut sync_state = SyncState::new();
+        sync_state.apply(&response(json!({
+            "next_batch": "s1",
+            "rooms": {
+                "join": {
+                    "!room:example.org": {
+                        "state": {
+                            "events": [
+                                state_event("m.room.name", "", json!({ "name": "Room" })),
+                                state_event(
+                                    "m.room.member",
+                                    "@alice:example.org",
+                                    json!({ "membership": "join" }),
+                                ),
+                            ],
+                        },
+                        "timeline": {
+                            "events": [
+                                state_event(
+                                    "m.room.member",
+                                    "@bob:example.org",
+                                    json!({ "membership": "join" }),
+                                ),
+                                {
+                                    "type": "m.room.message",
+                                    "content": { "msgtype": "m.text", "body": "Hi" },
+                                    "event_id": "$message",
+                                    "sender": "@bob:example.org",
+                                    "origin_server_ts": 2,
+                                },
+                            ],
+                            "prev_batch": "p1",
+                        },
+                        "unread_notifications": { "notification_count": 1 },
+                    },
+                },
+            },
+            "to_device": {
+                "events": [{
+                    "type": "m.dummy",
+                    "sender": "@bob:example.org",
+                    "content": {},
+                }],
+            },
+        })));
+
+        assert_eq!(sync_state.next_batch(), Some("s1"));
+        let room = sync_state.joined_room(room_id!("!room:example.org")).unwrap();
+        assert_eq!(room.name().unwrap().as_str(), "Room");
+        assert_eq!(room.topic(), None);
+        assert_eq!(
+            room.joined_members(),
+            vec![
+                user_id!("@alice:example.org").to_owned(),
+                user_id!("@bob:example.org").to_owned()
+            ]
+        );
+        assert_eq!(room.prev_batch(), Some("p1"));
+        assert_eq!(room.unread_notifications().notification_count, Some(1_u32.into()));
+        assert_eq!(sync_state.take_to_device_events().len(), 1);
+        assert!(sync_state.take_to_device_events().is_empty());
+
+        sync_state.apply(&response(json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": {
+                    "!room:example.org": {
+                        "timeline": {
+                            "events": [
+                                state_event(
+                                    "m.room.member",
+                                    "@bob:example.org",
+                                    json!({ "membership": "leave" }),
+                                ),
+                            ],
+                        },
+                    },
+                },
+            },
+        })));
+
+        let room = sync_state.joined_room(room_id!("!room:example.org")).unwrap();
+        assert_eq!(room.joined_members(), vec![user_id!("@alice:example.org").to_owned()]);
+        assert_eq!(
+            room.member(user_id!("@bob:example.org")).unwrap().content.membership,
+            MembershipState::Leave
+        );
+        assert_eq!(room.state_events::<RoomMemberEventContent>().len(), 2);
+        assert_eq!(room.unread_notifications().notification_count, Some(1_u32.into()));
+    }
+
+    #[test]
+    fn room_transitions() {
+        let mut sync_state = SyncState::new();
+        sync_state.apply(&response(json!({
+            "next_batch": "s1",
+            "rooms": {
+                "invite": {
+                    "!room:example.org": {
+                        "invite_state": {
+                            "events": [
+                                {
+                                    "type": "m.room.name",
+                                    "state_key": "",
+                                    "sender": "@bob:example.org",
+                                    "content": { "name": "Room" },
+                                },
+                            ],
+                        },
+                    },
+                },
+            },
+        })));
+
+        let room_id = room_id!("!room:example.org");
+        assert_eq!(sync_state.invited_room(room_id).unwrap().name().unwrap().as_str(), "Room");
+
+        sync_state.apply(&response(json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": {
+                    "!room:example.org": {
+                        "state": {
+                            "events": [state_event("m.room.topic", "", json!({ "topic": "Hi" }))],
+                        },
+                    },
+                },
+            },
+        })));
+
+        assert!(sync_state.invited_room(room_id).is_none());
+        assert_eq!(sync_state.joined_room(room_id).unwrap().topic().as_deref(), Some("Hi"));
+
+        sync_state.apply(&response(json!({
+            "next_batch": "s3",
+            "rooms": { "leave": { "!room:example.org": {} } },
+        })));
+
+        assert!(sync_state.joined_room(room_id).is_none());
+        assert_eq!(sync_state.left_room(room_id).unwrap().topic().as_deref(), Some("Hi"));
+
+        let json = serde_json::to_string(&sync_state).unwrap();
+        let restored: SyncState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.next_batch(), Some("s3"));
+        assert_eq!(restored.left_room(room_id).unwrap().topic().as_deref(), Some("Hi"));
+    }
+}
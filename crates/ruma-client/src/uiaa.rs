@@ -0,0 +1,489 @@
+//! Automatic handling of the [User-Interactive Authentication API][uiaa].
+//!
+//! [uiaa]: https://matrix.org/docs/spec/client_server/r0.6.1#user-interactive-authentication-api
+
+use std::fmt::{self, Display, Formatter};
+
+use assign::assign;
+use async_trait::async_trait;
+use bytes::BufMut;
+use ruma_api::{
+    error::{FromHttpResponseError, ServerError},
+    OutgoingRequest,
+};
+use ruma_client_api::{
+    error::ErrorBody,
+    r0::uiaa::{
+        AuthData, AuthFlow, AuthType, Dummy, Password, Token, UiaaInfo, UiaaResponse,
+        UserIdentifier,
+    },
+};
+use ruma_serde::JsonObject;
+use serde_json::Value as JsonValue;
+
+use super::{Client, Error, HttpClient};
+
+/// A handler that provides the authentication data for the stages of a User-Interactive
+/// Authentication flow, used by [`Client::send_with_uiaa`].
+#[async_trait]
+pub trait UiaaHandler: Send {
+    /// Selects the flow to complete among the flows offered by the homeserver, by returning its
+    /// index.
+    ///
+    /// Returning `None` aborts the authentication. Defaults to the first flow.
+    fn select_flow(&mut self, flows: &[AuthFlow]) -> Option<usize> {
+        if flows.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+
+    /// Provides the authentication data for the given stage of the selected flow.
+    ///
+    /// `m.login.dummy` stages are completed without calling this method. Returning `None` aborts
+    /// the authentication with [`UiaaError::UnsupportedStage`].
+    async fn authenticate(&mut self, stage: &AuthType, info: &UiaaInfo) -> Option<StageAuth>;
+
+    /// Called with the list of completed stages every time the homeserver responds with the
+    /// status of the authentication.
+    fn stages_completed(&mut self, _completed: &[AuthType]) {}
+}
+
+/// The authentication data for one stage of a User-Interactive Authentication flow.
+///
+/// The session ID is added by [`Client::send_with_uiaa`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum StageAuth {
+    /// Password-based authentication (`m.login.password`).
+    Password {
+        /// The fully qualified user ID or just the localpart of the user.
+        user: String,
+
+        /// The plaintext password.
+        password: String,
+    },
+
+    /// Token-based authentication (`m.login.token`).
+    Token {
+        /// The login token.
+        token: String,
+
+        /// The transaction ID.
+        txn_id: String,
+    },
+
+    /// Dummy authentication (`m.login.dummy`).
+    Dummy,
+
+    /// Acknowledgement that the stage was completed with the [fallback] web page.
+    ///
+    /// [fallback]: https://matrix.org/docs/spec/client_server/r0.6.1#fallback
+    FallbackAcknowledgement,
+}
+
+impl StageAuth {
+    /// Converts this stage to the `AuthData` sent to the homeserver.
+    ///
+    /// Returns `None` for a fallback acknowledgement without a session ID.
+    fn to_auth_data<'a>(&'a self, session: Option<&'a str>) -> Option<AuthData<'a>> {
+        let auth_data = match self {
+            Self::Password { user, password } => AuthData::Password(assign!(
+                Password::new(UserIdentifier::MatrixId(user), password),
+                { session }
+            )),
+            Self::Token { token, txn_id } => {
+                AuthData::Token(assign!(Token::new(token, txn_id), { session }))
+            }
+            Self::Dummy => AuthData::Dummy(assign!(Dummy::new(), { session })),
+            Self::FallbackAcknowledgement => AuthData::fallback_acknowledgement(session?),
+        };
+
+        Some(auth_data)
+    }
+}
+
+/// The error type of [`Client::send_with_uiaa`].
+#[derive(Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum UiaaError<E> {
+    /// Sending the request failed, or the homeserver responded with an error that is not part of
+    /// the User-Interactive Authentication.
+    Request(Box<Error<E, UiaaResponse>>),
+
+    /// The handler didn't select any of the flows offered by the homeserver.
+    NoFlowSelected(Box<UiaaInfo>),
+
+    /// The handler didn't provide authentication data for a stage.
+    UnsupportedStage {
+        /// The stage that couldn't be completed.
+        stage: AuthType,
+
+        /// The status of the authentication.
+        info: Box<UiaaInfo>,
+    },
+
+    /// The homeserver didn't provide a session ID, which is required for the stage.
+    MissingSession {
+        /// The stage that couldn't be completed.
+        stage: AuthType,
+
+        /// The status of the authentication.
+        info: Box<UiaaInfo>,
+    },
+
+    /// The homeserver rejected the authentication data of a stage.
+    StageFailed {
+        /// The stage that failed.
+        stage: AuthType,
+
+        /// The error returned by the homeserver.
+        error: Box<ErrorBody>,
+
+        /// The status of the authentication.
+        info: Box<UiaaInfo>,
+    },
+
+    /// The homeserver neither accepted nor rejected the authentication data of a stage: it
+    /// responded without an error, but the stage is not completed.
+    StageNotCompleted {
+        /// The stage that was not completed.
+        stage: AuthType,
+
+        /// The status of the authentication.
+        info: Box<UiaaInfo>,
+    },
+
+    /// All the stages of the selected flow were completed, but the homeserver still requires
+    /// authentication.
+    Incomplete(Box<UiaaInfo>),
+}
+
+impl<E: Display> Display for UiaaError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(err) => write!(f, "{}", err),
+            Self::NoFlowSelected(_) => write!(f, "No authentication flow was selected."),
+            Self::UnsupportedStage { stage, .. } => {
+                write!(f, "The authentication stage {} is not supported.", stage)
+            }
+            Self::MissingSession { stage, .. } => {
+                write!(f, "The authentication stage {} requires a session ID.", stage)
+            }
+            Self::StageFailed { stage, error, .. } => {
+                write!(f, "The authentication stage {} failed: {}", stage, error.message)
+            }
+            Self::StageNotCompleted { stage, .. } => {
+                write!(f, "The authentication stage {} was not completed.", stage)
+            }
+            Self::Incomplete(_) => {
+                write!(f, "The authentication flow was completed without success.")
+            }
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for UiaaError<E> {}
+
+impl<E> From<Error<E, UiaaResponse>> for UiaaError<E> {
+    fn from(err: Error<E, UiaaResponse>) -> Self {
+        Self::Request(Box::new(err))
+    }
+}
+
+/// User-Interactive Authentication functionality of `Client`.
+impl<C> Client<C>
+where
+    C: HttpClient,
+    C::RequestBody: AsRef<[u8]>,
+{
+    /// Makes a request to a Matrix API endpoint that uses User-Interactive Authentication,
+    /// completing the authentication with `handler`.
+    ///
+    /// The request is first sent as is. As long as the homeserver responds with the status of the
+    /// authentication, the next stage of the flow chosen by [`UiaaHandler::select_flow`] that
+    /// isn't completed yet is completed with the data from [`UiaaHandler::authenticate`], and the
+    /// request is sent again with that data and the session ID in its `auth` field.
+    pub async fn send_with_uiaa<R, H>(
+        &self,
+        request: R,
+        handler: &mut H,
+    ) -> Result<R::IncomingResponse, UiaaError<C::Error>>
+    where
+        R: OutgoingRequest<EndpointError = UiaaResponse> + Clone,
+        H: UiaaHandler + ?Sized,
+    {
+        let mut flow: Option<AuthFlow> = None;
+        let mut auth: Option<(AuthType, JsonValue)> = None;
+
+        loop {
+            let result = match &auth {
+                None => self.send_request(request.clone()).await,
+                Some((_, auth)) => {
+                    self.send_customized_request(request.clone(), |http_request| {
+                        set_auth(http_request, auth).map_err(|err| Error::IntoHttp(err.into()))
+                    })
+                    .await
+                }
+            };
+
+            let info = match result {
+                Ok(response) => return Ok(response),
+                Err(Error::FromHttpResponse(FromHttpResponseError::Http(ServerError::Known(
+                    UiaaResponse::AuthResponse(info),
+                )))) => info,
+                Err(err) => return Err(err.into()),
+            };
+
+            handler.stages_completed(&info.completed);
+
+            // The stage that was just submitted must have either failed or been completed,
+            // otherwise it would be submitted again and again.
+            if let Some((stage, _)) = auth.take() {
+                if let Some(error) = &info.auth_error {
+                    let error = Box::new(error.clone());
+                    return Err(UiaaError::StageFailed { stage, error, info: Box::new(info) });
+                }
+
+                if !info.completed.contains(&stage) {
+                    return Err(UiaaError::StageNotCompleted { stage, info: Box::new(info) });
+                }
+            }
+
+            let flow = match &flow {
+                Some(flow) => flow,
+                None => match handler.select_flow(&info.flows).and_then(|i| info.flows.get(i)) {
+                    Some(selected) => flow.insert(selected.clone()),
+                    None => return Err(UiaaError::NoFlowSelected(Box::new(info))),
+                },
+            };
+
+            let stage = match flow.stages.iter().find(|stage| !info.completed.contains(stage)) {
+                Some(stage) => stage.clone(),
+                None => return Err(UiaaError::Incomplete(Box::new(info))),
+            };
+
+            let stage_auth = match stage {
+                AuthType::Dummy => StageAuth::Dummy,
+                _ => match handler.authenticate(&stage, &info).await {
+                    Some(stage_auth) => stage_auth,
+                    None => {
+                        return Err(UiaaError::UnsupportedStage { stage, info: Box::new(info) })
+                    }
+                },
+            };
+
+            let auth_data = match stage_auth.to_auth_data(info.session.as_deref()) {
+                Some(auth_data) => serde_json::to_value(auth_data)
+                    .map_err(|err| UiaaError::from(Error::IntoHttp(err.into())))?,
+                None => return Err(UiaaError::MissingSession { stage, info: Box::new(info) }),
+            };
+
+            auth = Some((stage, auth_data));
+        }
+    }
+}
+
+/// Sets the `auth` field of the JSON body of `http_request`.
+fn set_auth<B>(http_request: &mut http::Request<B>, auth: &JsonValue) -> serde_json::Result<()>
+where
+    B: AsRef<[u8]> + BufMut + Default,
+{
+    let body = http_request.body().as_ref();
+    let mut object: JsonObject =
+        if body.is_empty() { JsonObject::new() } else { serde_json::from_slice(body)? };
+    object.insert("auth".to_owned(), auth.clone());
+
+    let mut body = B::default();
+    serde_json::to_writer((&mut body).writer(), &object)?;
+    *http_request.body_mut() = body;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use http::StatusCode;
+    use ruma_client_api::r0::{
+        device::delete_devices,
+        uiaa::{AuthType, UiaaInfo},
+    };
+    use serde_json::{from_slice as from_json_slice, json, Value as JsonValue};
+
+    use super::{set_auth, StageAuth, UiaaError, UiaaHandler};
+    use crate::{http_client::stub::StubServer, Client};
+
+    fn client(responses: Vec<(StatusCode, JsonValue)>) -> Client<StubServer> {
+        let server = responses.into_iter().fold(StubServer::new(), |server, (status, body)| {
+            server.respond(status, serde_json::to_vec(&body).unwrap())
+        });
+        Client::with_http_client(server, "https://example.org".to_owned(), Some("token".to_owned()))
+    }
+
+    fn request_bodies(client: &Client<StubServer>) -> Vec<JsonValue> {
+        let requests = client.0.http_client.requests();
+        requests.iter().map(|request| from_json_slice(request.body()).unwrap()).collect()
+    }
+
+    /// A handler that authenticates with a password, and records its calls.
+    #[derive(Default)]
+    struct PasswordHandler {
+        calls: Vec<String>,
+    }
+
+    #[async_trait]
+    impl UiaaHandler for PasswordHandler {
+        async fn authenticate(&mut self, stage: &AuthType, _info: &UiaaInfo) -> Option<StageAuth> {
+            self.calls.push(format!("authenticate {}", stage));
+            match stage {
+                AuthType::Password => Some(StageAuth::Password {
+                    user: "alice".to_owned(),
+                    password: "hunter2".to_owned(),
+                }),
+                _ => None,
+            }
+        }
+
+        fn stages_completed(&mut self, completed: &[AuthType]) {
+            let completed: Vec<_> = completed.iter().map(ToString::to_string).collect();
+            self.calls.push(format!("completed [{}]", completed.join(", ")));
+        }
+    }
+
+    fn uiaa_info(completed: &[&str]) -> JsonValue {
+        json!({
+            "flows": [{ "stages": ["m.login.password", "m.login.dummy"] }],
+            "params": {},
+            "session": "xyz",
+            "completed": completed,
+        })
+    }
+
+    #[tokio::test]
+    async fn complete_flow() {
+        let client = client(vec![
+            (StatusCode::UNAUTHORIZED, uiaa_info(&[])),
+            (StatusCode::UNAUTHORIZED, uiaa_info(&["m.login.password"])),
+            (StatusCode::OK, json!({})),
+        ]);
+        let mut handler = PasswordHandler::default();
+
+        let devices = ["ABCDEF".into()];
+        client.send_with_uiaa(delete_devices::Request::new(&devices), &mut handler).await.unwrap();
+
+        assert_eq!(
+            handler.calls,
+            ["completed []", "authenticate m.login.password", "completed [m.login.password]"]
+        );
+        assert_eq!(
+            request_bodies(&client),
+            [
+                json!({ "devices": ["ABCDEF"] }),
+                json!({
+                    "devices": ["ABCDEF"],
+                    "auth": {
+                        "type": "m.login.password",
+                        "identifier": { "type": "m.id.user", "user": "alice" },
+                        "password": "hunter2",
+                        "session": "xyz",
+                    },
+                }),
+                json!({
+                    "devices": ["ABCDEF"],
+                    "auth": { "type": "m.login.dummy", "session": "xyz" },
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stage_failed() {
+        let mut failed = uiaa_info(&[]);
+        failed["errcode"] = json!("M_FORBIDDEN");
+        failed["error"] = json!("Invalid password");
+        let client = client(vec![
+            (StatusCode::UNAUTHORIZED, uiaa_info(&[])),
+            (StatusCode::UNAUTHORIZED, failed),
+        ]);
+
+        let devices = ["ABCDEF".into()];
+        let error = client
+            .send_with_uiaa(delete_devices::Request::new(&devices), &mut PasswordHandler::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            UiaaError::StageFailed { stage: AuthType::Password, error, .. }
+                if error.message == "Invalid password"
+        ));
+        assert_eq!(request_bodies(&client).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stage_not_completed() {
+        // The homeserver keeps asking for the dummy stage without completing it
+        let info =
+            json!({ "flows": [{ "stages": ["m.login.dummy"] }], "params": {}, "session": "xyz" });
+        let client = client(vec![
+            (StatusCode::UNAUTHORIZED, info.clone()),
+            (StatusCode::UNAUTHORIZED, info.clone()),
+            (StatusCode::UNAUTHORIZED, info),
+        ]);
+        let mut handler = PasswordHandler::default();
+
+        let devices = ["ABCDEF".into()];
+        let error = client
+            .send_with_uiaa(delete_devices::Request::new(&devices), &mut handler)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, UiaaError::StageNotCompleted { stage: AuthType::Dummy, .. }));
+        assert_eq!(request_bodies(&client).len(), 2);
+        assert_eq!(handler.calls, ["completed []", "completed []"]);
+    }
+
+    #[test]
+    fn stage_auth_data() {
+        let stage =
+            StageAuth::Password { user: "alice".to_owned(), password: "hunter2".to_owned() };
+        assert_eq!(
+            serde_json::to_value(stage.to_auth_data(Some("abcdef")).unwrap()).unwrap(),
+            json!({
+                "type": "m.login.password",
+                "identifier": { "type": "m.id.user", "user": "alice" },
+                "password": "hunter2",
+                "session": "abcdef",
+            })
+        );
+
+        assert!(StageAuth::FallbackAcknowledgement.to_auth_data(None).is_none());
+        assert_eq!(
+            serde_json::to_value(StageAuth::Dummy.to_auth_data(Some("abcdef")).unwrap()).unwrap(),
+            json!({ "type": "m.login.dummy", "session": "abcdef" })
+        );
+    }
+
+    #[test]
+    fn set_auth_in_body() {
+        let mut http_request = http::Request::new(br#"{"devices":["ABCDEF"]}"#.to_vec());
+        set_auth(&mut http_request, &json!({ "type": "m.login.dummy", "session": "xyz" })).unwrap();
+
+        assert_eq!(
+            from_json_slice::<JsonValue>(http_request.body()).unwrap(),
+            json!({
+                "devices": ["ABCDEF"],
+                "auth": { "type": "m.login.dummy", "session": "xyz" },
+            })
+        );
+
+        let mut http_request = http::Request::new(Vec::new());
+        set_auth(&mut http_request, &json!({ "type": "m.login.dummy" })).unwrap();
+        assert_eq!(
+            from_json_slice::<JsonValue>(http_request.body()).unwrap(),
+            json!({ "auth": { "type": "m.login.dummy" } })
+        );
+    }
+}
@@ -15,5 +15,9 @@ pub mod receipt;
 pub mod thirdparty;
 mod time;
 pub mod to_device;
+mod version;
 
-pub use time::{MilliSecondsSinceUnixEpoch, SecondsSinceUnixEpoch};
+pub use self::{
+    time::{MilliSecondsSinceUnixEpoch, SecondsSinceUnixEpoch},
+    version::{MatrixVersion, UnknownVersionError},
+};
@@ -0,0 +1,163 @@
+//! Matrix specification versions.
+
+use std::{borrow::Cow, convert::TryFrom, error::Error, fmt, str::FromStr};
+
+use serde::{
+    de::{self, Deserializer},
+    Deserialize, Serialize, Serializer,
+};
+
+/// A version of the Matrix specification.
+///
+/// Versions are ordered by release, so they can be compared to check whether a homeserver supports
+/// a feature that was introduced in a given version.
+///
+/// Before v1.1, each API of the specification had its own version, of which only the versions of
+/// the client-server API are included here. Endpoints and events of the other APIs that predate
+/// v1.1 are considered to be introduced in v1.1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum MatrixVersion {
+    /// Version r0.0.0 of the client-server API.
+    R0_0_0,
+
+    /// Version r0.0.1 of the client-server API.
+    R0_0_1,
+
+    /// Version r0.1.0 of the client-server API.
+    R0_1_0,
+
+    /// Version r0.2.0 of the client-server API.
+    R0_2_0,
+
+    /// Version r0.3.0 of the client-server API.
+    R0_3_0,
+
+    /// Version r0.4.0 of the client-server API.
+    R0_4_0,
+
+    /// Version r0.5.0 of the client-server API.
+    R0_5_0,
+
+    /// Version r0.6.0 of the client-server API.
+    R0_6_0,
+
+    /// Version r0.6.1 of the client-server API.
+    R0_6_1,
+
+    /// Version 1.1 of the Matrix specification.
+    V1_1,
+
+    /// Version 1.2 of the Matrix specification.
+    V1_2,
+}
+
+impl MatrixVersion {
+    /// The string representation of this version, as used in the `versions` of
+    /// `GET /_matrix/client/versions`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::R0_0_0 => "r0.0.0",
+            Self::R0_0_1 => "r0.0.1",
+            Self::R0_1_0 => "r0.1.0",
+            Self::R0_2_0 => "r0.2.0",
+            Self::R0_3_0 => "r0.3.0",
+            Self::R0_4_0 => "r0.4.0",
+            Self::R0_5_0 => "r0.5.0",
+            Self::R0_6_0 => "r0.6.0",
+            Self::R0_6_1 => "r0.6.1",
+            Self::V1_1 => "v1.1",
+            Self::V1_2 => "v1.2",
+        }
+    }
+}
+
+impl fmt::Display for MatrixVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MatrixVersion {
+    type Err = UnknownVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "r0.0.0" => Self::R0_0_0,
+            "r0.0.1" => Self::R0_0_1,
+            "r0.1.0" => Self::R0_1_0,
+            "r0.2.0" => Self::R0_2_0,
+            "r0.3.0" => Self::R0_3_0,
+            "r0.4.0" => Self::R0_4_0,
+            "r0.5.0" => Self::R0_5_0,
+            "r0.6.0" => Self::R0_6_0,
+            "r0.6.1" => Self::R0_6_1,
+            "v1.1" => Self::V1_1,
+            "v1.2" => Self::V1_2,
+            _ => return Err(UnknownVersionError(s.to_owned())),
+        })
+    }
+}
+
+impl TryFrom<&str> for MatrixVersion {
+    type Error = UnknownVersionError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Serialize for MatrixVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MatrixVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s: Cow<'_, str> = ruma_serde::deserialize_cow_str(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// An error when parsing a [`MatrixVersion`] from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::exhaustive_structs)]
+pub struct UnknownVersionError(pub String);
+
+impl fmt::Display for UnknownVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown Matrix version `{}`", self.0)
+    }
+}
+
+impl Error for UnknownVersionError {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::MatrixVersion;
+
+    #[test]
+    fn parse() {
+        assert_eq!("r0.6.1".parse::<MatrixVersion>().unwrap(), MatrixVersion::R0_6_1);
+        assert_eq!("v1.1".parse::<MatrixVersion>().unwrap(), MatrixVersion::V1_1);
+        assert_eq!(MatrixVersion::V1_2.to_string(), "v1.2");
+        assert!("v0.1".parse::<MatrixVersion>().is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(MatrixVersion::R0_0_0 < MatrixVersion::R0_0_1);
+        assert!(MatrixVersion::R0_6_1 < MatrixVersion::V1_1);
+        assert!(MatrixVersion::V1_1 < MatrixVersion::V1_2);
+    }
+
+    #[test]
+    fn serde() {
+        assert_eq!(to_json_value(MatrixVersion::R0_5_0).unwrap(), json!("r0.5.0"));
+        assert_eq!(from_json_value::<MatrixVersion>(json!("v1.1")).unwrap(), MatrixVersion::V1_1);
+        assert!(from_json_value::<MatrixVersion>(json!("v9.9")).is_err());
+    }
+}
@@ -18,6 +18,8 @@ mod kw {
     syn::custom_keyword!(custom_redacted);
     // The kind of event content this is.
     syn::custom_keyword!(kind);
+    // The Matrix version that introduced this event type.
+    syn::custom_keyword!(added);
 }
 
 /// Parses attributes for `*EventContent` derives.
@@ -29,6 +31,9 @@ enum EventMeta {
 
     Kind(EventKind),
 
+    /// The Matrix version that introduced the event type, e.g. `V1_1`.
+    Added(Ident),
+
     /// Fields marked with `#[ruma_event(skip_redaction)]` are kept when the event is
     /// redacted.
     SkipRedacted,
@@ -52,6 +57,13 @@ impl EventMeta {
             _ => None,
         }
     }
+
+    fn get_added(&self) -> Option<&Ident> {
+        match self {
+            Self::Added(v) => Some(v),
+            _ => None,
+        }
+    }
 }
 
 impl Parse for EventMeta {
@@ -65,6 +77,10 @@ impl Parse for EventMeta {
             let _: kw::kind = input.parse()?;
             let _: Token![=] = input.parse()?;
             EventKind::parse(input).map(EventMeta::Kind)
+        } else if lookahead.peek(kw::added) {
+            let _: kw::added = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            input.parse().map(EventMeta::Added)
         } else if lookahead.peek(kw::skip_redaction) {
             let _: kw::skip_redaction = input.parse()?;
             Ok(EventMeta::SkipRedacted)
@@ -91,6 +107,10 @@ impl MetaAttrs {
     fn get_event_kind(&self) -> Option<EventKind> {
         self.0.iter().find_map(|a| a.get_event_kind())
     }
+
+    fn get_added(&self) -> Option<&Ident> {
+        self.0.iter().find_map(|a| a.get_added())
+    }
 }
 
 impl Parse for MetaAttrs {
@@ -145,14 +165,27 @@ pub fn expand_event_content(
         }
     };
 
+    let mut added_versions: Vec<_> =
+        content_attr.iter().filter_map(|attrs| attrs.get_added()).collect();
+    let added = match added_versions.as_slice() {
+        [] => None,
+        [_] => Some(added_versions.pop().unwrap()),
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "multiple added attribute found, there can only be one",
+            ));
+        }
+    };
+
     // We only generate redacted content structs for state and message events
     let redacted_event_content = needs_redacted(&content_attr, event_kind)
-        .then(|| generate_redacted_event_content(input, event_type, ruma_events, event_kind))
+        .then(|| generate_redacted_event_content(input, event_type, added, ruma_events, event_kind))
         .transpose()?;
 
     let event_content_impl = generate_event_content_impl(&input.ident, event_type, ruma_events);
     let static_event_content_impl = event_kind.map(|k| {
-        generate_static_event_content_impl(&input.ident, k, false, event_type, ruma_events)
+        generate_static_event_content_impl(&input.ident, k, false, event_type, added, ruma_events)
     });
     let marker_trait_impl =
         event_kind.map(|k| generate_marker_trait_impl(k, &input.ident, ruma_events)).transpose()?;
@@ -172,6 +205,7 @@ pub fn expand_event_content(
 fn generate_redacted_event_content(
     input: &DeriveInput,
     event_type: &LitStr,
+    added: Option<&Ident>,
     ruma_events: &TokenStream,
     event_kind: Option<EventKind>,
 ) -> Result<TokenStream, syn::Error> {
@@ -274,7 +308,7 @@ fn generate_redacted_event_content(
     };
 
     let static_event_content_impl = event_kind.map(|k| {
-        generate_static_event_content_impl(&redacted_ident, k, true, event_type, ruma_events)
+        generate_static_event_content_impl(&redacted_ident, k, true, event_type, added, ruma_events)
     });
 
     Ok(quote! {
@@ -445,8 +479,11 @@ fn generate_static_event_content_impl(
     event_kind: EventKind,
     redacted: bool,
     event_type: &LitStr,
+    added: Option<&Ident>,
     ruma_events: &TokenStream,
 ) -> TokenStream {
+    let ruma_common = quote! { #ruma_events::exports::ruma_common };
+
     let event_kind = match event_kind {
         EventKind::GlobalAccountData => quote! { GlobalAccountData },
         EventKind::RoomAccountData => quote! { RoomAccountData },
@@ -459,10 +496,18 @@ fn generate_static_event_content_impl(
         }
     };
 
+    let added = match added {
+        Some(version) => {
+            quote! { ::std::option::Option::Some(#ruma_common::MatrixVersion::#version) }
+        }
+        None => quote! { ::std::option::Option::None },
+    };
+
     quote! {
         impl #ruma_events::StaticEventContent for #ident {
             const KIND: #ruma_events::EventKind = #ruma_events::EventKind::#event_kind;
             const TYPE: &'static ::std::primitive::str = #event_type;
+            const ADDED: ::std::option::Option<#ruma_common::MatrixVersion> = #added;
         }
     }
 }
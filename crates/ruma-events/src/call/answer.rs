@@ -11,7 +11,7 @@ use super::SessionDescription;
 /// This event is sent by the callee when they wish to answer the call.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.call.answer", kind = Message)]
+#[ruma_event(type = "m.call.answer", kind = Message, added = R0_0_0)]
 pub struct CallAnswerEventContent {
     /// The VoIP session description object.
     ///
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 /// call has has been established or before to abort the call.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.call.hangup", kind = Message)]
+#[ruma_event(type = "m.call.hangup", kind = Message, added = R0_0_0)]
 pub struct CallHangupEventContent {
     /// The ID of the call this event relates to.
     pub call_id: String,
@@ -11,7 +11,7 @@ use super::SessionDescription;
 /// This event is sent by the caller when they wish to establish a call.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.call.invite", kind = Message)]
+#[ruma_event(type = "m.call.invite", kind = Message, added = R0_0_0)]
 pub struct CallInviteEventContent {
     /// A unique identifier for the call.
     pub call_id: String,
@@ -17,7 +17,7 @@ use serde::{Deserialize, Serialize};
 /// Informs the client about the rooms that are considered direct by a user.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.direct", kind = GlobalAccountData)]
+#[ruma_event(type = "m.direct", kind = GlobalAccountData, added = R0_3_0)]
 pub struct DirectEventContent(pub BTreeMap<Box<UserId>, Vec<Box<RoomId>>>);
 
 impl Deref for DirectEventContent {
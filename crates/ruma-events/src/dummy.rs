@@ -21,7 +21,7 @@ use serde::{
 /// client receiving keys over the newly established session.
 #[derive(Clone, Debug, Default, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.dummy", kind = ToDevice)]
+#[ruma_event(type = "m.dummy", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceDummyEventContent;
 
 impl ToDeviceDummyEventContent {
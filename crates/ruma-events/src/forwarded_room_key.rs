@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 /// and convert it via `ToDeviceForwardedRoomKeyEventContent::from` / `.into()`.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.forwarded_room_key", kind = ToDevice)]
+#[ruma_event(type = "m.forwarded_room_key", kind = ToDevice, added = R0_4_0)]
 pub struct ToDeviceForwardedRoomKeyEventContent {
     /// The encryption algorithm the key in this event is to be used with.
     pub algorithm: EventEncryptionAlgorithm,
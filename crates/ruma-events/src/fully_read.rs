@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 /// This event appears in the user's room account data for the room the marker is applicable for.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.fully_read", kind = RoomAccountData)]
+#[ruma_event(type = "m.fully_read", kind = RoomAccountData, added = R0_3_0)]
 pub struct FullyReadEventContent {
     /// The event the user's read marker is located at in the room.
     pub event_id: Box<EventId>,
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// A list of users to ignore.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.ignored_user_list", kind = GlobalAccountData)]
+#[ruma_event(type = "m.ignored_user_list", kind = GlobalAccountData, added = R0_3_0)]
 pub struct IgnoredUserListEventContent {
     /// A list of users to ignore.
     #[serde(with = "ruma_serde::vec_as_map_of_empty")]
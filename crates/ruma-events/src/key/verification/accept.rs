@@ -17,7 +17,7 @@ use super::{
 /// Accepts a previously sent `m.key.verification.start` message.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.accept", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.accept", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceKeyVerificationAcceptEventContent {
     /// An opaque identifier for the verification process.
     ///
@@ -41,7 +41,7 @@ impl ToDeviceKeyVerificationAcceptEventContent {
 ///
 /// Accepts a previously sent `m.key.verification.start` message.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
-#[ruma_event(type = "m.key.verification.accept", kind = Message)]
+#[ruma_event(type = "m.key.verification.accept", kind = Message, added = V1_1)]
 #[cfg(feature = "unstable-pre-spec")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct KeyVerificationAcceptEventContent {
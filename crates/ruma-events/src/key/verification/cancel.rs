@@ -12,7 +12,7 @@ use super::Relation;
 /// Cancels a key verification process/request.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.cancel", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.cancel", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceKeyVerificationCancelEventContent {
     /// The opaque identifier for the verification process/request.
     pub transaction_id: String,
@@ -40,7 +40,7 @@ impl ToDeviceKeyVerificationCancelEventContent {
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg(feature = "unstable-pre-spec")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.cancel", kind = Message)]
+#[ruma_event(type = "m.key.verification.cancel", kind = Message, added = V1_1)]
 pub struct KeyVerificationCancelEventContent {
     /// A human readable description of the `code`.
     ///
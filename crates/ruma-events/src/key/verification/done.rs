@@ -10,7 +10,7 @@ use super::Relation;
 /// Event signaling that the interactive key verification has successfully concluded.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.done", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.done", kind = ToDevice, added = V1_1)]
 pub struct ToDeviceKeyVerificationDoneEventContent {
     /// An opaque identifier for the verification process.
     ///
@@ -30,7 +30,7 @@ impl ToDeviceKeyVerificationDoneEventContent {
 /// Event signaling that the interactive key verification has successfully concluded.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.done", kind = Message)]
+#[ruma_event(type = "m.key.verification.done", kind = Message, added = V1_1)]
 pub struct KeyVerificationDoneEventContent {
     /// Relation signaling which verification request this event is responding to.
     #[serde(rename = "m.relates_to")]
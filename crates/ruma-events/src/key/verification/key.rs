@@ -11,7 +11,7 @@ use super::Relation;
 /// Sends the ephemeral public key for a device to the partner device.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.key", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.key", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceKeyVerificationKeyEventContent {
     /// An opaque identifier for the verification process.
     ///
@@ -36,7 +36,7 @@ impl ToDeviceKeyVerificationKeyEventContent {
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg(feature = "unstable-pre-spec")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.key", kind = Message)]
+#[ruma_event(type = "m.key.verification.key", kind = Message, added = V1_1)]
 pub struct KeyVerificationKeyEventContent {
     /// The device's ephemeral public key, encoded as unpadded Base64.
     pub key: String,
@@ -13,7 +13,7 @@ use super::Relation;
 /// Sends the MAC of a device's key to the partner device.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.mac", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.mac", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceKeyVerificationMacEventContent {
     /// An opaque identifier for the verification process.
     ///
@@ -44,7 +44,7 @@ impl ToDeviceKeyVerificationMacEventContent {
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg(feature = "unstable-pre-spec")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.mac", kind = Message)]
+#[ruma_event(type = "m.key.verification.mac", kind = Message, added = V1_1)]
 pub struct KeyVerificationMacEventContent {
     /// A map of the key ID to the MAC of the key, using the algorithm in the verification process.
     ///
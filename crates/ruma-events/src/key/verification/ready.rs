@@ -11,7 +11,7 @@ use super::{Relation, VerificationMethod};
 /// Response to a previously sent `m.key.verification.request` message.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.ready", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.ready", kind = ToDevice, added = V1_1)]
 pub struct ToDeviceKeyVerificationReadyEventContent {
     /// The device ID which is initiating the request.
     pub from_device: Box<DeviceId>,
@@ -44,7 +44,7 @@ impl ToDeviceKeyVerificationReadyEventContent {
 /// Response to a previously sent `m.key.verification.request` message.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.ready", kind = Message)]
+#[ruma_event(type = "m.key.verification.ready", kind = Message, added = V1_1)]
 pub struct KeyVerificationReadyEventContent {
     /// The device ID which is initiating the request.
     pub from_device: Box<DeviceId>,
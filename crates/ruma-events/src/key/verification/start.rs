@@ -18,7 +18,7 @@ use super::{
 /// Begins an SAS key verification process.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.start", kind = ToDevice)]
+#[ruma_event(type = "m.key.verification.start", kind = ToDevice, added = R0_5_0)]
 pub struct ToDeviceKeyVerificationStartEventContent {
     /// The device ID which is initiating the process.
     pub from_device: Box<DeviceId>,
@@ -49,7 +49,7 @@ impl ToDeviceKeyVerificationStartEventContent {
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg(feature = "unstable-pre-spec")]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.key.verification.start", kind = Message)]
+#[ruma_event(type = "m.key.verification.start", kind = Message, added = V1_1)]
 pub struct KeyVerificationStartEventContent {
     /// The device ID which is initiating the process.
     pub from_device: Box<DeviceId>,
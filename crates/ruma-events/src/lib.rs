@@ -117,6 +117,7 @@
 
 use std::fmt::Debug;
 
+use ruma_common::MatrixVersion;
 use ruma_identifiers::{EventEncryptionAlgorithm, RoomVersionId};
 use ruma_serde::Raw;
 use serde::{
@@ -304,6 +305,12 @@ pub trait StaticEventContent: EventContent {
 
     /// The event type.
     const TYPE: &'static str;
+
+    /// The Matrix version that introduced this event type.
+    ///
+    /// `None` if the event type is not part of a stable Matrix version yet, or if its version is
+    /// not known.
+    const ADDED: Option<MatrixVersion> = None;
 }
 
 /// The "kind" of an event.
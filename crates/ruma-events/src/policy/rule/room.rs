@@ -10,7 +10,7 @@ use crate::policy::rule::PolicyRuleEventContent;
 /// This event type is used to apply rules to room entities.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.policy.rule.room", kind = State)]
+#[ruma_event(type = "m.policy.rule.room", kind = State, added = R0_6_1)]
 pub struct PolicyRuleRoomEventContent(pub PolicyRuleEventContent);
 
 #[cfg(test)]
@@ -10,5 +10,5 @@ use crate::policy::rule::PolicyRuleEventContent;
 /// This event type is used to apply rules to server entities.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.policy.rule.server", kind = State)]
+#[ruma_event(type = "m.policy.rule.server", kind = State, added = R0_6_1)]
 pub struct PolicyRuleServerEventContent(pub PolicyRuleEventContent);
@@ -10,5 +10,5 @@ use crate::policy::rule::PolicyRuleEventContent;
 /// This event type is used to apply rules to user entities.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.policy.rule.user", kind = State)]
+#[ruma_event(type = "m.policy.rule.user", kind = State, added = R0_6_1)]
 pub struct PolicyRuleUserEventContent(pub PolicyRuleEventContent);
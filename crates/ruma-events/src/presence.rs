@@ -3,7 +3,7 @@
 //! The only content valid for this event is `PresenceEventContent`.
 
 use js_int::UInt;
-use ruma_common::presence::PresenceState;
+use ruma_common::{presence::PresenceState, MatrixVersion};
 use ruma_events_macros::{Event, EventContent};
 use ruma_identifiers::{MxcUri, UserId};
 use serde::{Deserialize, Serialize};
@@ -26,7 +26,7 @@ pub struct PresenceEvent {
 /// This is the only type a `PresenceEvent` can contain as its `content` field.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.presence")]
+#[ruma_event(type = "m.presence", added = R0_0_0)]
 pub struct PresenceEventContent {
     /// The current avatar URL for this user.
     ///
@@ -76,6 +76,7 @@ impl PresenceEventContent {
 impl StaticEventContent for PresenceEventContent {
     const KIND: EventKind = EventKind::Presence;
     const TYPE: &'static str = "m.presence";
+    const ADDED: Option<MatrixVersion> = Some(MatrixVersion::R0_0_0);
 }
 
 #[cfg(test)]
@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
 /// Informs the client who has read a message specified by it's event id.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[allow(clippy::exhaustive_structs)]
-#[ruma_event(type = "m.receipt", kind = EphemeralRoom)]
+#[ruma_event(type = "m.receipt", kind = EphemeralRoom, added = R0_0_0)]
 pub struct ReceiptEventContent(pub BTreeMap<Box<EventId>, Receipts>);
 
 impl Deref for ReceiptEventContent {
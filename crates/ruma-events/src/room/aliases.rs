@@ -15,7 +15,7 @@ use crate::{
 /// Informs the room about what room aliases it has been given.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.aliases", kind = State, custom_redacted)]
+#[ruma_event(type = "m.room.aliases", kind = State, custom_redacted, added = R0_0_0)]
 pub struct RoomAliasesEventContent {
     /// A list of room aliases.
     pub aliases: Vec<Box<RoomAliasId>>,
@@ -15,7 +15,7 @@ use super::ThumbnailInfo;
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 #[cfg_attr(feature = "unstable-pre-spec", derive(Default))]
-#[ruma_event(type = "m.room.avatar", kind = State)]
+#[ruma_event(type = "m.room.avatar", kind = State, added = R0_0_0)]
 pub struct RoomAvatarEventContent {
     /// Information about the avatar image.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// Informs the room as to which alias is the canonical one.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.canonical_alias", kind = State)]
+#[ruma_event(type = "m.room.canonical_alias", kind = State, added = R0_0_0)]
 pub struct RoomCanonicalAliasEventContent {
     /// The canonical alias.
     ///
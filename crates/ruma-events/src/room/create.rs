@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 /// It acts as the root of all other events.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.create", kind = State)]
+#[ruma_event(type = "m.room.create", kind = State, added = R0_0_0)]
 pub struct RoomCreateEventContent {
     /// The `user_id` of the room creator.
     ///
@@ -18,7 +18,7 @@ mod relation_serde;
 /// The content of an `m.room.encrypted` event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.encrypted", kind = Message, kind = ToDevice)]
+#[ruma_event(type = "m.room.encrypted", kind = Message, kind = ToDevice, added = R0_3_0)]
 pub struct RoomEncryptedEventContent {
     /// Algorithm-specific fields.
     #[serde(flatten)]
@@ -47,7 +47,7 @@ impl From<EncryptedEventScheme> for RoomEncryptedEventContent {
 /// The to-device content of an `m.room.encrypted` event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.encrypted", kind = ToDevice)]
+#[ruma_event(type = "m.room.encrypted", kind = ToDevice, added = R0_3_0)]
 pub struct ToDeviceRoomEncryptedEventContent {
     /// Algorithm-specific fields.
     #[serde(flatten)]
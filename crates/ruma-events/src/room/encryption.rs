@@ -11,7 +11,7 @@ use crate::EventEncryptionAlgorithm;
 /// Defines how messages sent in this room should be encrypted.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.encryption", kind = State)]
+#[ruma_event(type = "m.room.encryption", kind = State, added = R0_3_0)]
 pub struct RoomEncryptionEventContent {
     /// The encryption algorithm to be used to encrypt messages sent in this room.
     ///
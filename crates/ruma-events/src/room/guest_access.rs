@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 /// servers should act as if it is present and has the value `GuestAccess::Forbidden`.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.guest_access", kind = State)]
+#[ruma_event(type = "m.room.guest_access", kind = State, added = R0_0_0)]
 pub struct RoomGuestAccessEventContent {
     /// A policy for guest user access to a room.
     pub guest_access: GuestAccess,
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 /// before they joined.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.history_visibility", kind = State)]
+#[ruma_event(type = "m.room.history_visibility", kind = State, added = R0_0_0)]
 pub struct RoomHistoryVisibilityEventContent {
     /// Who can see the room history.
     #[ruma_event(skip_redaction)]
@@ -21,7 +21,7 @@ use std::collections::BTreeMap;
 /// Describes how users are allowed to join the room.
 #[derive(Clone, Debug, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.join_rules", kind = State)]
+#[ruma_event(type = "m.room.join_rules", kind = State, added = R0_0_0)]
 pub struct RoomJoinRulesEventContent {
     /// The type of rules used for users wishing to join this room.
     #[ruma_event(skip_redaction)]
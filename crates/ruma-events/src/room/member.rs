@@ -42,7 +42,7 @@ use crate::{
 /// must be assumed as leave.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.member", kind = State, custom_redacted)]
+#[ruma_event(type = "m.room.member", kind = State, custom_redacted, added = R0_0_0)]
 pub struct RoomMemberEventContent {
     /// The avatar URL for this user, if any.
     ///
@@ -29,7 +29,7 @@ pub use reply::ReplyBaseEvent;
 /// Messages are not limited to be text.
 #[derive(Clone, Debug, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.message", kind = Message)]
+#[ruma_event(type = "m.room.message", kind = Message, added = R0_0_0)]
 pub struct RoomMessageEventContent {
     /// A key which identifies the type of message being sent.
     ///
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 /// not recognize this event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.message.feedback", kind = Message)]
+#[ruma_event(type = "m.room.message.feedback", kind = Message, added = R0_0_0)]
 pub struct RoomMessageFeedbackEventContent {
     /// The event that this feedback is related to.
     pub target_event_id: Box<EventId>,
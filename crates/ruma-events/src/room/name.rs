@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// The room name is a human-friendly string designed to be displayed to the end-user.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
-#[ruma_event(type = "m.room.name", kind = State)]
+#[ruma_event(type = "m.room.name", kind = State, added = R0_0_0)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomNameEventContent {
     /// The name of the room.
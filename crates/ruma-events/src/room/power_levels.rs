@@ -17,7 +17,7 @@ use ruma_common::power_levels::NotificationPowerLevels;
 /// Defines the power levels (privileges) of users in the room.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.power_levels", kind = State)]
+#[ruma_event(type = "m.room.power_levels", kind = State, added = R0_0_0)]
 pub struct RoomPowerLevelsEventContent {
     /// The level required to ban a user.
     ///
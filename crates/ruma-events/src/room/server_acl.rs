@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 /// An event to indicate which servers are permitted to participate in the room.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.server_acl", kind = State)]
+#[ruma_event(type = "m.room.server_acl", kind = State, added = R0_4_0)]
 pub struct RoomServerAclEventContent {
     /// Whether to allow server names that are IP address literals.
     ///
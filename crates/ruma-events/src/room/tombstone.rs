@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// A state event signifying that a room has been upgraded to a different room version, and that
 /// clients should go there.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
-#[ruma_event(type = "m.room.tombstone", kind = State)]
+#[ruma_event(type = "m.room.tombstone", kind = State, added = R0_5_0)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
 pub struct RoomTombstoneEventContent {
     /// A server-defined message.
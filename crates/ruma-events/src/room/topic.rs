@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 /// A topic is a short message detailing what is currently being discussed in the room.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room.topic", kind = State)]
+#[ruma_event(type = "m.room.topic", kind = State, added = R0_0_0)]
 pub struct RoomTopicEventContent {
     /// The topic text.
     pub topic: String,
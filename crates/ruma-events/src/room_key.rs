@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// Typically encrypted as an `m.room.encrypted` event, then sent as a to-device event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room_key", kind = ToDevice)]
+#[ruma_event(type = "m.room_key", kind = ToDevice, added = R0_3_0)]
 pub struct ToDeviceRoomKeyEventContent {
     /// The encryption algorithm the key in this event is to be used with.
     ///
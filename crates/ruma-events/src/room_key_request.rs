@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 /// The content of an `m.room_key_request` event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.room_key_request", kind = ToDevice)]
+#[ruma_event(type = "m.room_key_request", kind = ToDevice, added = R0_4_0)]
 pub struct ToDeviceRoomKeyRequestEventContent {
     /// Whether this is a new key request or a cancellation of a previous request.
     pub action: Action,
@@ -14,7 +14,7 @@ use serde::{ser::SerializeStruct, Deserialize, Serialize};
 /// It is sent as an unencrypted to-device event.
 #[derive(Clone, Debug, Serialize, Deserialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.secret.request", kind = ToDevice)]
+#[ruma_event(type = "m.secret.request", kind = ToDevice, added = V1_1)]
 pub struct ToDeviceSecretRequestEventContent {
     /// The action for the request.
     #[serde(flatten)]
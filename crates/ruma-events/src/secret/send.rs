@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 /// It must be encrypted as an `m.room.encrypted` event, then sent as a to-device event.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.secret.send", kind = ToDevice)]
+#[ruma_event(type = "m.secret.send", kind = ToDevice, added = V1_1)]
 pub struct ToDeviceSecretSendEventContent {
     /// The ID of the request that this is a response to.
     pub request_id: String,
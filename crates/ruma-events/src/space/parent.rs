@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 /// parent.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.space.parent", kind = State)]
+#[ruma_event(type = "m.space.parent", kind = State, added = V1_2)]
 pub struct SpaceParentEventContent {
     /// List of candidate servers that can be used to join the room.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -14,7 +14,7 @@ pub type Tags = BTreeMap<TagName, TagInfo>;
 /// Informs the client of tags on a room.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.tag", kind = RoomAccountData)]
+#[ruma_event(type = "m.tag", kind = RoomAccountData, added = R0_0_0)]
 pub struct TagEventContent {
     /// A map of tag names to tag info.
     pub tags: Tags,
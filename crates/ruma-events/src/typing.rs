@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 /// Informs the client who is currently typing in a given room.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
 #[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
-#[ruma_event(type = "m.typing", kind = EphemeralRoom)]
+#[ruma_event(type = "m.typing", kind = EphemeralRoom, added = R0_0_0)]
 pub struct TypingEventContent {
     /// The list of user IDs typing in this room, if any.
     pub user_ids: Vec<Box<UserId>>,
@@ -1,3 +1,10 @@
+use ruma_common::MatrixVersion;
+use ruma_events::{
+    room::{message::RoomMessageEventContent, tombstone::RoomTombstoneEventContent},
+    sticker::StickerEventContent,
+    StaticEventContent,
+};
+
 #[test]
 fn ui() {
     let t = trybuild::TestCases::new();
@@ -5,3 +12,10 @@ fn ui() {
     t.compile_fail("tests/ui/02-no-event-type.rs");
     t.compile_fail("tests/ui/03-invalid-event-type.rs");
 }
+
+#[test]
+fn added_version() {
+    assert_eq!(RoomMessageEventContent::ADDED, Some(MatrixVersion::R0_0_0));
+    assert_eq!(StickerEventContent::ADDED, Some(MatrixVersion::R0_4_0));
+    assert_eq!(RoomTombstoneEventContent::ADDED, Some(MatrixVersion::R0_5_0));
+}
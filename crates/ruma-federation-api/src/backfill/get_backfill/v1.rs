@@ -15,6 +15,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/backfill/:room_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
@@ -14,6 +14,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/publicRooms",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     #[derive(Default)]
@@ -11,6 +11,7 @@ ruma_api! {
         path: "/.well-known/matrix/server",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     #[derive(Default)]
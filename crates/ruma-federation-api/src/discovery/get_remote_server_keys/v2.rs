@@ -16,6 +16,7 @@ ruma_api! {
         path: "/_matrix/key/v2/query/:server_name",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
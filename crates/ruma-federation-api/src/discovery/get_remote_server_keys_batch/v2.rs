@@ -17,6 +17,7 @@ ruma_api! {
         path: "/_matrix/key/v2/query",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/key/v2/server",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     #[derive(Default)]
@@ -14,6 +14,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/event/:event_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
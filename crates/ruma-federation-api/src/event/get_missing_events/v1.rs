@@ -14,6 +14,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/get_missing_events/:room_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
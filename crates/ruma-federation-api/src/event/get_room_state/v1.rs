@@ -13,6 +13,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/state/:room_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
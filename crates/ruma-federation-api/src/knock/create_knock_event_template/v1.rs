@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/make_knock/:room_id/:user_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/send_knock/:room_id/:event_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
@@ -20,6 +20,8 @@ pub mod keys;
 #[cfg(feature = "unstable-pre-spec")]
 pub mod knock;
 pub mod membership;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod openid;
 pub mod query;
 pub mod thirdparty;
@@ -16,6 +16,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/invite/:room_id/:event_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
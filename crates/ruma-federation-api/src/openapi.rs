@@ -0,0 +1,13 @@
+//! OpenAPI description of the Matrix Server-Server API.
+
+use ruma_api::openapi::Document;
+
+/// Assembles an OpenAPI document of all the endpoints of this crate.
+///
+/// Endpoints behind Cargo features are only included if the feature is enabled.
+pub fn document() -> Document {
+    let mut document = Document::new("Matrix Server-Server API", env!("CARGO_PKG_VERSION"));
+    document.extend(ruma_api::openapi::endpoints!());
+
+    document
+}
@@ -11,6 +11,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/openid/userinfo",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
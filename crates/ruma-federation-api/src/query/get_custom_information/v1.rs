@@ -13,6 +13,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/query/:query_type",
         rate_limited: false,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
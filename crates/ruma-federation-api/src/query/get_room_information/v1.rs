@@ -11,6 +11,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/query/directory",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
@@ -15,6 +15,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/3pid/onbind",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/exchange_third_party_invite/:room_id",
         rate_limited: false,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -18,6 +18,7 @@ ruma_api! {
         path: "/_matrix/federation/v1/send/:transaction_id",
         rate_limited: false,
         authentication: ServerSignatures,
+        added: V1_1,
     }
 
     request: {
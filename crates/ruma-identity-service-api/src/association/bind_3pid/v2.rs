@@ -12,6 +12,7 @@ ruma_api! {
         path: "/_matrix/identity/v2/3pid/bind",
         rate_limited: false,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -10,6 +10,7 @@ ruma_api! {
         name: "validate_email_by_end_user",
         path: "/_matrix/identity/v2/validate/email/submitToken",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -11,6 +11,7 @@ ruma_api! {
         name: "create_msisdn_validation_session",
         path: "/_matrix/identity/v2/validate/msisdn/requestToken",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -13,6 +13,7 @@ ruma_api! {
         path: "/_matrix/identity/v2/3pid/unbind",
         rate_limited: false,
         authentication: AccessToken,
+        added: V1_1,
     }
 
     request: {
@@ -10,6 +10,7 @@ ruma_api! {
         name: "get_account_information",
         path: "/_matrix/identity/v2/account",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -9,6 +9,7 @@ ruma_api! {
         name: "logout",
         path: "/_matrix/identity/v2/account/logout",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -13,6 +13,7 @@ ruma_api! {
         name: "register_account",
         path: "/_matrix/identity/v2/account/register",
         authentication: None,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -10,6 +10,7 @@ ruma_api! {
         name: "sign_invitation_ed25519",
         path: "/_matrix/identity/v2/sign-ed25519",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -12,6 +12,7 @@ ruma_api! {
         name: "store_invitation",
         path: "/_matrix/identity/v2/store-invite",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -9,6 +9,7 @@ ruma_api! {
         name: "check_public_key_validity",
         path: "/_matrix/identity/v2/pubkey/isvalid",
         authentication: None,
+        added: V1_1,
         rate_limited: false,
     }
 
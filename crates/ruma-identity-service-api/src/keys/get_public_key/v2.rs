@@ -11,6 +11,7 @@ ruma_api! {
         path: "/_matrix/identity/v2/pubkey/:key_id",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
@@ -9,6 +9,7 @@ ruma_api! {
         name: "validate_ephemeral_key",
         path: "/_matrix/identity/v2/pubkey/ephemeral/isvalid",
         authentication: None,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -12,5 +12,7 @@ pub mod authentication;
 pub mod invitation;
 pub mod keys;
 pub mod lookup;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod status;
 pub mod tos;
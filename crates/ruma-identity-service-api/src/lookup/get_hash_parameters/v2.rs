@@ -11,6 +11,7 @@ ruma_api! {
         name: "get_hash_parameters",
         path: "/_matrix/identity/v2/hash_details",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -14,6 +14,7 @@ ruma_api! {
         name: "lookup_3pid",
         path: "/_matrix/identity/v2/lookup",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -9,6 +9,7 @@ ruma_api! {
         name: "status",
         path: "/_matrix/identity/v2",
         authentication: None,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -9,6 +9,7 @@ ruma_api! {
         name: "accept_terms_of_service",
         path: "/_matrix/identity/v2/terms",
         authentication: AccessToken,
+        added: V1_1,
         rate_limited: false,
     }
 
@@ -12,6 +12,7 @@ ruma_api! {
         name: "get_terms_of_service",
         path: "/_matrix/identity/v2/terms",
         authentication: None,
+        added: V1_1,
         rate_limited: false,
     }
 
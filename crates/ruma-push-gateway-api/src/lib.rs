@@ -7,4 +7,6 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod send_event_notification;
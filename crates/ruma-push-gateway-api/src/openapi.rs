@@ -0,0 +1,13 @@
+//! OpenAPI description of the Matrix Push Gateway API.
+
+use ruma_api::openapi::Document;
+
+/// Assembles an OpenAPI document of all the endpoints of this crate.
+///
+/// Endpoints behind Cargo features are only included if the feature is enabled.
+pub fn document() -> Document {
+    let mut document = Document::new("Matrix Push Gateway API", env!("CARGO_PKG_VERSION"));
+    document.extend(ruma_api::openapi::endpoints!());
+
+    document
+}
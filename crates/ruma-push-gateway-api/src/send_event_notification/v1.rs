@@ -20,6 +20,7 @@ ruma_api! {
         path: "/_matrix/push/v1/notify",
         rate_limited: false,
         authentication: None,
+        added: V1_1,
     }
 
     request: {
@@ -0,0 +1,468 @@
+//! Signing and verification of device keys and cross-signing keys.
+
+use std::collections::BTreeMap;
+
+use ruma_common::encryption::{CrossSigningKey, DeviceKeys, KeyUsage};
+use ruma_identifiers::{DeviceId, UserId};
+use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue, Raw};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{sign_json, verify_json, Error, JsonError, JsonType, KeyPair, VerificationError};
+
+/// A chain of cross-signing signatures that was verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum TrustChain {
+    /// A device of a user is signed by the user's self-signing key, which is signed by the user's
+    /// master key.
+    Device {
+        /// The user the keys belong to.
+        user_id: Box<UserId>,
+
+        /// The public key of the user's master key.
+        master_key: String,
+
+        /// The public key of the user's self-signing key.
+        self_signing_key: String,
+
+        /// The ID of the device.
+        device_id: Box<DeviceId>,
+    },
+
+    /// The master key of another user is signed by a user's user-signing key, which is signed by
+    /// the user's master key.
+    User {
+        /// The user that signed the other user's master key.
+        user_id: Box<UserId>,
+
+        /// The public key of the user's master key.
+        master_key: String,
+
+        /// The public key of the user's user-signing key.
+        user_signing_key: String,
+
+        /// The user whose master key was signed.
+        other_user_id: Box<UserId>,
+
+        /// The public key of the other user's master key.
+        other_master_key: String,
+    },
+}
+
+/// Signs device keys with a key pair, e.g. the self-signing key of the user.
+///
+/// The signature is added under the ID of the user. Since cross-signing keys are identified by
+/// their public key, the version of a key pair of a cross-signing key must be its Unpadded Base64
+/// encoded public key.
+///
+/// # Errors
+///
+/// Returns an error if the device keys can't be converted to and from canonical JSON.
+pub fn sign_device_keys<K: KeyPair>(
+    user_id: &UserId,
+    key_pair: &K,
+    device_keys: &mut DeviceKeys,
+) -> Result<(), Error> {
+    sign_object(user_id, key_pair, device_keys)
+}
+
+/// Signs a cross-signing key with a key pair, e.g. the master key of the user.
+///
+/// The signature is added under the ID of the user. Since cross-signing keys are identified by
+/// their public key, the version of a key pair of a cross-signing key must be its Unpadded Base64
+/// encoded public key.
+///
+/// # Errors
+///
+/// Returns an error if the key can't be converted to and from canonical JSON.
+pub fn sign_cross_signing_key<K: KeyPair>(
+    user_id: &UserId,
+    key_pair: &K,
+    key: &mut CrossSigningKey,
+) -> Result<(), Error> {
+    sign_object(user_id, key_pair, key)
+}
+
+/// Verifies that device keys are signed by the self-signing key of their user.
+///
+/// The signature is checked against the JSON of the device keys as received, so that fields that
+/// are not part of [`DeviceKeys`] are still covered by it.
+///
+/// # Errors
+///
+/// Returns an error if `self_signing_key` is not a self-signing key of the user of the device,
+/// or if the device keys don't have a valid signature of it.
+pub fn verify_device_keys(
+    device_keys: &Raw<DeviceKeys>,
+    self_signing_key: &Raw<CrossSigningKey>,
+) -> Result<(), Error> {
+    let (device_keys, device_keys_object) = parse(device_keys)?;
+    let (self_signing_key, _) = parse(self_signing_key)?;
+
+    check_usage(&self_signing_key, &KeyUsage::SelfSigning)?;
+    if device_keys.user_id != self_signing_key.user_id {
+        return Err(VerificationError::UserIdMismatch.into());
+    }
+
+    verify_signed_by(device_keys_object, &self_signing_key)
+}
+
+/// Verifies that a cross-signing key is signed by another cross-signing key.
+///
+/// The signature is looked up under the user of `signing_key`, and checked against the JSON of
+/// `key` as received. This only checks the signature, not whether the usages of the keys allow
+/// one to sign the other; use [`verify_device_trust`] and [`verify_user_trust`] to check a full
+/// chain of signatures.
+///
+/// # Errors
+///
+/// Returns an error if `signing_key` doesn't have exactly one Ed25519 public key, or if `key`
+/// doesn't have a valid signature of it.
+pub fn verify_cross_signing_key(
+    key: &Raw<CrossSigningKey>,
+    signing_key: &Raw<CrossSigningKey>,
+) -> Result<(), Error> {
+    let (_, object) = parse(key)?;
+    let (signing_key, _) = parse(signing_key)?;
+
+    verify_signed_by(object, &signing_key)
+}
+
+/// Verifies the chain of signatures from the master key of a user to one of their devices.
+///
+/// # Errors
+///
+/// Returns an error if any of the keys doesn't have the expected usage or belongs to another
+/// user, or if any of the signatures of the chain is missing or invalid.
+pub fn verify_device_trust(
+    master_key: &Raw<CrossSigningKey>,
+    self_signing_key: &Raw<CrossSigningKey>,
+    device_keys: &Raw<DeviceKeys>,
+) -> Result<TrustChain, Error> {
+    let (master_key, _) = parse(master_key)?;
+    let (self_signing_key, self_signing_key_object) = parse(self_signing_key)?;
+    let (device_keys, device_keys_object) = parse(device_keys)?;
+
+    check_usage(&master_key, &KeyUsage::Master)?;
+    check_usage(&self_signing_key, &KeyUsage::SelfSigning)?;
+    if self_signing_key.user_id != master_key.user_id
+        || device_keys.user_id != self_signing_key.user_id
+    {
+        return Err(VerificationError::UserIdMismatch.into());
+    }
+
+    verify_signed_by(self_signing_key_object, &master_key)?;
+    verify_signed_by(device_keys_object, &self_signing_key)?;
+
+    Ok(TrustChain::Device {
+        master_key: public_key(&master_key)?.1.to_owned(),
+        self_signing_key: public_key(&self_signing_key)?.1.to_owned(),
+        user_id: master_key.user_id,
+        device_id: device_keys.device_id,
+    })
+}
+
+/// Verifies the chain of signatures from the master key of a user to the master key of another
+/// user.
+///
+/// # Errors
+///
+/// Returns an error if any of the keys doesn't have the expected usage or belongs to the wrong
+/// user, or if any of the signatures of the chain is missing or invalid.
+pub fn verify_user_trust(
+    master_key: &Raw<CrossSigningKey>,
+    user_signing_key: &Raw<CrossSigningKey>,
+    other_master_key: &Raw<CrossSigningKey>,
+) -> Result<TrustChain, Error> {
+    let (master_key, _) = parse(master_key)?;
+    let (user_signing_key, user_signing_key_object) = parse(user_signing_key)?;
+    let (other_master_key, other_master_key_object) = parse(other_master_key)?;
+
+    check_usage(&master_key, &KeyUsage::Master)?;
+    check_usage(&user_signing_key, &KeyUsage::UserSigning)?;
+    check_usage(&other_master_key, &KeyUsage::Master)?;
+    if user_signing_key.user_id != master_key.user_id {
+        return Err(VerificationError::UserIdMismatch.into());
+    }
+
+    verify_signed_by(user_signing_key_object, &master_key)?;
+    verify_signed_by(other_master_key_object, &user_signing_key)?;
+
+    Ok(TrustChain::User {
+        master_key: public_key(&master_key)?.1.to_owned(),
+        user_signing_key: public_key(&user_signing_key)?.1.to_owned(),
+        other_master_key: public_key(&other_master_key)?.1.to_owned(),
+        user_id: master_key.user_id,
+        other_user_id: other_master_key.user_id,
+    })
+}
+
+/// Deserializes a key, and converts its JSON to canonical JSON to check its signatures.
+fn parse<T: DeserializeOwned>(raw: &Raw<T>) -> Result<(T, CanonicalJsonObject), Error> {
+    let value = raw.deserialize().map_err(JsonError::from)?;
+    let object = serde_json::from_str(raw.json().get()).map_err(JsonError::from)?;
+    Ok((value, object))
+}
+
+/// Returns the key ID and the public key of a cross-signing key.
+fn public_key(key: &CrossSigningKey) -> Result<(&str, &str), Error> {
+    let mut keys = key.keys.iter();
+    match (keys.next(), keys.next()) {
+        (Some((key_id, public_key)), None) if key_id.starts_with("ed25519:") => {
+            Ok((key_id, public_key))
+        }
+        _ => Err(VerificationError::InvalidCrossSigningKey.into()),
+    }
+}
+
+fn check_usage(key: &CrossSigningKey, usage: &KeyUsage) -> Result<(), Error> {
+    let has_usage = key.usage.iter().any(|u| {
+        matches!(
+            (u, usage),
+            (KeyUsage::Master, KeyUsage::Master)
+                | (KeyUsage::SelfSigning, KeyUsage::SelfSigning)
+                | (KeyUsage::UserSigning, KeyUsage::UserSigning)
+        )
+    });
+
+    if has_usage {
+        Ok(())
+    } else {
+        Err(VerificationError::KeyUsageMismatch.into())
+    }
+}
+
+fn to_canonical_object<T: Serialize>(value: &T) -> Result<CanonicalJsonObject, Error> {
+    Ok(serde_json::to_value(value).and_then(serde_json::from_value).map_err(JsonError::from)?)
+}
+
+fn sign_object<K, T>(user_id: &UserId, key_pair: &K, value: &mut T) -> Result<(), Error>
+where
+    K: KeyPair,
+    T: Serialize + DeserializeOwned,
+{
+    let mut object = to_canonical_object(value)?;
+    sign_json(user_id.as_str(), key_pair, &mut object)?;
+
+    *value =
+        serde_json::to_value(object).and_then(serde_json::from_value).map_err(JsonError::from)?;
+    Ok(())
+}
+
+/// Verifies the signature of `signing_key` on `object`, ignoring all other signatures.
+fn verify_signed_by(
+    mut object: CanonicalJsonObject,
+    signing_key: &CrossSigningKey,
+) -> Result<(), Error> {
+    let (key_id, public_key) = public_key(signing_key)?;
+    let user_id = signing_key.user_id.as_str();
+
+    let signature = match object.get("signatures") {
+        Some(CanonicalJsonValue::Object(signatures)) => match signatures.get(user_id) {
+            Some(CanonicalJsonValue::Object(set)) => set.get(key_id).cloned(),
+            Some(_) => {
+                return Err(JsonError::not_multiples_of_type("signature sets", JsonType::Object))
+            }
+            None => None,
+        },
+        Some(_) => return Err(JsonError::not_of_type("signatures", JsonType::Object)),
+        None => None,
+    }
+    .ok_or_else(|| VerificationError::signature_not_found(signing_key.user_id.server_name()))?;
+
+    let mut signature_set = BTreeMap::new();
+    signature_set.insert(key_id.to_owned(), signature);
+    let mut signatures = BTreeMap::new();
+    signatures.insert(user_id.to_owned(), CanonicalJsonValue::Object(signature_set));
+    object.insert("signatures".to_owned(), CanonicalJsonValue::Object(signatures));
+
+    let mut public_keys = BTreeMap::new();
+    public_keys.insert(key_id.to_owned(), public_key.to_owned());
+    let mut public_key_map = BTreeMap::new();
+    public_key_map.insert(user_id.to_owned(), public_keys);
+
+    verify_json(&public_key_map, &object)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, convert::TryFrom};
+
+    use ruma_common::encryption::{CrossSigningKey, DeviceKeys, KeyUsage};
+    use ruma_identifiers::{server_name, user_id, DeviceKeyId, EventEncryptionAlgorithm, UserId};
+    use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue, Raw};
+    use serde::Serialize;
+
+    use super::{
+        sign_cross_signing_key, sign_device_keys, verify_device_keys, verify_device_trust,
+        verify_user_trust, TrustChain,
+    };
+    use crate::{sign_json, Ed25519KeyPair, Error, VerificationError};
+
+    fn raw<T: Serialize>(value: &T) -> Raw<T> {
+        Raw::new(value).unwrap()
+    }
+
+    /// Generates a key pair whose version is its public key, as used for cross-signing keys.
+    fn generate_key_pair() -> Ed25519KeyPair {
+        let key_pair =
+            Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "".to_owned()).unwrap();
+        Ed25519KeyPair::from_seed(key_pair.seed(), key_pair.public_key_base64()).unwrap()
+    }
+
+    fn cross_signing_key(
+        user_id: &UserId,
+        usage: KeyUsage,
+        key_pair: &Ed25519KeyPair,
+    ) -> CrossSigningKey {
+        let mut keys = BTreeMap::new();
+        keys.insert(format!("ed25519:{}", key_pair.version()), key_pair.public_key_base64());
+        CrossSigningKey::new(user_id.to_owned(), vec![usage], keys, BTreeMap::new())
+    }
+
+    fn device_keys(user_id: &UserId) -> DeviceKeys {
+        let mut keys = BTreeMap::new();
+        keys.insert(
+            Box::<DeviceKeyId>::try_from("ed25519:DEVICE").unwrap(),
+            "device_public_key".to_owned(),
+        );
+        DeviceKeys::new(
+            user_id.to_owned(),
+            "DEVICE".into(),
+            vec![EventEncryptionAlgorithm::MegolmV1AesSha2],
+            keys,
+            BTreeMap::new(),
+        )
+    }
+
+    #[test]
+    fn device_trust_chain() {
+        let alice = user_id!("@alice:example.org");
+        let master = generate_key_pair();
+        let self_signing = generate_key_pair();
+
+        let master_key = cross_signing_key(alice, KeyUsage::Master, &master);
+        let mut self_signing_key = cross_signing_key(alice, KeyUsage::SelfSigning, &self_signing);
+        let mut device_keys = device_keys(alice);
+
+        // Nothing is signed yet
+        assert!(verify_device_keys(&raw(&device_keys), &raw(&self_signing_key)).is_err());
+
+        sign_cross_signing_key(alice, &master, &mut self_signing_key).unwrap();
+        sign_device_keys(alice, &self_signing, &mut device_keys).unwrap();
+
+        let chain =
+            verify_device_trust(&raw(&master_key), &raw(&self_signing_key), &raw(&device_keys))
+                .unwrap();
+        assert_eq!(
+            chain,
+            TrustChain::Device {
+                user_id: alice.to_owned(),
+                master_key: master.public_key_base64(),
+                self_signing_key: self_signing.public_key_base64(),
+                device_id: "DEVICE".into(),
+            }
+        );
+
+        // The keys are not interchangeable
+        assert!(matches!(
+            verify_device_trust(
+                &raw(&self_signing_key),
+                &raw(&self_signing_key),
+                &raw(&device_keys)
+            ),
+            Err(Error::Verification(VerificationError::KeyUsageMismatch))
+        ));
+
+        // A modified device is no longer trusted
+        device_keys.algorithms.clear();
+        assert!(matches!(
+            verify_device_trust(&raw(&master_key), &raw(&self_signing_key), &raw(&device_keys)),
+            Err(Error::Verification(VerificationError::Signature(_)))
+        ));
+    }
+
+    #[test]
+    fn user_trust_chain() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let master = generate_key_pair();
+        let user_signing = generate_key_pair();
+        let bob_master = generate_key_pair();
+
+        let master_key = cross_signing_key(alice, KeyUsage::Master, &master);
+        let mut user_signing_key = cross_signing_key(alice, KeyUsage::UserSigning, &user_signing);
+        let mut bob_master_key = cross_signing_key(bob, KeyUsage::Master, &bob_master);
+
+        sign_cross_signing_key(alice, &master, &mut user_signing_key).unwrap();
+
+        // Bob's master key isn't signed by Alice yet
+        assert!(matches!(
+            verify_user_trust(&raw(&master_key), &raw(&user_signing_key), &raw(&bob_master_key)),
+            Err(Error::Verification(VerificationError::SignatureNotFound(server)))
+                if server == server_name!("example.org")
+        ));
+
+        sign_cross_signing_key(alice, &user_signing, &mut bob_master_key).unwrap();
+
+        let chain =
+            verify_user_trust(&raw(&master_key), &raw(&user_signing_key), &raw(&bob_master_key))
+                .unwrap();
+        assert_eq!(
+            chain,
+            TrustChain::User {
+                user_id: alice.to_owned(),
+                master_key: master.public_key_base64(),
+                user_signing_key: user_signing.public_key_base64(),
+                other_user_id: bob.to_owned(),
+                other_master_key: bob_master.public_key_base64(),
+            }
+        );
+    }
+
+    #[test]
+    fn keys_of_other_user() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let master = generate_key_pair();
+        let self_signing = generate_key_pair();
+
+        let master_key = cross_signing_key(bob, KeyUsage::Master, &master);
+        let mut self_signing_key = cross_signing_key(alice, KeyUsage::SelfSigning, &self_signing);
+        sign_cross_signing_key(bob, &master, &mut self_signing_key).unwrap();
+
+        assert!(matches!(
+            verify_device_trust(
+                &raw(&master_key),
+                &raw(&self_signing_key),
+                &raw(&device_keys(alice))
+            ),
+            Err(Error::Verification(VerificationError::UserIdMismatch))
+        ));
+    }
+
+    #[test]
+    fn unknown_fields_are_signed() {
+        let alice = user_id!("@alice:example.org");
+        let self_signing = generate_key_pair();
+        let mut self_signing_key = cross_signing_key(alice, KeyUsage::SelfSigning, &self_signing);
+        sign_cross_signing_key(alice, &generate_key_pair(), &mut self_signing_key).unwrap();
+
+        // Device keys with a field that `DeviceKeys` doesn't know about, e.g. from a newer client
+        let mut object: CanonicalJsonObject =
+            serde_json::from_value(serde_json::to_value(device_keys(alice)).unwrap()).unwrap();
+        object.insert("org.example.field".to_owned(), CanonicalJsonValue::String("x".to_owned()));
+        sign_json(alice.as_str(), &self_signing, &mut object).unwrap();
+
+        let device_keys = Raw::from_json(serde_json::value::to_raw_value(&object).unwrap());
+        verify_device_keys(&device_keys, &raw(&self_signing_key)).unwrap();
+
+        // The field is covered by the signature
+        object.insert("org.example.field".to_owned(), CanonicalJsonValue::String("y".to_owned()));
+        let device_keys = Raw::from_json(serde_json::value::to_raw_value(&object).unwrap());
+        assert!(matches!(
+            verify_device_keys(&device_keys, &raw(&self_signing_key)),
+            Err(Error::Verification(VerificationError::Signature(_)))
+        ));
+    }
+}
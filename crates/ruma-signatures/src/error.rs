@@ -29,6 +29,10 @@ pub enum Error {
     /// PDU was too large
     #[error("PDU is larger than maximum of 65535 bytes")]
     PduSize,
+
+    /// An [`AsyncSigner`](crate::AsyncSigner) failed to create a signature.
+    #[error("Signer error: {0}")]
+    Signer(Box<dyn std::error::Error + Send + Sync>),
 }
 
 /// All errors related to JSON validation/parsing.
@@ -149,6 +153,31 @@ pub enum VerificationError {
     /// For when [`ed25519_dalek`] cannot verify a signature.
     #[error("Could not verify signature: {0}")]
     Signature(#[source] ed25519_dalek::SignatureError),
+
+    /// For when a cross-signing key doesn't have the usage required by its place in a chain of
+    /// signatures.
+    #[error("Cross-signing key does not have the expected usage")]
+    KeyUsageMismatch,
+
+    /// For when keys that must belong to the same user belong to different users.
+    #[error("Keys belong to different users")]
+    UserIdMismatch,
+
+    /// For when a cross-signing key doesn't have exactly one Ed25519 public key.
+    #[error("Cross-signing key must have exactly one Ed25519 public key")]
+    InvalidCrossSigningKey,
+
+    /// For when a federation request has no `X-Matrix` `Authorization` header.
+    #[error("Request has no X-Matrix Authorization header")]
+    XMatrixHeaderNotFound,
+
+    /// For when the `X-Matrix` `Authorization` headers of a request name different origins.
+    #[error("X-Matrix Authorization headers have different origins")]
+    OriginMismatch,
+
+    /// For when a federation request is addressed to another server.
+    #[error("X-Matrix Authorization header has a different destination")]
+    DestinationMismatch,
 }
 
 impl VerificationError {
@@ -222,6 +251,14 @@ pub enum ParseError {
         #[source]
         source: base64::DecodeError,
     },
+
+    /// For when a line of a signing key file can't be parsed.
+    #[error("Could not parse signing key {0:?}, expected `<algorithm> <version> <seed>`")]
+    SigningKey(String),
+
+    /// For when an `X-Matrix` `Authorization` header can't be parsed.
+    #[error("Could not parse X-Matrix Authorization header: {0}")]
+    XMatrix(String),
 }
 
 impl ParseError {
@@ -1,7 +1,6 @@
 //! Functions for signing and verifying JSON and events.
 
 use std::{
-    borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     mem,
@@ -14,13 +13,14 @@ use serde_json::{from_str as from_json_str, to_string as to_json_string};
 use sha2::{digest::Digest, Sha256};
 
 use crate::{
-    keys::{KeyPair, PublicKeyMap},
+    keys::{AsyncSigner, KeyPair, PublicKeyMap},
+    signatures::Signature,
     split_id,
-    verification::{Ed25519Verifier, Verified, Verifier},
+    verification::{verify_ed25519_batch, Ed25519Verifier, Verified, Verifier},
     Error, JsonError, JsonType, ParseError, VerificationError,
 };
 
-const MAX_PDU_BYTES: usize = 65_535;
+pub(crate) const MAX_PDU_BYTES: usize = 65_535;
 
 /// The fields that are allowed to remain in an event during redaction.
 static ALLOWED_KEYS: &[&str] = &[
@@ -141,21 +141,50 @@ pub fn sign_json<K>(
 where
     K: KeyPair,
 {
-    let (signatures_key, mut signature_map) = match object.remove_entry("signatures") {
-        Some((key, CanonicalJsonValue::Object(signatures))) => (Cow::Owned(key), signatures),
-        Some(_) => return Err(JsonError::not_of_type("signatures", JsonType::Object)),
-        None => (Cow::Borrowed("signatures"), BTreeMap::new()),
-    };
+    let signature = key_pair.sign(canonical_json(object)?.as_bytes());
 
-    let maybe_unsigned_entry = object.remove_entry("unsigned");
+    insert_signature(entity_id, object, &signature)
+}
 
-    // Get the canonical JSON string.
-    let json = to_json_string(object).map_err(JsonError::Serde)?;
+/// Signs an arbitrary JSON object with a signer whose signing operation is asynchronous.
+///
+/// This works like [`sign_json`], but lets the signature be created outside of the process, e.g.
+/// by a separate signing service. See [`AsyncSigner`] for details.
+///
+/// # Errors
+///
+/// Returns an error if:
+///
+/// * `object` contains a field called `signatures` that is not a JSON object.
+/// * The signer fails to sign the JSON.
+pub async fn sign_json_async<S>(
+    entity_id: &str,
+    signer: &S,
+    object: &mut CanonicalJsonObject,
+) -> Result<(), Error>
+where
+    S: AsyncSigner + ?Sized,
+{
+    let json = canonical_json(object)?;
+    let signature = signer.sign(json.as_bytes()).await?;
+
+    insert_signature(entity_id, object, &signature)
+}
 
-    // Sign the canonical JSON string.
-    let signature = key_pair.sign(json.as_bytes());
+/// Adds a signature of `entity_id` to the `signatures` of `object`.
+fn insert_signature(
+    entity_id: &str,
+    object: &mut CanonicalJsonObject,
+    signature: &Signature,
+) -> Result<(), Error> {
+    let signature_map = match object
+        .entry("signatures".to_owned())
+        .or_insert_with(|| CanonicalJsonValue::Object(BTreeMap::new()))
+    {
+        CanonicalJsonValue::Object(signatures) => signatures,
+        _ => return Err(JsonError::not_of_type("signatures", JsonType::Object)),
+    };
 
-    // Insert the new signature in the map we pulled out (or created) previously.
     let signature_set = signature_map
         .entry(entity_id.to_owned())
         .or_insert_with(|| CanonicalJsonValue::Object(BTreeMap::new()));
@@ -167,13 +196,6 @@ where
 
     signature_set.insert(signature.id(), CanonicalJsonValue::String(signature.base64()));
 
-    // Put `signatures` and `unsigned` back in.
-    object.insert(signatures_key.into(), CanonicalJsonValue::Object(signature_map));
-
-    if let Some((k, v)) = maybe_unsigned_entry {
-        object.insert(k, v);
-    }
-
     Ok(())
 }
 
@@ -495,6 +517,48 @@ pub fn hash_and_sign_event<K>(
 where
     K: KeyPair,
 {
+    insert_content_hash(object)?;
+
+    let mut redacted = redact(object, version)?;
+
+    sign_json(entity_id, key_pair, &mut redacted)?;
+
+    object.insert("signatures".into(), mem::take(redacted.get_mut("signatures").unwrap()));
+
+    Ok(())
+}
+
+/// Hashes and signs an event with a signer whose signing operation is asynchronous.
+///
+/// This works like [`hash_and_sign_event`], but lets the signature be created outside of the
+/// process, e.g. by a separate signing service. See [`AsyncSigner`] for details.
+///
+/// # Errors
+///
+/// Returns an error if the event is malformed, as described for [`hash_and_sign_event`], or if
+/// the signer fails to sign the event.
+pub async fn hash_and_sign_event_async<S>(
+    entity_id: &str,
+    signer: &S,
+    object: &mut CanonicalJsonObject,
+    version: &RoomVersionId,
+) -> Result<(), Error>
+where
+    S: AsyncSigner + ?Sized,
+{
+    insert_content_hash(object)?;
+
+    let mut redacted = redact(object, version)?;
+
+    sign_json_async(entity_id, signer, &mut redacted).await?;
+
+    object.insert("signatures".into(), mem::take(redacted.get_mut("signatures").unwrap()));
+
+    Ok(())
+}
+
+/// Adds the content hash of an event to its `hashes`.
+fn insert_content_hash(object: &mut CanonicalJsonObject) -> Result<(), Error> {
     let hash = content_hash(object)?;
 
     let hashes_value = object
@@ -508,12 +572,6 @@ where
         _ => return Err(JsonError::not_of_type("hashes", JsonType::Object)),
     };
 
-    let mut redacted = redact(object, version)?;
-
-    sign_json(entity_id, key_pair, &mut redacted)?;
-
-    object.insert("signatures".into(), mem::take(redacted.get_mut("signatures").unwrap()));
-
     Ok(())
 }
 
@@ -590,31 +648,188 @@ pub fn verify_event(
     version: &RoomVersionId,
 ) -> Result<Verified, Error> {
     let redacted = redact(object, version)?;
+    let hash = event_hash(object)?;
+    let signatures = event_signatures_to_check(public_key_map, object, version)?;
+    let canonical_json = from_json_str(&canonical_json(&redacted)?).map_err(JsonError::from)?;
+
+    for SignatureAndPubkey { signature, public_key } in signatures {
+        let verify = |config: Config| {
+            let signature_bytes = decode_config(signature, config)
+                .map_err(|e| ParseError::base64("signature", signature, e))?;
+
+            let public_key_bytes = decode_config(public_key, config)
+                .map_err(|e| ParseError::base64("public key", public_key, e))?;
+
+            verify_json_with(&Ed25519Verifier, &public_key_bytes, &signature_bytes, &canonical_json)
+        };
+
+        #[cfg(feature = "compat")]
+        also_try_forgiving_base64(STANDARD_NO_PAD, verify)?;
+        #[cfg(not(feature = "compat"))]
+        verify(STANDARD_NO_PAD)?;
+    }
+
+    let calculated_hash = content_hash(object)?;
+
+    if *hash == calculated_hash {
+        Ok(Verified::All)
+    } else {
+        Ok(Verified::Signatures)
+    }
+}
+
+/// Verifies the signatures and content hashes of many events at once.
+///
+/// This gives the same results as calling [`verify_event`] on each of the events, but checks the
+/// signatures of all events with a single batched Ed25519 verification, which is a lot faster
+/// than checking them one by one. Signatures with a small-order or non-canonically encoded
+/// point, which batched verification could accept although [`verify_event`] rejects them, are
+/// still checked one by one.
+///
+/// If the batch contains an invalid signature, it is split up until the events with invalid
+/// signatures are found, and those are checked with [`verify_event`] to get the precise error.
+///
+/// # Parameters
+///
+/// * public_key_map: A map from entity identifiers to a map from key identifiers to public keys.
+///   See [`verify_event`] for details.
+/// * events: The JSON objects of the events that were signed, with the room version of each.
+///
+/// Returns the result of the verification of each event, in the same order as `events`.
+pub fn verify_events_batch(
+    public_key_map: &PublicKeyMap,
+    events: &[(CanonicalJsonObject, RoomVersionId)],
+) -> Vec<Result<Verified, Error>> {
+    let mut results: Vec<Option<Result<Verified, Error>>> = events.iter().map(|_| None).collect();
+    let mut prepared = Vec::new();
+
+    for (index, (object, version)) in events.iter().enumerate() {
+        match PreparedEvent::new(public_key_map, object, version) {
+            Ok(event) => prepared.push((index, event)),
+            // Let `verify_event` report the error, or accept the event if it only failed because
+            // of the stricter Base64 decoding used here.
+            Err(_) => results[index] = Some(verify_event(public_key_map, object, version)),
+        }
+    }
+
+    verify_prepared_events(public_key_map, events, &prepared, &mut results);
+
+    results.into_iter().map(|result| result.expect("every event was verified")).collect()
+}
+
+/// An event whose signatures are ready to be checked with batched verification.
+struct PreparedEvent {
+    /// The canonical JSON of the redacted event, i.e. the signed message.
+    message: String,
+
+    /// The decoded public key and signature of each signature to check.
+    signatures: Vec<(Vec<u8>, Vec<u8>)>,
+
+    /// The result of the verification if all signatures are valid.
+    verified: Verified,
+}
+
+impl PreparedEvent {
+    fn new(
+        public_key_map: &PublicKeyMap,
+        object: &CanonicalJsonObject,
+        version: &RoomVersionId,
+    ) -> Result<Self, Error> {
+        let redacted = redact(object, version)?;
+        let hash = event_hash(object)?;
+
+        let signatures = event_signatures_to_check(public_key_map, object, version)?
+            .into_iter()
+            .map(|SignatureAndPubkey { signature, public_key }| {
+                let public_key_bytes = decode_config(public_key, STANDARD_NO_PAD)
+                    .map_err(|e| ParseError::base64("public key", public_key, e))?;
+                let signature_bytes = decode_config(signature, STANDARD_NO_PAD)
+                    .map_err(|e| ParseError::base64("signature", signature, e))?;
+
+                Ok((public_key_bytes, signature_bytes))
+            })
+            .collect::<Result<_, Error>>()?;
 
-    let hash = match object.get("hashes") {
+        let verified =
+            if *hash == content_hash(object)? { Verified::All } else { Verified::Signatures };
+
+        Ok(Self { message: canonical_json(&redacted)?, signatures, verified })
+    }
+}
+
+/// Checks the signatures of `prepared` in a batch, splitting it up if verification fails.
+fn verify_prepared_events(
+    public_key_map: &PublicKeyMap,
+    events: &[(CanonicalJsonObject, RoomVersionId)],
+    prepared: &[(usize, PreparedEvent)],
+    results: &mut [Option<Result<Verified, Error>>],
+) {
+    let signatures: Vec<_> = prepared
+        .iter()
+        .flat_map(|(_, event)| {
+            event.signatures.iter().map(move |(public_key, signature)| {
+                (event.message.as_bytes(), public_key.as_slice(), signature.as_slice())
+            })
+        })
+        .collect();
+
+    if signatures.is_empty() || verify_ed25519_batch(&signatures).is_ok() {
+        for (index, event) in prepared {
+            results[*index] = Some(Ok(event.verified.clone()));
+        }
+        return;
+    }
+
+    match prepared {
+        [] => {}
+        [(index, _)] => {
+            let (object, version) = &events[*index];
+            results[*index] = Some(verify_event(public_key_map, object, version));
+        }
+        _ => {
+            let (left, right) = prepared.split_at(prepared.len() / 2);
+            verify_prepared_events(public_key_map, events, left, results);
+            verify_prepared_events(public_key_map, events, right, results);
+        }
+    }
+}
+
+/// Extracts the SHA-256 content hash of an event.
+fn event_hash(object: &CanonicalJsonObject) -> Result<&str, Error> {
+    match object.get("hashes") {
         Some(hashes_value) => match hashes_value {
             CanonicalJsonValue::Object(hashes) => match hashes.get("sha256") {
                 Some(hash_value) => match hash_value {
-                    CanonicalJsonValue::String(hash) => hash,
-                    _ => return Err(JsonError::not_of_type("sha256 hash", JsonType::String)),
+                    CanonicalJsonValue::String(hash) => Ok(hash),
+                    _ => Err(JsonError::not_of_type("sha256 hash", JsonType::String)),
                 },
-                None => return Err(JsonError::not_of_type("hashes", JsonType::Object)),
+                None => Err(JsonError::not_of_type("hashes", JsonType::Object)),
             },
-            _ => return Err(JsonError::field_missing_from_object("sha256")),
+            _ => Err(JsonError::field_missing_from_object("sha256")),
         },
-        None => return Err(JsonError::field_missing_from_object("hashes")),
-    };
+        None => Err(JsonError::field_missing_from_object("hashes")),
+    }
+}
 
+/// Selects the signatures of an event that need to be checked, with the public key to check each
+/// one with.
+///
+/// For every server that must have signed the event, the first of its public keys that the event
+/// has a signature for is used.
+fn event_signatures_to_check<'a>(
+    public_key_map: &'a PublicKeyMap,
+    object: &'a CanonicalJsonObject,
+    version: &RoomVersionId,
+) -> Result<Vec<SignatureAndPubkey<'a>>, Error> {
     let signature_map = match object.get("signatures") {
         Some(CanonicalJsonValue::Object(signatures)) => signatures,
         Some(_) => return Err(JsonError::not_of_type("signatures", JsonType::Object)),
         None => return Err(JsonError::field_missing_from_object("signatures")),
     };
 
-    let servers_to_check = servers_to_check_signatures(object, version)?;
-    let canonical_json = from_json_str(&canonical_json(&redacted)?).map_err(JsonError::from)?;
+    let mut signatures = Vec::new();
 
-    for entity_id in servers_to_check {
+    for entity_id in servers_to_check_signatures(object, version)? {
         let signature_set = match signature_map.get(entity_id.as_str()) {
             Some(CanonicalJsonValue::Object(set)) => set,
             Some(_) => {
@@ -637,52 +852,31 @@ pub fn verify_event(
             }
 
             if let Some(signature) = signature_set.get(key_id) {
-                maybe_signature_and_public_key = Some(SignatureAndPubkey { signature, public_key });
+                maybe_signature_and_public_key = Some((signature, public_key));
 
                 break;
             }
         }
 
-        let signature_and_pubkey = match maybe_signature_and_public_key {
+        let (signature, public_key) = match maybe_signature_and_public_key {
             Some(value) => value,
             None => return Err(VerificationError::UnknownPublicKeysForSignature.into()),
         };
 
-        let signature = match signature_and_pubkey.signature {
+        let signature = match signature {
             CanonicalJsonValue::String(signature) => signature,
             _ => return Err(JsonError::not_of_type("signature", JsonType::String)),
         };
 
-        let public_key = signature_and_pubkey.public_key;
-
-        let verify = |config: Config| {
-            let signature_bytes = decode_config(signature, config)
-                .map_err(|e| ParseError::base64("signature", signature, e))?;
-
-            let public_key_bytes = decode_config(&public_key, config)
-                .map_err(|e| ParseError::base64("public key", public_key, e))?;
-
-            verify_json_with(&Ed25519Verifier, &public_key_bytes, &signature_bytes, &canonical_json)
-        };
-
-        #[cfg(feature = "compat")]
-        also_try_forgiving_base64(STANDARD_NO_PAD, verify)?;
-        #[cfg(not(feature = "compat"))]
-        verify(STANDARD_NO_PAD)?;
+        signatures.push(SignatureAndPubkey { signature, public_key });
     }
 
-    let calculated_hash = content_hash(object)?;
-
-    if *hash == calculated_hash {
-        Ok(Verified::All)
-    } else {
-        Ok(Verified::Signatures)
-    }
+    Ok(signatures)
 }
 
 struct SignatureAndPubkey<'a> {
-    signature: &'a CanonicalJsonValue,
-    public_key: &'a String,
+    signature: &'a str,
+    public_key: &'a str,
 }
 
 /// Internal implementation detail of the canonical JSON algorithm.
@@ -859,15 +1053,20 @@ mod tests {
         convert::{TryFrom, TryInto},
     };
 
+    use async_trait::async_trait;
     use base64::{encode_config, STANDARD_NO_PAD};
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
     use ruma_identifiers::{RoomVersionId, ServerSigningKeyId, SigningKeyAlgorithm};
-    use ruma_serde::CanonicalJsonValue;
+    use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
     use serde_json::json;
+    use sha2::{Digest, Sha512};
+    use tokio::sync::{mpsc, oneshot};
 
-    use super::canonical_json;
+    use super::{canonical_json, redact};
     use crate::{
-        sign_json, verify_event, Ed25519KeyPair, Error, PublicKeyMap, PublicKeySet,
-        VerificationError, Verified,
+        hash_and_sign_event, hash_and_sign_event_async, sign_json, sign_json_async, verify_event,
+        verify_events_batch, AsyncSigner, Ed25519KeyPair, Error, InProcessSigner, KeyPair,
+        PublicKeyMap, PublicKeySet, Signature, VerificationError, Verified,
     };
 
     #[test]
@@ -1080,6 +1279,243 @@ mod tests {
         };
     }
 
+    #[test]
+    fn verify_events_batch_reports_each_event() {
+        let key_pair_sender = generate_key_pair();
+        let key_pair_other = generate_key_pair();
+
+        let event = |body: &str, sender: &str, key_pair: &Ed25519KeyPair| {
+            let mut object = serde_json::from_value(json!({
+                "auth_events": [],
+                "content": { "body": body },
+                "depth": 3,
+                "origin": "domain",
+                "origin_server_ts": 1_000_000,
+                "prev_events": [],
+                "room_id": "!x:domain",
+                "sender": format!("@name:{}", sender),
+                "type": "X",
+            }))
+            .unwrap();
+            hash_and_sign_event(sender, key_pair, &mut object, &RoomVersionId::V6).unwrap();
+            object
+        };
+
+        let valid = event("valid", "domain-sender", &key_pair_sender);
+
+        // The content no longer matches the hash, but the signatures are still valid
+        let mut redacted = event("redacted", "domain-sender", &key_pair_sender);
+        redacted.insert("content".to_owned(), CanonicalJsonValue::Object(BTreeMap::new()));
+
+        // Signed with a key that doesn't match the public key of the sender's server
+        let forged = event("forged", "domain-sender", &key_pair_other);
+
+        // The public key of the sender's server is unknown
+        let unknown = event("unknown", "domain-other", &key_pair_other);
+
+        let mut public_key_map = BTreeMap::new();
+        add_key_to_map(&mut public_key_map, "domain-sender", &key_pair_sender);
+
+        let events: Vec<_> = vec![valid.clone(), redacted, forged, valid, unknown]
+            .into_iter()
+            .map(|object| (object, RoomVersionId::V6))
+            .collect();
+        let results = verify_events_batch(&public_key_map, &events);
+
+        assert_eq!(results.len(), 5);
+        assert!(matches!(results[0], Ok(Verified::All)));
+        assert!(matches!(results[1], Ok(Verified::Signatures)));
+        assert!(matches!(results[2], Err(Error::Verification(VerificationError::Signature(_)))));
+        assert!(matches!(results[3], Ok(Verified::All)));
+        assert!(matches!(
+            results[4],
+            Err(Error::Verification(VerificationError::PublicKeyNotFound(_)))
+        ));
+
+        for ((object, version), result) in events.iter().zip(&results) {
+            let expected = verify_event(&public_key_map, object, version);
+            assert_eq!(format!("{:?}", result), format!("{:?}", expected));
+        }
+    }
+
+    #[test]
+    fn verify_events_batch_rejects_signatures_with_small_order_r() {
+        let key_pair = generate_key_pair();
+        let mut object = serde_json::from_value(json!({
+            "auth_events": [],
+            "content": { "body": "forged" },
+            "depth": 3,
+            "origin": "domain",
+            "origin_server_ts": 1_000_000,
+            "prev_events": [],
+            "room_id": "!x:domain",
+            "sender": "@name:domain-sender",
+            "type": "X",
+        }))
+        .unwrap();
+        hash_and_sign_event("domain-sender", &key_pair, &mut object, &RoomVersionId::V6).unwrap();
+        let message = canonical_json(&redact(&object, &RoomVersionId::V6).unwrap()).unwrap();
+
+        // A key pair with a known secret scalar, to build signatures by hand.
+        let secret = Scalar::from(0x1234_5678_u64);
+        let public_key = (&secret * &ED25519_BASEPOINT_TABLE).compress();
+
+        let mut public_key_map = BTreeMap::new();
+        let mut key_set = PublicKeySet::new();
+        key_set
+            .insert("ed25519:1".to_owned(), encode_config(public_key.as_bytes(), STANDARD_NO_PAD));
+        public_key_map.insert("domain-sender".to_owned(), key_set);
+
+        // Signatures with `s = k * secret`, so that `[s]B - [k]A` is the identity. The single
+        // verification equation only holds if `R` is the canonical encoding of the identity,
+        // while batched verification would accept any encoding of a small-order point.
+        let small_order_rs = [
+            // The identity, encoded with `y = p + 1`.
+            {
+                let mut r = [0xff; 32];
+                r[0] = 0xee;
+                r[31] = 0x7f;
+                r
+            },
+            // The point of order 2, `(0, -1)`.
+            {
+                let mut r = [0xff; 32];
+                r[0] = 0xec;
+                r[31] = 0x7f;
+                r
+            },
+        ];
+
+        for r in &small_order_rs {
+            let k = Scalar::from_hash(
+                Sha512::new().chain(r).chain(public_key.as_bytes()).chain(message.as_bytes()),
+            );
+            let mut signature = r.to_vec();
+            signature.extend_from_slice((k * secret).as_bytes());
+
+            let mut forged = object.clone();
+            let signatures = match forged.get_mut("signatures") {
+                Some(CanonicalJsonValue::Object(signatures)) => signatures,
+                _ => unreachable!(),
+            };
+            signatures.insert(
+                "domain-sender".to_owned(),
+                CanonicalJsonValue::Object(
+                    vec![(
+                        "ed25519:1".to_owned(),
+                        CanonicalJsonValue::String(encode_config(&signature, STANDARD_NO_PAD)),
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+            );
+
+            assert!(verify_event(&public_key_map, &forged, &RoomVersionId::V6).is_err());
+
+            // Batched verification is randomized, so try it a few times.
+            let events = vec![(forged, RoomVersionId::V6); 2];
+            for _ in 0..16 {
+                for result in verify_events_batch(&public_key_map, &events) {
+                    assert!(matches!(
+                        result,
+                        Err(Error::Verification(VerificationError::Signature(_)))
+                    ));
+                }
+            }
+        }
+    }
+
+    /// A signer that sends messages to a task owning the key pair, like a signing daemon.
+    struct ChannelSigner {
+        sender: mpsc::UnboundedSender<(Vec<u8>, oneshot::Sender<Signature>)>,
+    }
+
+    impl ChannelSigner {
+        fn new(key_pair: Ed25519KeyPair) -> Self {
+            let (sender, mut receiver) =
+                mpsc::unbounded_channel::<(Vec<u8>, oneshot::Sender<Signature>)>();
+            tokio::spawn(async move {
+                while let Some((message, response)) = receiver.recv().await {
+                    let _ = response.send(key_pair.sign(&message));
+                }
+            });
+
+            Self { sender }
+        }
+    }
+
+    #[async_trait]
+    impl AsyncSigner for ChannelSigner {
+        async fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+            let (response, receiver) = oneshot::channel();
+            self.sender.send((message.to_vec(), response)).map_err(|e| Error::Signer(e.into()))?;
+            receiver.await.map_err(|e| Error::Signer(e.into()))
+        }
+    }
+
+    struct FailingSigner;
+
+    #[async_trait]
+    impl AsyncSigner for FailingSigner {
+        async fn sign(&self, _message: &[u8]) -> Result<Signature, Error> {
+            Err(Error::Signer("signing daemon is unreachable".into()))
+        }
+    }
+
+    fn unsigned_event() -> CanonicalJsonObject {
+        serde_json::from_value(json!({
+            "auth_events": [],
+            "content": {},
+            "depth": 3,
+            "origin": "domain",
+            "origin_server_ts": 1_000_000,
+            "prev_events": [],
+            "room_id": "!x:domain",
+            "sender": "@a:domain",
+            "type": "X",
+            "unsigned": {
+                "age_ts": 1_000_000
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn sign_async_matches_sign() {
+        let key_content = Ed25519KeyPair::generate().unwrap();
+        let key_pair = Ed25519KeyPair::from_der(&key_content, "1".to_owned()).unwrap();
+        let signer =
+            ChannelSigner::new(Ed25519KeyPair::from_der(&key_content, "1".to_owned()).unwrap());
+
+        let mut object = unsigned_event();
+        let mut object_async = unsigned_event();
+        sign_json("domain", &key_pair, &mut object).unwrap();
+        sign_json_async("domain", &signer, &mut object_async).await.unwrap();
+        assert_eq!(object, object_async);
+
+        let mut event = unsigned_event();
+        let mut event_async = unsigned_event();
+        hash_and_sign_event("domain", &key_pair, &mut event, &RoomVersionId::V6).unwrap();
+        hash_and_sign_event_async(
+            "domain",
+            &InProcessSigner(key_pair),
+            &mut event_async,
+            &RoomVersionId::V6,
+        )
+        .await
+        .unwrap();
+        assert_eq!(event, event_async);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn sign_async_reports_signer_errors() {
+        let mut object = unsigned_event();
+        let result = sign_json_async("domain", &FailingSigner, &mut object).await;
+
+        assert!(matches!(result, Err(Error::Signer(_))));
+        assert_eq!(object, unsigned_event());
+    }
+
     fn generate_key_pair() -> Ed25519KeyPair {
         let key_content = Ed25519KeyPair::generate().unwrap();
         Ed25519KeyPair::from_der(&key_content, "1".to_owned())
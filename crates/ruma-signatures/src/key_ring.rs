@@ -0,0 +1,447 @@
+//! A store of the signing keys of other homeservers.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    convert::TryFrom,
+};
+
+use js_int::UInt;
+use ruma_common::MilliSecondsSinceUnixEpoch;
+use ruma_federation_api::discovery::ServerSigningKeys;
+use ruma_identifiers::{RoomVersionId, ServerName};
+use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
+
+use crate::{
+    verify_event, verify_json, Error, JsonError, JsonType, PublicKeyMap, PublicKeySet,
+    VerificationError, Verified,
+};
+
+/// A public key of a homeserver, with the time until which it may be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ValidKey {
+    /// The Unpadded Base64 encoded key.
+    key: String,
+
+    /// The `valid_until_ts` of a current key, or the `expired_ts` of an old key.
+    valid_until_ts: MilliSecondsSinceUnixEpoch,
+}
+
+/// A store of the signing keys of homeservers.
+///
+/// Keys are added from the `ServerSigningKeys` returned by the
+/// [`get_server_keys`](ruma_federation_api::discovery::get_server_keys) endpoint of a server
+/// itself, or by the
+/// [`get_remote_server_keys`](ruma_federation_api::discovery::get_remote_server_keys) endpoint of a
+/// notary server. Their signatures are checked before they are stored.
+///
+/// The stored keys can then be used to verify events, honouring the validity of the keys for room
+/// versions that enforce it.
+#[derive(Clone, Debug, Default)]
+pub struct KeyRing {
+    servers: BTreeMap<Box<ServerName>, BTreeMap<String, ValidKey>>,
+}
+
+impl KeyRing {
+    /// Creates an empty `KeyRing`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key without checking any signature.
+    ///
+    /// This is meant for keys that are trusted through configuration, like the keys of notary
+    /// servers.
+    pub fn add_trusted_key(
+        &mut self,
+        server_name: &ServerName,
+        key_id: &str,
+        key: String,
+        valid_until_ts: MilliSecondsSinceUnixEpoch,
+    ) {
+        self.insert(server_name, key_id.to_owned(), ValidKey { key, valid_until_ts });
+    }
+
+    /// Adds the keys that a server published about itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keys are not signed by the server with one of its `verify_keys`.
+    /// No key is added in that case.
+    pub fn add_server_keys(&mut self, server_keys: ServerSigningKeys) -> Result<(), Error> {
+        let object = to_canonical_object(&server_keys)?;
+
+        let self_keys = verify_keys(&server_keys);
+        verify_signatures_of(&object, &server_keys.server_name, &self_keys)?;
+
+        self.insert_server_keys(server_keys);
+        Ok(())
+    }
+
+    /// Adds the keys of a server that were returned by the given notary server.
+    ///
+    /// The keys of the notary server must already be part of the key ring.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keys are not signed by the server with one of its `verify_keys` or
+    /// not signed by the notary server with one of its known keys. No key is added in that case.
+    pub fn add_notary_keys(
+        &mut self,
+        notary: &ServerName,
+        server_keys: ServerSigningKeys,
+    ) -> Result<(), Error> {
+        let object = to_canonical_object(&server_keys)?;
+
+        let self_keys = verify_keys(&server_keys);
+        verify_signatures_of(&object, &server_keys.server_name, &self_keys)?;
+
+        if notary != server_keys.server_name {
+            let notary_keys = self
+                .servers
+                .get(notary)
+                .map(|keys| keys.iter().map(|(id, key)| (id.clone(), key.key.clone())).collect())
+                .ok_or_else(|| VerificationError::public_key_not_found(notary))?;
+            verify_signatures_of(&object, notary, &notary_keys)?;
+        }
+
+        self.insert_server_keys(server_keys);
+        Ok(())
+    }
+
+    /// Returns `true` if the key ring contains a key of the given server that is valid at the
+    /// given time.
+    pub fn has_valid_key(&self, server_name: &ServerName, ts: MilliSecondsSinceUnixEpoch) -> bool {
+        self.servers
+            .get(server_name)
+            .map_or(false, |keys| keys.values().any(|key| key.valid_until_ts >= ts))
+    }
+
+    /// Returns all keys of the key ring, regardless of their validity.
+    pub fn public_key_map(&self) -> PublicKeyMap {
+        self.filtered_public_key_map(|_| true)
+    }
+
+    /// Returns the keys of the key ring that are valid at the given time.
+    pub fn public_key_map_valid_at(&self, ts: MilliSecondsSinceUnixEpoch) -> PublicKeyMap {
+        self.filtered_public_key_map(|key| key.valid_until_ts >= ts)
+    }
+
+    /// Returns the keys of the key ring that may be used to verify the given event.
+    ///
+    /// For room versions that enforce the validity of keys, only the keys that are valid at the
+    /// `origin_server_ts` of the event are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the room version enforces the validity of keys and the event has no
+    /// valid `origin_server_ts`.
+    pub fn public_key_map_for_event(
+        &self,
+        object: &CanonicalJsonObject,
+        version: &RoomVersionId,
+    ) -> Result<PublicKeyMap, Error> {
+        if !enforce_key_validity(version) {
+            return Ok(self.public_key_map());
+        }
+
+        let origin_server_ts = match object.get("origin_server_ts") {
+            Some(CanonicalJsonValue::Integer(ts)) => UInt::try_from(i64::from(*ts))
+                .map_err(|_| JsonError::not_of_type("origin_server_ts", JsonType::Integer))?,
+            Some(_) => return Err(JsonError::not_of_type("origin_server_ts", JsonType::Integer)),
+            None => return Err(JsonError::field_missing_from_object("origin_server_ts")),
+        };
+
+        Ok(self.public_key_map_valid_at(MilliSecondsSinceUnixEpoch(origin_server_ts)))
+    }
+
+    /// Verifies the signatures and hashes of an event with the keys of the key ring.
+    ///
+    /// See [`verify_event`] for details.
+    pub fn verify_event(
+        &self,
+        object: &CanonicalJsonObject,
+        version: &RoomVersionId,
+    ) -> Result<Verified, Error> {
+        verify_event(&self.public_key_map_for_event(object, version)?, object, version)
+    }
+
+    fn insert_server_keys(&mut self, server_keys: ServerSigningKeys) {
+        let server_name = server_keys.server_name;
+
+        for (key_id, verify_key) in server_keys.verify_keys {
+            let key = ValidKey { key: verify_key.key, valid_until_ts: server_keys.valid_until_ts };
+            self.insert(&server_name, key_id.to_string(), key);
+        }
+
+        for (key_id, old_verify_key) in server_keys.old_verify_keys {
+            let key =
+                ValidKey { key: old_verify_key.key, valid_until_ts: old_verify_key.expired_ts };
+            self.insert(&server_name, key_id.to_string(), key);
+        }
+    }
+
+    /// Adds a key, keeping the existing one if it is valid for longer.
+    fn insert(&mut self, server_name: &ServerName, key_id: String, key: ValidKey) {
+        match self.servers.entry(server_name.to_owned()).or_default().entry(key_id) {
+            Entry::Vacant(entry) => {
+                entry.insert(key);
+            }
+            Entry::Occupied(mut entry) => {
+                if entry.get().valid_until_ts < key.valid_until_ts {
+                    entry.insert(key);
+                }
+            }
+        }
+    }
+
+    fn filtered_public_key_map(&self, filter: impl Fn(&ValidKey) -> bool) -> PublicKeyMap {
+        self.servers
+            .iter()
+            .map(|(server_name, keys)| {
+                let keys = keys
+                    .iter()
+                    .filter(|(_, key)| filter(key))
+                    .map(|(id, key)| (id.clone(), key.key.clone()))
+                    .collect();
+                (server_name.to_string(), keys)
+            })
+            .collect()
+    }
+}
+
+/// Whether the room version requires keys to be valid at the `origin_server_ts` of events.
+fn enforce_key_validity(version: &RoomVersionId) -> bool {
+    !matches!(
+        version,
+        RoomVersionId::V1 | RoomVersionId::V2 | RoomVersionId::V3 | RoomVersionId::V4
+    )
+}
+
+fn to_canonical_object(server_keys: &ServerSigningKeys) -> Result<CanonicalJsonObject, Error> {
+    Ok(serde_json::to_value(server_keys)
+        .and_then(serde_json::from_value)
+        .map_err(JsonError::from)?)
+}
+
+fn verify_keys(server_keys: &ServerSigningKeys) -> PublicKeySet {
+    server_keys.verify_keys.iter().map(|(id, key)| (id.to_string(), key.key.clone())).collect()
+}
+
+/// Verifies the signatures of `entity` on `object`, ignoring the signatures of other entities.
+fn verify_signatures_of(
+    object: &CanonicalJsonObject,
+    entity: &ServerName,
+    public_keys: &PublicKeySet,
+) -> Result<(), Error> {
+    let signature_set = match object.get("signatures") {
+        Some(CanonicalJsonValue::Object(signatures)) => signatures.get(entity.as_str()),
+        Some(_) => return Err(JsonError::not_of_type("signatures", JsonType::Object)),
+        None => return Err(JsonError::field_missing_from_object("signatures")),
+    };
+
+    let signature_set = match signature_set {
+        Some(CanonicalJsonValue::Object(set)) if !set.is_empty() => set,
+        Some(CanonicalJsonValue::Object(_)) | None => {
+            return Err(VerificationError::signature_not_found(entity))
+        }
+        Some(_) => {
+            return Err(JsonError::not_multiples_of_type("signature sets", JsonType::Object))
+        }
+    };
+
+    let mut signatures = BTreeMap::new();
+    signatures.insert(entity.to_string(), CanonicalJsonValue::Object(signature_set.clone()));
+    let mut object = object.clone();
+    object.insert("signatures".to_owned(), CanonicalJsonValue::Object(signatures));
+
+    let mut public_key_map = BTreeMap::new();
+    public_key_map.insert(entity.to_string(), public_keys.clone());
+
+    verify_json(&public_key_map, &object)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use base64::{encode_config, STANDARD_NO_PAD};
+    use js_int::uint;
+    use ruma_common::MilliSecondsSinceUnixEpoch;
+    use ruma_federation_api::discovery::{ServerSigningKeys, VerifyKey};
+    use ruma_identifiers::{server_name, RoomVersionId, ServerName, ServerSigningKeyId};
+    use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
+
+    use super::KeyRing;
+    use crate::{hash_and_sign_event, sign_json, Ed25519KeyPair, Error, VerificationError};
+
+    fn generate_key_pair() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "1".to_owned()).unwrap()
+    }
+
+    fn ts(ts: u32) -> MilliSecondsSinceUnixEpoch {
+        MilliSecondsSinceUnixEpoch(ts.into())
+    }
+
+    /// Builds the keys of `server_name`, signed by each of `signers`.
+    fn server_keys(
+        server_name: &ServerName,
+        key_pair: &Ed25519KeyPair,
+        valid_until_ts: MilliSecondsSinceUnixEpoch,
+        signers: &[(&ServerName, &Ed25519KeyPair)],
+    ) -> ServerSigningKeys {
+        let mut server_keys = ServerSigningKeys::new(server_name.to_owned(), valid_until_ts);
+        server_keys.verify_keys.insert(
+            Box::<ServerSigningKeyId>::try_from("ed25519:1").unwrap(),
+            VerifyKey::new(encode_config(key_pair.public_key(), STANDARD_NO_PAD)),
+        );
+
+        let mut object = match ruma_serde::to_canonical_value(&server_keys).unwrap() {
+            CanonicalJsonValue::Object(object) => object,
+            _ => unreachable!(),
+        };
+        for (signer, key_pair) in signers {
+            sign_json(signer.as_str(), *key_pair, &mut object).unwrap();
+        }
+
+        serde_json::from_value(serde_json::to_value(object).unwrap()).unwrap()
+    }
+
+    fn event(key_pair: &Ed25519KeyPair, origin_server_ts: u32) -> CanonicalJsonObject {
+        let mut object = serde_json::from_value(serde_json::json!({
+            "auth_events": [],
+            "content": {},
+            "depth": 3,
+            "origin": "domain",
+            "origin_server_ts": origin_server_ts,
+            "prev_events": [],
+            "room_id": "!x:domain",
+            "sender": "@a:domain",
+            "type": "X",
+        }))
+        .unwrap();
+        hash_and_sign_event("domain", key_pair, &mut object, &RoomVersionId::V6).unwrap();
+        object
+    }
+
+    #[test]
+    fn add_self_signed_keys() {
+        let key_pair = generate_key_pair();
+        let domain = server_name!("domain");
+
+        let mut key_ring = KeyRing::new();
+        key_ring
+            .add_server_keys(server_keys(domain, &key_pair, ts(2000), &[(domain, &key_pair)]))
+            .unwrap();
+
+        assert!(key_ring.has_valid_key(domain, ts(2000)));
+        assert!(!key_ring.has_valid_key(domain, ts(2001)));
+        assert_eq!(key_ring.public_key_map()["domain"].len(), 1);
+    }
+
+    #[test]
+    fn reject_unsigned_keys() {
+        let key_pair = generate_key_pair();
+        let domain = server_name!("domain");
+
+        let mut key_ring = KeyRing::new();
+        let result = key_ring.add_server_keys(server_keys(domain, &key_pair, ts(2000), &[]));
+
+        assert!(matches!(
+            result,
+            Err(Error::Verification(VerificationError::SignatureNotFound(_)))
+        ));
+        assert!(key_ring.public_key_map().is_empty());
+    }
+
+    #[test]
+    fn reject_keys_signed_by_other_key() {
+        let domain = server_name!("domain");
+
+        let mut key_ring = KeyRing::new();
+        let result = key_ring.add_server_keys(server_keys(
+            domain,
+            &generate_key_pair(),
+            ts(2000),
+            &[(domain, &generate_key_pair())],
+        ));
+
+        assert!(result.is_err());
+        assert!(key_ring.public_key_map().is_empty());
+    }
+
+    #[test]
+    fn add_notary_keys() {
+        let key_pair = generate_key_pair();
+        let notary_key_pair = generate_key_pair();
+        let domain = server_name!("domain");
+        let notary = server_name!("notary");
+
+        let mut key_ring = KeyRing::new();
+        let keys = server_keys(
+            domain,
+            &key_pair,
+            ts(2000),
+            &[(domain, &key_pair), (notary, &notary_key_pair)],
+        );
+
+        // The notary's keys are unknown
+        assert!(matches!(
+            key_ring.add_notary_keys(notary, keys.clone()),
+            Err(Error::Verification(VerificationError::PublicKeyNotFound(_)))
+        ));
+
+        key_ring.add_trusted_key(
+            notary,
+            "ed25519:1",
+            encode_config(notary_key_pair.public_key(), STANDARD_NO_PAD),
+            ts(5000),
+        );
+        key_ring.add_notary_keys(notary, keys).unwrap();
+        assert!(key_ring.has_valid_key(domain, ts(2000)));
+
+        // Not signed by the notary
+        let keys = server_keys(
+            server_name!("other"),
+            &key_pair,
+            ts(2000),
+            &[(server_name!("other"), &key_pair)],
+        );
+        assert!(key_ring.add_notary_keys(notary, keys).is_err());
+    }
+
+    #[test]
+    fn verify_event_honours_key_validity() {
+        let key_pair = generate_key_pair();
+        let domain = server_name!("domain");
+
+        let mut key_ring = KeyRing::new();
+        key_ring
+            .add_server_keys(server_keys(domain, &key_pair, ts(2000), &[(domain, &key_pair)]))
+            .unwrap();
+
+        assert!(key_ring.verify_event(&event(&key_pair, 1000), &RoomVersionId::V6).is_ok());
+        assert!(matches!(
+            key_ring.verify_event(&event(&key_pair, 3000), &RoomVersionId::V6),
+            Err(Error::Verification(VerificationError::UnknownPublicKeysForSignature))
+        ));
+
+        // Room versions before 5 ignore `valid_until_ts`
+        assert!(key_ring.verify_event(&event(&key_pair, 3000), &RoomVersionId::V4).is_ok());
+    }
+
+    #[test]
+    fn longer_validity_is_kept() {
+        let key_pair = generate_key_pair();
+        let domain = server_name!("domain");
+
+        let mut key_ring = KeyRing::new();
+        key_ring
+            .add_server_keys(server_keys(domain, &key_pair, ts(2000), &[(domain, &key_pair)]))
+            .unwrap();
+        key_ring
+            .add_server_keys(server_keys(domain, &key_pair, ts(1000), &[(domain, &key_pair)]))
+            .unwrap();
+
+        assert!(key_ring.has_valid_key(domain, MilliSecondsSinceUnixEpoch(uint!(2000))));
+    }
+}
@@ -2,17 +2,23 @@
 
 use std::{
     collections::BTreeMap,
+    convert::TryFrom,
     fmt::{Debug, Formatter, Result as FmtResult},
 };
 
+use async_trait::async_trait;
+use base64::{decode_config, encode_config, STANDARD_NO_PAD};
 use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+#[cfg(feature = "federation-api")]
+use ruma_federation_api::discovery::VerifyKey;
+use ruma_identifiers::ServerSigningKeyId;
 
 use pkcs8::{
     der::{Decodable, Encodable},
     AlgorithmIdentifier, ObjectIdentifier, PrivateKeyInfo,
 };
 
-use crate::{signatures::Signature, Algorithm, Error, ParseError};
+use crate::{signatures::Signature, split_id, Algorithm, Error, ParseError, SplitError};
 
 /// A cryptographic key pair for digitally signing data.
 pub trait KeyPair: Sized {
@@ -24,10 +30,45 @@ pub trait KeyPair: Sized {
     fn sign(&self, message: &[u8]) -> Signature;
 }
 
+/// A signer whose signing operation is asynchronous.
+///
+/// This allows the private key to be kept outside of the process, e.g. in a separate signing
+/// service that is reached over a socket. It is used by [`sign_json_async`](crate::sign_json_async)
+/// and [`hash_and_sign_event_async`](crate::hash_and_sign_event_async).
+///
+/// To use a [`KeyPair`] that is held in memory, wrap it in an [`InProcessSigner`].
+#[async_trait]
+pub trait AsyncSigner: Sync {
+    /// Signs an arbitrary series of bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature could not be created. Errors of the signing backend can
+    /// be reported with [`Error::Signer`].
+    async fn sign(&self, message: &[u8]) -> Result<Signature, Error>;
+}
+
+/// An [`AsyncSigner`] that signs with a [`KeyPair`] held in memory.
+///
+/// This is mostly useful for tests and for services that don't keep their keys in a separate
+/// process, so they can use the same code path.
+#[derive(Debug)]
+#[allow(clippy::exhaustive_structs)]
+pub struct InProcessSigner<K>(pub K);
+
+#[async_trait]
+impl<K: KeyPair + Sync> AsyncSigner for InProcessSigner<K> {
+    async fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        Ok(self.0.sign(message))
+    }
+}
+
 pub const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new("1.3.101.112");
 
 /// An Ed25519 key pair.
 pub struct Ed25519KeyPair {
+    privkey: SecretKey,
+
     extended_privkey: ExpandedSecretKey,
 
     pubkey: PublicKey,
@@ -64,11 +105,27 @@ impl Ed25519KeyPair {
             }
         }
 
-        Ok(Self {
+        Ok(Self::from_secret_key(secret_key, version))
+    }
+
+    /// Creates a key pair from the 32 bytes of an Ed25519 seed, i.e. the raw private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the seed is not 32 bytes long.
+    pub fn from_seed(seed: &[u8], version: String) -> Result<Self, Error> {
+        let secret_key = SecretKey::from_bytes(seed).map_err(ParseError::SecretKey)?;
+
+        Ok(Self::from_secret_key(secret_key, version))
+    }
+
+    fn from_secret_key(secret_key: SecretKey, version: String) -> Self {
+        Self {
             extended_privkey: ExpandedSecretKey::from(&secret_key),
-            pubkey: derived_pubkey,
+            pubkey: PublicKey::from(&secret_key),
+            privkey: secret_key,
             version,
-        })
+        }
     }
 
     /// Initializes a new key pair.
@@ -155,6 +212,37 @@ impl Ed25519KeyPair {
     pub fn public_key(&self) -> &[u8] {
         self.pubkey.as_ref()
     }
+
+    /// Returns the seed of the key pair, i.e. the 32 bytes of the raw private key.
+    pub fn seed(&self) -> &[u8] {
+        self.privkey.as_bytes()
+    }
+
+    /// Returns the key ID of this key pair, e.g. `ed25519:1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version of the key pair is not a valid key name.
+    pub fn key_id(&self) -> Result<Box<ServerSigningKeyId>, Error> {
+        Box::<ServerSigningKeyId>::try_from(format!("{}:{}", Algorithm::Ed25519, self.version))
+            .map_err(|_| SplitError::InvalidVersion(self.version.clone()).into())
+    }
+
+    /// Returns the public key encoded as Unpadded Base64, as it is published by homeservers.
+    pub fn public_key_base64(&self) -> String {
+        encode_config(self.public_key(), STANDARD_NO_PAD)
+    }
+
+    /// Returns the entry for this key pair in the `verify_keys` of
+    /// [`ServerSigningKeys`](ruma_federation_api::discovery::ServerSigningKeys).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version of the key pair is not a valid key name.
+    #[cfg(feature = "federation-api")]
+    pub fn verify_key(&self) -> Result<(Box<ServerSigningKeyId>, VerifyKey), Error> {
+        Ok((self.key_id()?, VerifyKey::new(self.public_key_base64())))
+    }
 }
 
 impl KeyPair for Ed25519KeyPair {
@@ -177,6 +265,58 @@ impl Debug for Ed25519KeyPair {
     }
 }
 
+/// Reads key pairs from the contents of a signing key file, as used by Synapse and Dendrite.
+///
+/// Each non-empty line of the file contains one key, in the form
+/// `<algorithm> <version> <Unpadded Base64 encoded seed>`, e.g. `ed25519 a_bcDe <seed>`.
+///
+/// # Errors
+///
+/// Returns an error if a line is malformed, uses another algorithm than Ed25519 or contains an
+/// invalid seed.
+pub fn read_signing_keys(contents: &str) -> Result<Vec<Ed25519KeyPair>, Error> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let (algorithm, version, seed) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(algorithm), Some(version), Some(seed)) if parts.next().is_none() => {
+                    (algorithm, version, seed)
+                }
+                _ => return Err(ParseError::SigningKey(line.to_owned()).into()),
+            };
+
+            let (_, version) = split_id(&format!("{}:{}", algorithm, version))?;
+
+            // Some tools write the seed with padding.
+            let seed = seed.trim_end_matches('=');
+            let seed = decode_config(seed, STANDARD_NO_PAD)
+                .map_err(|e| ParseError::base64("seed", seed, e))?;
+
+            Ed25519KeyPair::from_seed(&seed, version)
+        })
+        .collect()
+}
+
+/// Writes key pairs in the format of a signing key file, as used by Synapse and Dendrite.
+///
+/// See [`read_signing_keys`] for the format.
+pub fn write_signing_keys(key_pairs: &[Ed25519KeyPair]) -> String {
+    key_pairs
+        .iter()
+        .map(|key_pair| {
+            format!(
+                "{} {} {}\n",
+                Algorithm::Ed25519,
+                key_pair.version,
+                encode_config(key_pair.seed(), STANDARD_NO_PAD)
+            )
+        })
+        .collect()
+}
+
 /// A map from entity names to sets of public keys for that entity.
 ///
 /// "Entity" is generally a homeserver, e.g. "example.com".
@@ -189,7 +329,8 @@ pub type PublicKeySet = BTreeMap<String, String>;
 
 #[cfg(test)]
 mod tests {
-    use super::Ed25519KeyPair;
+    use super::{read_signing_keys, write_signing_keys, Ed25519KeyPair};
+    use crate::{Error, SplitError};
 
     const RING_DOC: &[u8] = &[
         0x30, 0x53, 0x02, 0x01, 0x01, 0x30, 0x05, 0x06, 0x03, 0x2B, 0x65, 0x70, 0x04, 0x22, 0x04,
@@ -217,4 +358,51 @@ mod tests {
 
         assert_eq!(keypair.pubkey.as_bytes(), RING_PUBKEY);
     }
+
+    #[test]
+    fn signing_key_file() {
+        let contents = "ed25519 a_ABCD YZ7YJaYdMinX2CIDxg43SOnJEZY7AxWUGTqG7OYtc8A\n";
+        let key_pairs = read_signing_keys(contents).unwrap();
+
+        assert_eq!(key_pairs.len(), 1);
+        assert_eq!(key_pairs[0].version(), "a_ABCD");
+        assert_eq!(key_pairs[0].public_key(), RING_PUBKEY);
+        assert_eq!(key_pairs[0].key_id().unwrap().as_str(), "ed25519:a_ABCD");
+        assert_eq!(write_signing_keys(&key_pairs), contents);
+    }
+
+    #[test]
+    fn signing_key_file_roundtrip() {
+        let key_pairs = vec![
+            Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "1".to_owned()).unwrap(),
+            Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "2".to_owned()).unwrap(),
+        ];
+        let read = read_signing_keys(&write_signing_keys(&key_pairs)).unwrap();
+
+        assert_eq!(read.len(), 2);
+        for (read, key_pair) in read.iter().zip(&key_pairs) {
+            assert_eq!(read.version(), key_pair.version());
+            assert_eq!(read.public_key(), key_pair.public_key());
+        }
+    }
+
+    #[test]
+    fn invalid_signing_key_file() {
+        assert!(matches!(read_signing_keys("ed25519 a_ABCD"), Err(Error::Parse(_))));
+        assert!(matches!(
+            read_signing_keys("rsa a_ABCD YZ7YJaYdMinX2CIDxg43SOnJEZY7AxWUGTqG7OYtc8A"),
+            Err(Error::SplitError(SplitError::UnsupportedAlgorithm(_)))
+        ));
+        assert!(matches!(read_signing_keys("ed25519 a_ABCD YJ7YJaYd"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn key_pair_from_seed() {
+        let key_pair = Ed25519KeyPair::from_der(RING_DOC, "1".to_owned()).unwrap();
+        let from_seed = Ed25519KeyPair::from_seed(key_pair.seed(), "1".to_owned()).unwrap();
+
+        assert_eq!(from_seed.public_key(), RING_PUBKEY);
+        assert_eq!(from_seed.public_key_base64(), key_pair.public_key_base64());
+        assert!(Ed25519KeyPair::from_seed(&[0; 31], "1".to_owned()).is_err());
+    }
 }
@@ -33,6 +33,10 @@
 //! is the same. To hash and sign an event, use the `hash_and_sign_event` function. See the
 //! documentation of this function for more details and a full example of use.
 //!
+//! If the private keys are not held by the process, e.g. because they are kept by a separate
+//! signing service, implement the `AsyncSigner` trait and use the `sign_json_async` and
+//! `hash_and_sign_event_async` functions instead.
+//!
 //! # Verifying signatures and hashes
 //!
 //! When a homeserver receives data from another homeserver via the federation, it's necessary to
@@ -40,27 +44,60 @@
 //!
 //! To verify a signature on arbitrary JSON, use the `verify_json` function. To verify the
 //! signatures and hashes on an event, use the `verify_event` function. See the documentation for
-//! these respective functions for more details and full examples of use.
+//! these respective functions for more details and full examples of use. To verify many events at
+//! once, for example when joining a large room, use the `verify_events_batch` function.
+//!
+//! With the `cross-signing` feature, the `verify_device_trust` and `verify_user_trust` functions
+//! check the signatures of the cross-signing keys of users and their devices.
+//!
+//! With the `federation-api` feature, the `KeyRing` type stores the signing keys that homeservers
+//! publish and can verify events with the keys that are valid at the time they were sent.
+//!
+//! # Federation requests
+//!
+//! Requests between homeservers are authenticated with an `Authorization` header using the
+//! `X-Matrix` scheme. To add this header to an outgoing request, use the `sign_request` function.
+//! To check the headers of an incoming request, use the `verify_request` function.
 
 #![warn(missing_docs)]
 
 use ruma_serde::{AsRefStr, DisplayAsRefStr};
 
+#[cfg(feature = "cross-signing")]
+pub use cross_signing::{
+    sign_cross_signing_key, sign_device_keys, verify_cross_signing_key, verify_device_keys,
+    verify_device_trust, verify_user_trust, TrustChain,
+};
 pub use error::{Error, JsonError, JsonType, ParseError, SplitError, VerificationError};
 pub use functions::{
-    canonical_json, content_hash, hash_and_sign_event, redact, reference_hash, sign_json,
-    verify_event, verify_json,
+    canonical_json, content_hash, hash_and_sign_event, hash_and_sign_event_async, redact,
+    reference_hash, sign_json, sign_json_async, verify_event, verify_events_batch, verify_json,
+};
+#[cfg(feature = "federation-api")]
+pub use key_ring::KeyRing;
+pub use keys::{
+    read_signing_keys, write_signing_keys, AsyncSigner, Ed25519KeyPair, InProcessSigner, KeyPair,
+    PublicKeyMap, PublicKeySet,
 };
-pub use keys::{Ed25519KeyPair, KeyPair, PublicKeyMap, PublicKeySet};
+#[cfg(feature = "federation-api")]
+pub use pdu::{validate_pdu, PduValidation};
 pub use ruma_serde::{CanonicalJsonError, CanonicalJsonObject, CanonicalJsonValue};
 pub use signatures::Signature;
 pub use verification::Verified;
+pub use x_matrix::{request_json, sign_request, verify_request, XMatrix};
 
+#[cfg(feature = "cross-signing")]
+mod cross_signing;
 mod error;
 mod functions;
+#[cfg(feature = "federation-api")]
+mod key_ring;
 mod keys;
+#[cfg(feature = "federation-api")]
+mod pdu;
 mod signatures;
 mod verification;
+mod x_matrix;
 
 /// The algorithm used for signing data.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, AsRefStr, DisplayAsRefStr)]
@@ -0,0 +1,236 @@
+//! Validation of incoming PDUs.
+
+use std::convert::TryFrom;
+
+use ruma_identifiers::{EventId, RoomVersionId};
+use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
+
+use crate::{
+    functions::MAX_PDU_BYTES, redact, reference_hash, Error, JsonError, KeyRing, VerificationError,
+    Verified,
+};
+
+/// The outcome of [`validate_pdu`].
+#[derive(Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum PduValidation {
+    /// The PDU passed all checks.
+    Accepted {
+        /// The ID of the event.
+        event_id: Box<EventId>,
+    },
+
+    /// The signatures of the PDU are valid, but its content doesn't match its content hash.
+    ///
+    /// The specification requires the redacted PDU to be used instead of the received one.
+    ContentHashMismatch {
+        /// The ID of the event.
+        event_id: Box<EventId>,
+
+        /// The redacted PDU.
+        redacted: CanonicalJsonObject,
+    },
+
+    /// The signature of the origin or the sender server is missing or invalid.
+    BadSignature(VerificationError),
+
+    /// The event ID of the PDU doesn't match the PDU.
+    ///
+    /// For room versions 1 and 2, the PDU has no valid `event_id`. For later room versions, the
+    /// PDU has an `event_id` that is not exactly the one derived from its reference hash.
+    EventIdMismatch,
+
+    /// The canonical JSON of the PDU is larger than 65535 bytes.
+    TooLarge,
+}
+
+/// Validates a PDU that was received over federation.
+///
+/// This checks the size of the PDU, its event ID, the signatures of the servers that must have
+/// signed it and its content hash. The keys of these servers must already be part of `key_ring`;
+/// signatures from servers without a key that is valid for the PDU are reported as
+/// [`PduValidation::BadSignature`].
+///
+/// # Errors
+///
+/// Returns an error if the PDU is malformed, e.g. if it has no `hashes` or `sender`.
+pub fn validate_pdu(
+    object: &CanonicalJsonObject,
+    version: &RoomVersionId,
+    key_ring: &KeyRing,
+) -> Result<PduValidation, Error> {
+    let json = serde_json::to_string(object).map_err(JsonError::from)?;
+    if json.len() > MAX_PDU_BYTES {
+        return Ok(PduValidation::TooLarge);
+    }
+
+    let event_id = match version {
+        RoomVersionId::V1 | RoomVersionId::V2 => match object.get("event_id") {
+            Some(CanonicalJsonValue::String(event_id)) => {
+                match Box::<EventId>::try_from(event_id.as_str()) {
+                    Ok(event_id) if event_id.server_name().is_some() => event_id,
+                    _ => return Ok(PduValidation::EventIdMismatch),
+                }
+            }
+            _ => return Ok(PduValidation::EventIdMismatch),
+        },
+        _ => {
+            let reference_id = format!("${}", reference_hash(object, version)?);
+            let reference_id = Box::<EventId>::try_from(reference_id)
+                .expect("reference hash should be a valid event ID");
+
+            match object.get("event_id") {
+                None => reference_id,
+                Some(CanonicalJsonValue::String(event_id))
+                    if event_id.as_str() == reference_id.as_str() =>
+                {
+                    reference_id
+                }
+                Some(_) => return Ok(PduValidation::EventIdMismatch),
+            }
+        }
+    };
+
+    match key_ring.verify_event(object, version) {
+        Ok(Verified::All) => Ok(PduValidation::Accepted { event_id }),
+        Ok(Verified::Signatures) => {
+            Ok(PduValidation::ContentHashMismatch { event_id, redacted: redact(object, version)? })
+        }
+        Err(Error::Verification(error)) => Ok(PduValidation::BadSignature(error)),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use base64::{encode_config, STANDARD_NO_PAD};
+    use ruma_common::MilliSecondsSinceUnixEpoch;
+    use ruma_identifiers::{server_name, EventId, RoomVersionId};
+    use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
+    use serde_json::json;
+
+    use super::{validate_pdu, PduValidation};
+    use crate::{hash_and_sign_event, reference_hash, Ed25519KeyPair, KeyRing, VerificationError};
+
+    fn generate_key_pair() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "1".to_owned()).unwrap()
+    }
+
+    fn key_ring(key_pair: &Ed25519KeyPair) -> KeyRing {
+        let mut key_ring = KeyRing::new();
+        key_ring.add_trusted_key(
+            server_name!("domain"),
+            "ed25519:1",
+            encode_config(key_pair.public_key(), STANDARD_NO_PAD),
+            MilliSecondsSinceUnixEpoch(2_000_000_u32.into()),
+        );
+        key_ring
+    }
+
+    fn pdu(key_pair: &Ed25519KeyPair, version: &RoomVersionId, body: &str) -> CanonicalJsonObject {
+        let mut object: CanonicalJsonObject = serde_json::from_value(json!({
+            "auth_events": [],
+            "content": { "body": body },
+            "depth": 3,
+            "origin": "domain",
+            "origin_server_ts": 1_000_000,
+            "prev_events": [],
+            "room_id": "!x:domain",
+            "sender": "@a:domain",
+            "type": "X",
+        }))
+        .unwrap();
+        if let RoomVersionId::V1 | RoomVersionId::V2 = version {
+            object
+                .insert("event_id".to_owned(), CanonicalJsonValue::String("$a:domain".to_owned()));
+        }
+        hash_and_sign_event("domain", key_pair, &mut object, version).unwrap();
+        object
+    }
+
+    #[test]
+    fn accepted() {
+        let key_pair = generate_key_pair();
+        let object = pdu(&key_pair, &RoomVersionId::V6, "hello");
+
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        let expected_id = format!("${}", reference_hash(&object, &RoomVersionId::V6).unwrap());
+        match outcome {
+            PduValidation::Accepted { event_id } => assert_eq!(event_id.as_str(), expected_id),
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+
+        let object = pdu(&key_pair, &RoomVersionId::V1, "hello");
+        let outcome = validate_pdu(&object, &RoomVersionId::V1, &key_ring(&key_pair)).unwrap();
+        match outcome {
+            PduValidation::Accepted { event_id } => assert_eq!(event_id.as_str(), "$a:domain"),
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn content_hash_mismatch() {
+        let key_pair = generate_key_pair();
+        let mut object = pdu(&key_pair, &RoomVersionId::V6, "hello");
+        object.insert("content".to_owned(), CanonicalJsonValue::Object(Default::default()));
+
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        match outcome {
+            PduValidation::ContentHashMismatch { redacted, .. } => {
+                assert_eq!(redacted["content"], CanonicalJsonValue::Object(Default::default()));
+            }
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn bad_signature() {
+        let object = pdu(&generate_key_pair(), &RoomVersionId::V6, "hello");
+
+        let outcome =
+            validate_pdu(&object, &RoomVersionId::V6, &key_ring(&generate_key_pair())).unwrap();
+        assert!(matches!(outcome, PduValidation::BadSignature(VerificationError::Signature(_))));
+
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &KeyRing::new()).unwrap();
+        assert!(matches!(
+            outcome,
+            PduValidation::BadSignature(VerificationError::PublicKeyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn event_id_mismatch() {
+        let key_pair = generate_key_pair();
+
+        let mut object = pdu(&key_pair, &RoomVersionId::V1, "hello");
+        object.remove("event_id");
+        let outcome = validate_pdu(&object, &RoomVersionId::V1, &key_ring(&key_pair)).unwrap();
+        assert!(matches!(outcome, PduValidation::EventIdMismatch));
+
+        let mut object = pdu(&key_pair, &RoomVersionId::V6, "hello");
+        let other_id = Box::<EventId>::try_from("$other").unwrap();
+        object.insert("event_id".to_owned(), CanonicalJsonValue::String(other_id.to_string()));
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        assert!(matches!(outcome, PduValidation::EventIdMismatch));
+
+        object.insert("event_id".to_owned(), CanonicalJsonValue::String("not an ID".to_owned()));
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        assert!(matches!(outcome, PduValidation::EventIdMismatch));
+
+        object.insert("event_id".to_owned(), CanonicalJsonValue::Integer(1u32.into()));
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        assert!(matches!(outcome, PduValidation::EventIdMismatch));
+    }
+
+    #[test]
+    fn too_large() {
+        let key_pair = generate_key_pair();
+        let mut object = pdu(&key_pair, &RoomVersionId::V6, "hello");
+        object.insert("content".to_owned(), CanonicalJsonValue::String("a".repeat(70_000)));
+
+        let outcome = validate_pdu(&object, &RoomVersionId::V6, &key_ring(&key_pair)).unwrap();
+        assert!(matches!(outcome, PduValidation::TooLarge));
+    }
+}
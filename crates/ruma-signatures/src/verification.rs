@@ -1,8 +1,9 @@
 //! Verification of digital signatures.
 
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
-use ed25519_dalek::{PublicKey, Verifier as _};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{PublicKey, Signature, Verifier as _};
 
 use crate::{Error, ParseError, VerificationError};
 
@@ -42,6 +43,65 @@ impl Verifier for Ed25519Verifier {
     }
 }
 
+/// Verifies many Ed25519 signatures at once.
+///
+/// Each item of `signatures` is a message, the raw bytes of a public key and the raw bytes of a
+/// signature of the message.
+///
+/// This accepts the same signatures as [`Ed25519Verifier`]: signatures for which batched
+/// verification could give a different result are verified one by one.
+///
+/// # Errors
+///
+/// Returns an error if any of the public keys or signatures is malformed or any of the signatures
+/// is invalid, without telling which one.
+pub(crate) fn verify_ed25519_batch(signatures: &[(&[u8], &[u8], &[u8])]) -> Result<(), Error> {
+    let mut messages = Vec::with_capacity(signatures.len());
+    let mut public_keys = Vec::with_capacity(signatures.len());
+    let mut parsed_signatures = Vec::with_capacity(signatures.len());
+
+    for (message, public_key_bytes, signature_bytes) in signatures {
+        let public_key = PublicKey::from_bytes(public_key_bytes).map_err(ParseError::PublicKey)?;
+        let signature = Signature::try_from(*signature_bytes).map_err(ParseError::Signature)?;
+
+        if batch_verification_agrees(public_key_bytes, &signature_bytes[..32]) {
+            messages.push(*message);
+            public_keys.push(public_key);
+            parsed_signatures.push(signature);
+        } else {
+            public_key.verify(message, &signature).map_err(VerificationError::Signature)?;
+        }
+    }
+
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    ed25519_dalek::verify_batch(&messages, &parsed_signatures, &public_keys)
+        .map_err(VerificationError::Signature)
+        .map_err(Error::from)
+}
+
+/// Whether batched verification gives the same result as `PublicKey::verify` for a signature.
+///
+/// `PublicKey::verify` checks that `[s]B - [k]A` is encoded exactly as the `R` of the signature,
+/// while `verify_batch` decompresses `R` and checks that `[s]B - R - [k]A` sums up to the identity
+/// with random weights. A small-order component in `R` or `A`, or a non-canonical encoding of `R`,
+/// can make the latter accept a signature that the former rejects. Without them, both accept the
+/// same signatures.
+///
+/// Both slices must have a length of 32 bytes: `r` is the first half of the signature.
+fn batch_verification_agrees(public_key: &[u8], r: &[u8]) -> bool {
+    let is_canonical_and_torsion_free = |bytes: &[u8]| {
+        let compressed = CompressedEdwardsY::from_slice(bytes);
+        compressed
+            .decompress()
+            .map_or(false, |point| point.is_torsion_free() && point.compress() == compressed)
+    };
+
+    is_canonical_and_torsion_free(public_key) && is_canonical_and_torsion_free(r)
+}
+
 /// A value returned when an event is successfully verified.
 ///
 /// Event verification involves verifying both signatures and a content hash. It is possible for
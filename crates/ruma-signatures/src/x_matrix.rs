@@ -0,0 +1,385 @@
+//! Signing and verification of federation requests with the `X-Matrix` authorization scheme.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use http::{header::AUTHORIZATION, HeaderValue, Request};
+use ruma_identifiers::ServerName;
+use ruma_serde::{CanonicalJsonObject, CanonicalJsonValue};
+
+use crate::{
+    functions::canonical_json, keys::KeyPair, verify_json, Error, JsonError, ParseError,
+    PublicKeyMap, VerificationError,
+};
+
+/// The parameters of an `Authorization` header using the `X-Matrix` scheme.
+///
+/// Federation requests are authenticated by a signature of the origin server over the request,
+/// sent in a header of the form:
+///
+/// ```text
+/// X-Matrix origin="origin.example.com",destination="destination.example.com",key="ed25519:1",sig="ABCDEF..."
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct XMatrix {
+    /// The server name of the sending server.
+    pub origin: Box<ServerName>,
+
+    /// The server name of the receiving server.
+    ///
+    /// Older servers don't send this parameter.
+    pub destination: Option<Box<ServerName>>,
+
+    /// The ID, including the algorithm name, of the sending server's key used to sign the
+    /// request.
+    pub key: String,
+
+    /// The Base64-encoded signature of the request.
+    pub sig: String,
+}
+
+impl XMatrix {
+    /// Creates a new `XMatrix` with the given origin, destination, key ID and signature.
+    pub fn new(
+        origin: Box<ServerName>,
+        destination: Option<Box<ServerName>>,
+        key: String,
+        sig: String,
+    ) -> Self {
+        Self { origin, destination, key, sig }
+    }
+}
+
+impl Display for XMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "X-Matrix origin=\"{}\",", self.origin)?;
+        if let Some(destination) = &self.destination {
+            write!(f, "destination=\"{}\",", destination)?;
+        }
+        write!(f, "key=\"{}\",sig=\"{}\"", self.key, self.sig)
+    }
+}
+
+impl FromStr for XMatrix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ParseError::XMatrix(reason.to_owned());
+
+        let (scheme, params) = s.trim().split_once(' ').ok_or_else(|| invalid("missing scheme"))?;
+        if !scheme.eq_ignore_ascii_case("X-Matrix") {
+            return Err(invalid("scheme is not X-Matrix").into());
+        }
+
+        let mut origin = None;
+        let mut destination = None;
+        let mut key = None;
+        let mut sig = None;
+
+        for param in params.split(',') {
+            let (name, value) =
+                param.split_once('=').ok_or_else(|| invalid("parameter without a value"))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value)
+                .to_owned();
+
+            match name.trim() {
+                "origin" => {
+                    origin = Some(
+                        Box::<ServerName>::try_from(value)
+                            .map_err(|_| invalid("invalid origin"))?,
+                    )
+                }
+                "destination" => {
+                    destination = Some(
+                        Box::<ServerName>::try_from(value)
+                            .map_err(|_| invalid("invalid destination"))?,
+                    )
+                }
+                "key" => key = Some(value),
+                "sig" => sig = Some(value),
+                // Unknown parameters are ignored, as required by the specification.
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            origin: origin.ok_or_else(|| invalid("missing origin"))?,
+            destination,
+            key: key.ok_or_else(|| invalid("missing key"))?,
+            sig: sig.ok_or_else(|| invalid("missing sig"))?,
+        })
+    }
+}
+
+/// Builds the JSON object that is signed to authenticate a federation request.
+///
+/// The object contains the `method`, the `uri` (path and query string), the `origin` and
+/// `destination` server names and, if the request has a body, the JSON `content` of the request.
+///
+/// # Errors
+///
+/// Returns an error if the request body is not empty and not valid JSON.
+pub fn request_json<T: AsRef<[u8]>>(
+    request: &Request<T>,
+    origin: &ServerName,
+    destination: &ServerName,
+) -> Result<CanonicalJsonObject, Error> {
+    let uri = request.uri().path_and_query().map_or_else(|| request.uri().path(), |p| p.as_str());
+
+    let mut object = BTreeMap::new();
+    object.insert("method".to_owned(), CanonicalJsonValue::String(request.method().to_string()));
+    object.insert("uri".to_owned(), CanonicalJsonValue::String(uri.to_owned()));
+    object.insert("origin".to_owned(), CanonicalJsonValue::String(origin.to_string()));
+    object.insert("destination".to_owned(), CanonicalJsonValue::String(destination.to_string()));
+
+    let body = request.body().as_ref();
+    if !body.is_empty() {
+        let content: CanonicalJsonValue = serde_json::from_slice(body).map_err(JsonError::from)?;
+        object.insert("content".to_owned(), content);
+    }
+
+    Ok(object)
+}
+
+/// Signs a federation request and adds the signature as an `X-Matrix` `Authorization` header.
+///
+/// # Parameters
+///
+/// * origin: The server name of the sending server.
+/// * destination: The server name of the receiving server.
+/// * key_pair: The key pair of the sending server used to sign the request.
+/// * request: The request to sign.
+///
+/// # Errors
+///
+/// Returns an error if the request body is not empty and not valid JSON.
+pub fn sign_request<K, T>(
+    origin: &ServerName,
+    destination: &ServerName,
+    key_pair: &K,
+    request: &mut Request<T>,
+) -> Result<(), Error>
+where
+    K: KeyPair,
+    T: AsRef<[u8]>,
+{
+    let object = request_json(request, origin, destination)?;
+    let signature = key_pair.sign(canonical_json(&object)?.as_bytes());
+
+    let x_matrix = XMatrix::new(
+        origin.to_owned(),
+        Some(destination.to_owned()),
+        signature.id(),
+        signature.base64(),
+    );
+
+    // Server names, key IDs and unpadded Base64 only contain visible ASCII characters.
+    let header = HeaderValue::from_str(&x_matrix.to_string())
+        .expect("X-Matrix header should be a valid header value");
+    request.headers_mut().append(AUTHORIZATION, header);
+
+    Ok(())
+}
+
+/// Verifies the `X-Matrix` `Authorization` headers of a federation request.
+///
+/// Returns the server name of the origin of the request if the signatures are valid.
+///
+/// # Parameters
+///
+/// * public_key_map: The public keys of the origin server.
+/// * destination: The server name of the receiving server, i.e. the server calling this function.
+/// * request: The request to verify.
+///
+/// # Errors
+///
+/// Returns an error if:
+///
+/// * The request has no `X-Matrix` `Authorization` header or one of them is malformed.
+/// * The headers disagree on the origin or name a different destination.
+/// * The request body is not empty and not valid JSON.
+/// * Verification of the signatures fails.
+pub fn verify_request<T: AsRef<[u8]>>(
+    public_key_map: &PublicKeyMap,
+    destination: &ServerName,
+    request: &Request<T>,
+) -> Result<Box<ServerName>, Error> {
+    let mut origin: Option<Box<ServerName>> = None;
+    let mut signatures = BTreeMap::new();
+
+    for header in request.headers().get_all(AUTHORIZATION) {
+        let header = header
+            .to_str()
+            .map_err(|_| ParseError::XMatrix("header contains invalid characters".to_owned()))?;
+        if !header.trim_start().get(..8).map_or(false, |s| s.eq_ignore_ascii_case("X-Matrix")) {
+            continue;
+        }
+
+        let x_matrix: XMatrix = header.parse()?;
+
+        if x_matrix.destination.as_deref().map_or(false, |d| d != destination) {
+            return Err(VerificationError::DestinationMismatch.into());
+        }
+
+        match &origin {
+            Some(origin) if *origin != x_matrix.origin => {
+                return Err(VerificationError::OriginMismatch.into());
+            }
+            Some(_) => {}
+            None => origin = Some(x_matrix.origin),
+        }
+
+        signatures.insert(x_matrix.key, CanonicalJsonValue::String(x_matrix.sig));
+    }
+
+    let origin = origin.ok_or(VerificationError::XMatrixHeaderNotFound)?;
+
+    let mut object = request_json(request, &origin, destination)?;
+    let mut signature_map = BTreeMap::new();
+    signature_map.insert(origin.to_string(), CanonicalJsonValue::Object(signatures));
+    object.insert("signatures".to_owned(), CanonicalJsonValue::Object(signature_map));
+
+    verify_json(public_key_map, &object)?;
+
+    Ok(origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use base64::{encode_config, STANDARD_NO_PAD};
+    use http::{header::AUTHORIZATION, Request};
+    use ruma_identifiers::server_name;
+
+    use super::{sign_request, verify_request, XMatrix};
+    use crate::{Ed25519KeyPair, Error, PublicKeyMap, VerificationError};
+
+    fn key_pair() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_der(&Ed25519KeyPair::generate().unwrap(), "1".to_owned()).unwrap()
+    }
+
+    fn public_key_map(origin: &str, key_pair: &Ed25519KeyPair) -> PublicKeyMap {
+        let mut public_key_set = BTreeMap::new();
+        public_key_set
+            .insert("ed25519:1".to_owned(), encode_config(key_pair.public_key(), STANDARD_NO_PAD));
+        let mut public_key_map = BTreeMap::new();
+        public_key_map.insert(origin.to_owned(), public_key_set);
+        public_key_map
+    }
+
+    fn request(body: &[u8]) -> Request<Vec<u8>> {
+        Request::put("https://destination.example.com/_matrix/federation/v1/send/1?a=b")
+            .body(body.to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_x_matrix() {
+        let header = "X-Matrix origin=origin.example.com,key=\"ed25519:1\",sig=\"ABCDEF\"";
+        let x_matrix: XMatrix = header.parse().unwrap();
+
+        assert_eq!(x_matrix.origin, server_name!("origin.example.com"));
+        assert_eq!(x_matrix.destination, None);
+        assert_eq!(x_matrix.key, "ed25519:1");
+        assert_eq!(x_matrix.sig, "ABCDEF");
+
+        let x_matrix = XMatrix::new(
+            server_name!("origin.example.com").to_owned(),
+            Some(server_name!("destination.example.com").to_owned()),
+            "ed25519:1".to_owned(),
+            "ABCDEF".to_owned(),
+        );
+        assert_eq!(x_matrix.to_string().parse::<XMatrix>().unwrap(), x_matrix);
+    }
+
+    #[test]
+    fn parse_invalid_x_matrix() {
+        assert!("Bearer abcdef".parse::<XMatrix>().is_err());
+        assert!("X-Matrix origin=origin.example.com,key=\"ed25519:1\"".parse::<XMatrix>().is_err());
+    }
+
+    #[test]
+    fn sign_and_verify_request() {
+        let key_pair = key_pair();
+        let origin = server_name!("origin.example.com");
+        let destination = server_name!("destination.example.com");
+
+        let mut request = request(br#"{"pdus":[]}"#);
+        sign_request(origin, destination, &key_pair, &mut request).unwrap();
+
+        let header: XMatrix = request.headers()[AUTHORIZATION].to_str().unwrap().parse().unwrap();
+        assert_eq!(header.origin, origin);
+        assert_eq!(header.destination.as_deref(), Some(destination));
+        assert_eq!(header.key, "ed25519:1");
+
+        let public_key_map = public_key_map(origin.as_str(), &key_pair);
+        assert_eq!(verify_request(&public_key_map, destination, &request).unwrap(), origin);
+    }
+
+    #[test]
+    fn verify_request_without_body() {
+        let key_pair = key_pair();
+        let origin = server_name!("origin.example.com");
+        let destination = server_name!("destination.example.com");
+
+        let mut request = request(b"");
+        sign_request(origin, destination, &key_pair, &mut request).unwrap();
+
+        let public_key_map = public_key_map(origin.as_str(), &key_pair);
+        assert!(verify_request(&public_key_map, destination, &request).is_ok());
+    }
+
+    #[test]
+    fn verify_tampered_request() {
+        let key_pair = key_pair();
+        let origin = server_name!("origin.example.com");
+        let destination = server_name!("destination.example.com");
+
+        let mut request = request(br#"{"pdus":[]}"#);
+        sign_request(origin, destination, &key_pair, &mut request).unwrap();
+        *request.body_mut() = br#"{"pdus":[{}]}"#.to_vec();
+
+        let public_key_map = public_key_map(origin.as_str(), &key_pair);
+        assert!(matches!(
+            verify_request(&public_key_map, destination, &request),
+            Err(Error::Verification(VerificationError::Signature(_)))
+        ));
+    }
+
+    #[test]
+    fn verify_request_for_other_destination() {
+        let key_pair = key_pair();
+        let origin = server_name!("origin.example.com");
+
+        let mut request = request(b"");
+        sign_request(origin, server_name!("other.example.com"), &key_pair, &mut request).unwrap();
+
+        let public_key_map = public_key_map(origin.as_str(), &key_pair);
+        assert!(matches!(
+            verify_request(&public_key_map, server_name!("destination.example.com"), &request),
+            Err(Error::Verification(VerificationError::DestinationMismatch))
+        ));
+    }
+
+    #[test]
+    fn verify_unsigned_request() {
+        assert!(matches!(
+            verify_request(
+                &BTreeMap::new(),
+                server_name!("destination.example.com"),
+                &request(b"")
+            ),
+            Err(Error::Verification(VerificationError::XMatrixHeaderNotFound))
+        ));
+    }
+}
@@ -534,6 +534,7 @@ fn BAN_STATE_SET() -> HashMap<Box<EventId>, Arc<StateEvent>> {
 }
 
 mod event {
+    use js_int::UInt;
     use ruma_common::MilliSecondsSinceUnixEpoch;
     use ruma_events::{pdu::Pdu, EventType};
     use ruma_identifiers::{EventId, RoomId, UserId};
@@ -593,6 +594,15 @@ mod event {
             }
         }
 
+        fn depth(&self) -> UInt {
+            match &self.rest {
+                Pdu::RoomV1Pdu(ev) => ev.depth,
+                Pdu::RoomV3Pdu(ev) => ev.depth,
+                #[cfg(not(feature = "unstable-exhaustive-types"))]
+                _ => unreachable!("new PDU version"),
+            }
+        }
+
         fn state_key(&self) -> Option<&str> {
             match &self.rest {
                 Pdu::RoomV1Pdu(ev) => ev.state_key.as_deref(),
@@ -0,0 +1,325 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::Arc,
+};
+
+use ruma_identifiers::EventId;
+use tracing::debug;
+
+use crate::{Error, Event, Result, StateMap};
+
+/// A memoizing cache of the auth chains of events.
+///
+/// The auth chain of an event is the full recursive set of its `auth_events`, not including the
+/// event itself. Since most events of a room share the larger part of their auth chain, every
+/// chain that is computed is kept, so following calls only need to walk the events they haven't
+/// seen yet.
+#[derive(Clone, Debug)]
+pub struct AuthChainCache<Id> {
+    chains: HashMap<Id, Arc<HashSet<Id>>>,
+    skip_missing_events: bool,
+}
+
+impl<Id> AuthChainCache<Id>
+where
+    Id: Clone + Eq + Hash + Borrow<EventId>,
+{
+    /// Creates an empty `AuthChainCache`.
+    pub fn new() -> Self {
+        Self { chains: HashMap::new(), skip_missing_events: false }
+    }
+
+    /// Creates an empty `AuthChainCache` that doesn't fail on events that can't be fetched.
+    ///
+    /// The IDs of such events are still part of the auth chains of the events that reference
+    /// them, but their own auth events are unknown, so their auth chain is empty. This is useful
+    /// when only a part of the events of a room is available.
+    pub fn skipping_missing_events() -> Self {
+        Self { chains: HashMap::new(), skip_missing_events: true }
+    }
+
+    /// Returns the auth chain of the event with the given ID.
+    ///
+    /// Events that are not in the cache yet are fetched through `fetch_event`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotFound`] if an event of the chain can't be fetched, unless the cache was
+    /// created with [`skipping_missing_events`](Self::skipping_missing_events), and
+    /// [`Error::InvalidPdu`] if the `auth_events` of the events form a cycle or `fetch_event`
+    /// returns an event with a different ID than the requested one.
+    pub fn auth_chain<E>(
+        &mut self,
+        event_id: &EventId,
+        fetch_event: impl Fn(&EventId) -> Option<E>,
+    ) -> Result<Arc<HashSet<Id>>>
+    where
+        E: Event<Id = Id>,
+    {
+        if let Some(chain) = self.chains.get(event_id) {
+            return Ok(Arc::clone(chain));
+        }
+
+        let skip_missing_events = self.skip_missing_events;
+        let fetch = |id: &EventId| match fetch_event(id) {
+            Some(event) if event.event_id().borrow() == id => Ok(Some(event)),
+            Some(event) => Err(Error::InvalidPdu(format!(
+                "fetched {} instead of {}",
+                event.event_id().borrow(),
+                id
+            ))),
+            None if skip_missing_events => {
+                debug!("skipping missing auth event {}", id);
+                Ok(None)
+            }
+            None => Err(Error::NotFound(format!("Failed to find {}", id))),
+        };
+
+        // Depth-first walk, an event's chain is built once the chains of all of its auth events
+        // are known. Events are only fetched when they are first visited.
+        let mut stack: Vec<(Id, Option<E>)> = vec![];
+        let event = match fetch(event_id)? {
+            Some(event) => event,
+            None => return Ok(Arc::new(HashSet::new())),
+        };
+        stack.push((event.event_id().clone(), None));
+        let mut first = Some(event);
+        let mut in_progress = HashSet::new();
+
+        while let Some((id, visited)) = stack.pop() {
+            if self.chains.contains_key(id.borrow()) {
+                continue;
+            }
+
+            if let Some(event) = visited {
+                let mut chain = HashSet::new();
+                for auth_id in event.auth_events() {
+                    let auth_chain = self.chains.get(auth_id.borrow()).ok_or_else(|| {
+                        Error::InvalidPdu(format!("auth chain of {} is missing", auth_id.borrow()))
+                    })?;
+
+                    chain.insert(auth_id.clone());
+                    chain.extend(auth_chain.iter().cloned());
+                }
+
+                let event_id: &EventId = id.borrow();
+                debug!("auth chain of {} has {} events", event_id, chain.len());
+                in_progress.remove(&id);
+                self.chains.insert(id, Arc::new(chain));
+                continue;
+            }
+
+            let event = match first.take() {
+                Some(event) => event,
+                None => match fetch(id.borrow())? {
+                    Some(event) => event,
+                    None => {
+                        self.chains.insert(id, Arc::new(HashSet::new()));
+                        continue;
+                    }
+                },
+            };
+
+            let pending: Vec<_> = event
+                .auth_events()
+                .filter(|&auth_id| !self.chains.contains_key(auth_id.borrow()))
+                .cloned()
+                .collect();
+
+            in_progress.insert(id.clone());
+            stack.push((id.clone(), Some(event)));
+
+            for auth_id in pending {
+                if in_progress.contains::<Id>(&auth_id) {
+                    let id: &EventId = id.borrow();
+                    return Err(Error::InvalidPdu(format!("auth events of {} form a cycle", id)));
+                }
+
+                stack.push((auth_id, None));
+            }
+        }
+
+        self.chains
+            .get(event_id)
+            .map(Arc::clone)
+            .ok_or_else(|| Error::InvalidPdu(format!("auth chain of {} is missing", event_id)))
+    }
+
+    /// Returns the auth chain of each of the given state sets, i.e. the union of the auth chains
+    /// of the events in the set.
+    ///
+    /// The result can be passed as the `auth_chain_sets` of [`resolve`](crate::resolve).
+    pub fn auth_chain_sets<'a, E>(
+        &mut self,
+        state_sets: impl IntoIterator<Item = &'a StateMap<Id>>,
+        fetch_event: impl Fn(&EventId) -> Option<E>,
+    ) -> Result<Vec<HashSet<Id>>>
+    where
+        E: Event<Id = Id>,
+        Id: 'a,
+    {
+        state_sets
+            .into_iter()
+            .map(|state| {
+                let mut chain = HashSet::new();
+                for event_id in state.values() {
+                    chain.extend(self.auth_chain(event_id.borrow(), &fetch_event)?.iter().cloned());
+                }
+                Ok(chain)
+            })
+            .collect()
+    }
+
+    /// Returns the number of events whose auth chain is cached.
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Returns `true` if no auth chain is cached.
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+}
+
+impl<Id> Default for AuthChainCache<Id>
+where
+    Id: Clone + Eq + Hash + Borrow<EventId>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, sync::Arc};
+
+    use maplit::hashset;
+    use ruma_events::EventType;
+    use serde_json::{json, value::to_raw_value as to_raw_json_value};
+
+    use super::AuthChainCache;
+    use crate::{
+        test_utils::{alice, event_id, to_pdu_event, StateEvent, INITIAL_EVENTS},
+        Error, Event,
+    };
+
+    #[test]
+    fn auth_chain_is_memoized() {
+        let events = INITIAL_EVENTS();
+        let fetches = Cell::new(0);
+        let fetch_event = |id: &_| {
+            fetches.set(fetches.get() + 1);
+            events.get(id).map(Arc::clone)
+        };
+
+        let mut cache = AuthChainCache::new();
+
+        let chain = cache.auth_chain(&event_id("IMC"), fetch_event).unwrap();
+        assert_eq!(
+            *chain,
+            hashset![event_id("CREATE"), event_id("IMA"), event_id("IPOWER"), event_id("IJR")]
+        );
+        assert_eq!(fetches.get(), 5);
+
+        // Only IMB itself has to be fetched, its auth events were walked for IMC
+        let chain = cache.auth_chain(&event_id("IMB"), fetch_event).unwrap();
+        assert_eq!(
+            *chain,
+            hashset![event_id("CREATE"), event_id("IMA"), event_id("IPOWER"), event_id("IJR")]
+        );
+        assert_eq!(fetches.get(), 6);
+        assert_eq!(cache.len(), 6);
+    }
+
+    #[test]
+    fn auth_chain_sets() {
+        let events = INITIAL_EVENTS();
+        let state = [event_id("IPOWER"), event_id("IJR")]
+            .iter()
+            .map(|id| {
+                let event = &events[id];
+                ((event.event_type().clone(), "".to_owned()), id.clone())
+            })
+            .collect();
+
+        let mut cache = AuthChainCache::new();
+        let sets = cache.auth_chain_sets(&[state], |id| events.get(id).map(Arc::clone)).unwrap();
+
+        assert_eq!(sets, vec![hashset![event_id("CREATE"), event_id("IMA"), event_id("IPOWER")]]);
+    }
+
+    #[test]
+    fn missing_auth_event() {
+        let events = INITIAL_EVENTS();
+        let mut cache = AuthChainCache::new();
+
+        let result = cache.auth_chain(&event_id("IMC"), |id| {
+            (id != &*event_id("IMA")).then(|| Arc::clone(&events[id]))
+        });
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn skipping_missing_auth_events() {
+        let events = INITIAL_EVENTS();
+        let mut cache = AuthChainCache::skipping_missing_events();
+
+        let chain = cache
+            .auth_chain(&event_id("IMC"), |id| {
+                (id != &*event_id("IMA")).then(|| Arc::clone(&events[id]))
+            })
+            .unwrap();
+        // IMA is part of the chain, but not its auth events that aren't referenced by others
+        assert_eq!(
+            *chain,
+            hashset![event_id("CREATE"), event_id("IMA"), event_id("IPOWER"), event_id("IJR")]
+        );
+
+        let chain = cache.auth_chain(&event_id("IMA"), |_| None::<Arc<StateEvent>>).unwrap();
+        assert!(chain.is_empty());
+
+        let mut cache = AuthChainCache::skipping_missing_events();
+        let chain = cache.auth_chain(&event_id("UNKNOWN"), |_| None::<Arc<StateEvent>>).unwrap();
+        assert!(chain.is_empty());
+    }
+
+    #[test]
+    fn fetched_event_with_other_id() {
+        let events = INITIAL_EVENTS();
+        let mut cache = AuthChainCache::new();
+
+        let result = cache.auth_chain(&event_id("IMC"), |id| {
+            let id = if id == &*event_id("IMA") { event_id("IMB") } else { id.to_owned() };
+            Some(Arc::clone(&events[&id]))
+        });
+        assert!(matches!(result, Err(Error::InvalidPdu(_))));
+
+        let result =
+            cache.auth_chain(&event_id("IMC"), |_| Some(Arc::clone(&events[&event_id("IMB")])));
+        assert!(matches!(result, Err(Error::InvalidPdu(_))));
+    }
+
+    #[test]
+    fn auth_events_cycle() {
+        let content = to_raw_json_value(&json!({ "topic": "cycle" })).unwrap();
+        let a = to_pdu_event(
+            "A",
+            alice(),
+            EventType::RoomTopic,
+            Some(""),
+            content.clone(),
+            &["B"],
+            &[],
+        );
+        let b = to_pdu_event("B", alice(), EventType::RoomTopic, Some(""), content, &["A"], &[]);
+
+        let mut cache = AuthChainCache::new();
+        let result = cache.auth_chain(&event_id("A"), |id| {
+            [&a, &b].iter().find(|ev| *ev.event_id == *id).map(|ev| Arc::clone(ev))
+        });
+        assert!(matches!(result, Err(Error::InvalidPdu(_))));
+    }
+}
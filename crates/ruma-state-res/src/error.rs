@@ -20,6 +20,13 @@ pub enum Error {
     #[error("Not found error: {0}")]
     NotFound(String),
 
+    /// Fetching events failed.
+    ///
+    /// This is distinct from `NotFound`, which means the events could be looked up, but the
+    /// given event was not among them.
+    #[error("Failed to fetch events: {0}")]
+    FetchFailed(Box<dyn std::error::Error>),
+
     /// Invalid fields in the given PDU.
     #[error("Invalid PDU: {0}")]
     InvalidPdu(String),
@@ -1,4 +1,4 @@
-use std::{borrow::Borrow, collections::BTreeSet, convert::TryFrom};
+use std::{borrow::Borrow, cell::RefCell, collections::BTreeSet, convert::TryFrom};
 
 use js_int::{int, Int};
 use ruma_events::{
@@ -11,14 +11,154 @@ use ruma_events::{
     },
     EventType,
 };
-use ruma_identifiers::{RoomVersionId, UserId};
+use ruma_identifiers::{EventId, RoomVersionId, UserId};
 use ruma_serde::Raw;
 use serde::{de::IgnoredAny, Deserialize};
 use serde_json::{from_str as from_json_str, value::RawValue as RawJsonValue};
+use thiserror::Error as ThisError;
 use tracing::{debug, error, info, warn};
 
 use crate::{room_version::RoomVersion, Error, Event, PowerLevelsContentFields, Result};
 
+/// The reason an event was rejected by [`auth_check`].
+#[derive(Clone, Debug, PartialEq, Eq, ThisError)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub enum RejectionReason {
+    /// An `m.room.create` event has previous events.
+    #[error("the room creation event has previous events")]
+    CreateHasPrevEvents,
+
+    /// The server of an `m.room.create` event's room ID doesn't match the sender's.
+    #[error("the server of the room ID does not match the sender of the room creation event")]
+    CreateServerMismatch,
+
+    /// An `m.room.create` event has an unrecognized `room_version`.
+    #[error("the room creation event has an invalid room version")]
+    CreateInvalidRoomVersion,
+
+    /// An `m.room.create` event has no `creator` field.
+    #[error("the room creation event has no creator")]
+    CreateMissingCreator,
+
+    /// There is no `m.room.create` event in the room state.
+    #[error("no m.room.create event in the room state")]
+    MissingCreateEvent,
+
+    /// The state key of an `m.room.aliases` event doesn't match the sender's server.
+    #[error("the state key of the m.room.aliases event does not match the sender's server")]
+    AliasesStateKeyMismatch,
+
+    /// An `m.room.member` event has no state key.
+    #[error("the membership event has no state key")]
+    MissingStateKey,
+
+    /// An `m.room.member` event has no valid `membership` field.
+    #[error("the membership event has no valid membership")]
+    InvalidMembership,
+
+    /// The membership transition is not one of the known ones.
+    #[error("unknown membership transition")]
+    UnknownMembership,
+
+    /// A user tried to make another user join the room.
+    #[error("a user cannot make another user join")]
+    JoinOnBehalfOfOtherUser,
+
+    /// The target of a membership event is banned from the room.
+    #[error("the target user is banned")]
+    TargetBanned,
+
+    /// The target of an invite is already in the room.
+    #[error("the target user is already joined")]
+    TargetAlreadyJoined,
+
+    /// The join rules of the room don't allow the user to join.
+    #[error("the join rules do not allow the user to join")]
+    JoinRulesForbidJoin,
+
+    /// A join to a restricted room was not authorised by a user who is joined and able to
+    /// invite.
+    #[error("the join to the restricted room was not authorised by a joined user able to invite")]
+    JoinNotAuthorised,
+
+    /// A user tried to leave a room they are neither invited to nor joined to.
+    #[error("the user is neither invited nor joined")]
+    NotInvitedOrJoined,
+
+    /// The sender is not joined to the room.
+    #[error("the sender is not joined to the room")]
+    SenderNotJoined,
+
+    /// The sender's power level is below the level required for the event.
+    #[error("the sender's power level {actual} is below the required level {required}")]
+    InsufficientPowerLevel {
+        /// The power level required for the event.
+        required: Int,
+
+        /// The sender's power level.
+        actual: Int,
+    },
+
+    /// The target of a kick or ban has a power level at least as high as the sender's.
+    #[error("the target user's power level is not lower than the sender's")]
+    TargetPowerLevelNotLower,
+
+    /// A state key starting with `@` doesn't match the sender.
+    #[error("the state key is a user ID that does not match the sender")]
+    StateKeyNotSender,
+
+    /// An `m.room.power_levels` event has a non-empty state key.
+    #[error("the power levels event has a non-empty state key")]
+    PowerLevelsInvalidStateKey,
+
+    /// An `m.room.power_levels` event changes a level above the sender's own power level, or
+    /// one equal to it for another user.
+    #[error("the power levels event changes levels the sender is not allowed to change")]
+    PowerLevelsChangeNotAllowed,
+
+    /// The sender of a redaction is not allowed to redact the event.
+    #[error("the sender is not allowed to redact the event")]
+    RedactionNotAllowed,
+
+    /// The `mxid` of a third-party invite doesn't match the target user.
+    #[error("the third-party invite is for a different user")]
+    ThirdPartyInviteMxidMismatch,
+
+    /// There is no `m.room.third_party_invite` event matching the invite's token.
+    #[error("no m.room.third_party_invite event matches the token")]
+    MissingThirdPartyInvite,
+
+    /// The sender of a third-party invite membership doesn't match the sender of the
+    /// `m.room.third_party_invite` event.
+    #[error("the sender does not match the sender of the m.room.third_party_invite event")]
+    ThirdPartyInviteSenderMismatch,
+
+    /// None of the public keys of the `m.room.third_party_invite` event match the signed token.
+    #[error("the third-party invite is not signed by any of the room's public keys")]
+    ThirdPartyInviteBadSignature,
+}
+
+/// The outcome of [`auth_check`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct AuthOutcome {
+    /// Why the event was rejected, or `None` if it was allowed.
+    pub rejection: Option<RejectionReason>,
+
+    /// The IDs of the state events that were consulted while authorizing the event, in the order
+    /// they were looked up.
+    pub auth_events: Vec<Box<EventId>>,
+}
+
+impl AuthOutcome {
+    /// Whether the event passed the authorization rules.
+    pub fn is_allowed(&self) -> bool {
+        self.rejection.is_none()
+    }
+}
+
+type Verdict = std::result::Result<(), RejectionReason>;
+
 /// For the given event `kind` what are the relevant auth events that are needed to authenticate
 /// this `content`.
 ///
@@ -30,6 +170,7 @@ pub fn auth_types_for_event(
     sender: &UserId,
     state_key: Option<&str>,
     content: &RawJsonValue,
+    room_version: &RoomVersion,
 ) -> serde_json::Result<Vec<(EventType, String)>> {
     if kind == &EventType::RoomCreate {
         return Ok(vec![]);
@@ -46,6 +187,7 @@ pub fn auth_types_for_event(
         struct RoomMemberContentFields {
             membership: Option<Raw<MembershipState>>,
             third_party_invite: Option<Raw<ThirdPartyInvite>>,
+            join_authorised_via_users_server: Option<Raw<Box<UserId>>>,
         }
 
         if let Some(state_key) = state_key {
@@ -72,6 +214,17 @@ pub fn auth_types_for_event(
                         }
                     }
                 }
+
+                if membership == MembershipState::Join && room_version.restricted_join_rules {
+                    if let Some(Ok(u)) =
+                        content.join_authorised_via_users_server.map(|m| m.deserialize())
+                    {
+                        let key = (EventType::RoomMember, u.to_string());
+                        if !auth_types.contains(&key) {
+                            auth_types.push(key);
+                        }
+                    }
+                }
             }
         }
     }
@@ -88,16 +241,61 @@ pub fn auth_types_for_event(
 ///
 /// The `fetch_state` closure should gather state from a state snapshot. We need to know if the
 /// event passes auth against some state not a recursive collection of auth_events fields.
+///
+/// Returns an [`AuthOutcome`] holding the reason the event was rejected, if it was, and the state
+/// events that were looked up through `fetch_state`. An `Err` is only returned if an event could
+/// not be processed at all, e.g. because of malformed content.
 pub fn auth_check<E: Event>(
     room_version: &RoomVersion,
     incoming_event: impl Event,
     prev_event: Option<impl Event>,
     current_third_party_invite: Option<impl Event>,
     fetch_state: impl Fn(&EventType, &str) -> Option<E>,
-) -> Result<bool> {
+) -> Result<AuthOutcome> {
+    let auth_events = RefCell::new(Vec::<Box<EventId>>::new());
+    let fetch_state = |ty: &EventType, key: &str| {
+        let event = fetch_state(ty, key);
+        if let Some(event) = &event {
+            let event_id: &EventId = event.event_id().borrow();
+            let mut auth_events = auth_events.borrow_mut();
+            if !auth_events.iter().any(|id| **id == *event_id) {
+                auth_events.push(event_id.to_owned());
+            }
+        }
+        event
+    };
+
+    let verdict = check_event(
+        room_version,
+        &incoming_event,
+        prev_event,
+        current_third_party_invite,
+        fetch_state,
+    )?;
+
+    if let Err(reason) = &verdict {
+        warn!(
+            "{} ({}) was rejected: {}",
+            incoming_event.event_id(),
+            incoming_event.event_type(),
+            reason
+        );
+    }
+
+    Ok(AuthOutcome { rejection: verdict.err(), auth_events: auth_events.into_inner() })
+}
+
+fn check_event<E: Event>(
+    room_version: &RoomVersion,
+    incoming_event: impl Event,
+    prev_event: Option<impl Event>,
+    current_third_party_invite: Option<impl Event>,
+    fetch_state: impl Fn(&EventType, &str) -> Option<E>,
+) -> Result<Verdict> {
     #[derive(Deserialize)]
     struct RoomMemberContentFields {
         membership: Option<Raw<MembershipState>>,
+        join_authorised_via_users_server: Option<Raw<Box<UserId>>>,
     }
 
     info!(
@@ -130,32 +328,28 @@ pub fn auth_check<E: Event>(
 
         // If it has any previous events, reject
         if incoming_event.prev_events().next().is_some() {
-            warn!("the room creation event had previous events");
-            return Ok(false);
+            return Ok(Err(RejectionReason::CreateHasPrevEvents));
         }
 
         // If the domain of the room_id does not match the domain of the sender, reject
         if incoming_event.room_id().server_name() != sender.server_name() {
-            warn!("creation events server does not match sender");
-            return Ok(false); // creation events room id does not match senders
+            return Ok(Err(RejectionReason::CreateServerMismatch));
         }
 
         let content: RoomCreateContentFields = from_json_str(incoming_event.content().get())?;
 
         // If content.room_version is present and is not a recognized version, reject
         if content.room_version.map(|v| v.deserialize().is_err()).unwrap_or(false) {
-            warn!("invalid room version found in m.room.create event");
-            return Ok(false);
+            return Ok(Err(RejectionReason::CreateInvalidRoomVersion));
         }
 
         // If content has no creator field, reject
         if content.creator.is_none() {
-            warn!("no creator field found in m.room.create content");
-            return Ok(false);
+            return Ok(Err(RejectionReason::CreateMissingCreator));
         }
 
         info!("m.room.create event was allowed");
-        return Ok(true);
+        return Ok(Ok(()));
     }
 
     /*
@@ -184,9 +378,7 @@ pub fn auth_check<E: Event>(
 
     // 3. If event does not have m.room.create in auth_events reject
     if room_create_event.is_none() {
-        warn!("no m.room.create event in auth chain");
-
-        return Ok(false);
+        return Ok(Err(RejectionReason::MissingCreateEvent));
     }
 
     // [synapse] checks for federation here
@@ -199,12 +391,11 @@ pub fn auth_check<E: Event>(
 
         // If sender's domain doesn't matches state_key, reject
         if incoming_event.state_key() != Some(sender.server_name().as_str()) {
-            warn!("state_key does not match sender");
-            return Ok(false);
+            return Ok(Err(RejectionReason::AliasesStateKeyMismatch));
         }
 
         info!("m.room.aliases event was allowed");
-        return Ok(true);
+        return Ok(Ok(()));
     }
 
     let power_levels_event = fetch_state(&EventType::RoomPowerLevels, "");
@@ -213,23 +404,30 @@ pub fn auth_check<E: Event>(
     if *incoming_event.event_type() == EventType::RoomMember {
         info!("starting m.room.member check");
         let state_key = match incoming_event.state_key() {
-            None => {
-                warn!("no statekey in member event");
-                return Ok(false);
-            }
+            None => return Ok(Err(RejectionReason::MissingStateKey)),
             Some(s) => s,
         };
 
         let content: RoomMemberContentFields = from_json_str(incoming_event.content().get())?;
         if content.membership.and_then(|m| m.deserialize().ok()).is_none() {
-            warn!("no valid membership field found for m.room.member event content");
-            return Ok(false);
+            return Ok(Err(RejectionReason::InvalidMembership));
         }
 
         let target_user =
             <&UserId>::try_from(state_key).map_err(|e| Error::InvalidPdu(format!("{}", e)))?;
 
-        if !valid_membership_change(
+        let user_for_join_auth = content
+            .join_authorised_via_users_server
+            .as_ref()
+            .and_then(|u| u.deserialize().ok())
+            .filter(|_| room_version.restricted_join_rules);
+
+        let user_for_join_auth_membership_event = user_for_join_auth
+            .as_ref()
+            .and_then(|auth_user| fetch_state(&EventType::RoomMember, auth_user.as_str()));
+
+        let verdict = valid_membership_change(
+            room_version,
             target_user,
             fetch_state(&EventType::RoomMember, target_user.as_str()).as_ref(),
             sender,
@@ -239,21 +437,20 @@ pub fn auth_check<E: Event>(
             current_third_party_invite,
             power_levels_event.as_ref(),
             fetch_state(&EventType::RoomJoinRules, "").as_ref(),
-        )? {
-            return Ok(false);
-        }
+            user_for_join_auth.as_deref(),
+            user_for_join_auth_membership_event.as_ref(),
+        )?;
 
-        info!("m.room.member event was allowed");
-        return Ok(true);
+        if verdict.is_ok() {
+            info!("m.room.member event was allowed");
+        }
+        return Ok(verdict);
     }
 
     // If the sender's current membership state is not join, reject
     let sender_member_event = match sender_member_event {
         Some(mem) => mem,
-        None => {
-            warn!("sender not found in room");
-            return Ok(false);
-        }
+        None => return Ok(Err(RejectionReason::SenderNotJoined)),
     };
 
     let sender_membership_event_content: RoomMemberContentFields =
@@ -264,8 +461,7 @@ pub fn auth_check<E: Event>(
         .deserialize()?;
 
     if !matches!(membership_state, MembershipState::Join) {
-        warn!("sender's membership is not join");
-        return Ok(false);
+        return Ok(Err(RejectionReason::SenderNotJoined));
     }
 
     let sender_power_level = if let Some(pl) = &power_levels_event {
@@ -302,34 +498,31 @@ pub fn auth_check<E: Event>(
         };
 
         if sender_power_level < invite_level {
-            warn!("sender's cannot send invites in this room");
-            return Ok(false);
+            return Ok(Err(RejectionReason::InsufficientPowerLevel {
+                required: invite_level,
+                actual: sender_power_level,
+            }));
         }
     }
 
     // If the event type's required power level is greater than the sender's power level, reject
     // If the event has a state_key that starts with an @ and does not match the sender, reject.
-    if !can_send_event(&incoming_event, power_levels_event.as_ref(), sender_power_level) {
-        warn!("user cannot send event");
-        return Ok(false);
+    if let Err(reason) =
+        can_send_event(&incoming_event, power_levels_event.as_ref(), sender_power_level)
+    {
+        return Ok(Err(reason));
     }
 
     if *incoming_event.event_type() == EventType::RoomPowerLevels {
         info!("starting m.room.power_levels check");
 
-        if let Some(required_pwr_lvl) = check_power_levels(
+        if let Err(reason) = check_power_levels(
             room_version,
             &incoming_event,
             power_levels_event.as_ref(),
             sender_power_level,
         ) {
-            if !required_pwr_lvl {
-                warn!("power level was not allowed");
-                return Ok(false);
-            }
-        } else {
-            warn!("power level was not allowed");
-            return Ok(false);
+            return Ok(Err(reason));
         }
         info!("power levels event allowed");
     }
@@ -354,13 +547,15 @@ pub fn auth_check<E: Event>(
             .map(|c| c.redact)
             .unwrap_or_else(|| int!(50));
 
-        if !check_redaction(room_version, incoming_event, sender_power_level, redact_level)? {
-            return Ok(false);
+        if let Err(reason) =
+            check_redaction(room_version, incoming_event, sender_power_level, redact_level)
+        {
+            return Ok(Err(reason));
         }
     }
 
     info!("allowing event passed all checks");
-    Ok(true)
+    Ok(Ok(()))
 }
 
 // TODO deserializing the member, power, join_rules event contents is done in conduit
@@ -370,11 +565,14 @@ pub fn auth_check<E: Event>(
 /// * `user` - Information about the membership event and user making the request.
 /// * `prev_event` - The event that occurred immediately before the `user` event or None.
 /// * `auth_events` - The set of auth events that relate to a membership event.
+/// * `user_for_join_auth` - The `join_authorised_via_users_server` of a join in a room version that
+///   supports restricted join rules, along with that user's membership event.
 ///
 /// This is generated by calling `auth_types_for_event` with the membership event and the current
 /// State.
 #[allow(clippy::too_many_arguments)]
 fn valid_membership_change(
+    room_version: &RoomVersion,
     target_user: &UserId,
     target_user_membership_event: Option<impl Event>,
     sender: &UserId,
@@ -384,7 +582,9 @@ fn valid_membership_change(
     current_third_party_invite: Option<impl Event>,
     power_levels_event: Option<impl Event>,
     join_rules_event: Option<impl Event>,
-) -> Result<bool> {
+    user_for_join_auth: Option<&UserId>,
+    user_for_join_auth_membership_event: Option<impl Event>,
+) -> Result<Verdict> {
     // FIXME: field extracting could be bundled for `content`
     #[derive(Deserialize)]
     struct GetMembership {
@@ -414,6 +614,11 @@ fn valid_membership_change(
         None => MembershipState::Leave,
     };
 
+    let user_for_join_auth_membership = match &user_for_join_auth_membership_event {
+        Some(pdu) => from_json_str::<GetMembership>(pdu.content().get())?.membership,
+        None => MembershipState::Leave,
+    };
+
     let power_levels: RoomPowerLevelsEventContent = match &power_levels_event {
         Some(ev) => from_json_str(ev.content().get())?,
         None => RoomPowerLevelsEventContent::default(),
@@ -435,7 +640,7 @@ fn valid_membership_change(
 
     if let Some(prev) = prev_event {
         if *prev.event_type() == EventType::RoomCreate && prev.prev_events().next().is_none() {
-            return Ok(true);
+            return Ok(Ok(()));
         }
     }
 
@@ -444,28 +649,73 @@ fn valid_membership_change(
     let target_user_membership_event_id =
         target_user_membership_event.as_ref().map(|e| e.event_id());
 
+    // Checks that the sender has at least the `required` power level and, for kicks and bans,
+    // more power than the target.
+    let check_sender_power = |required: Int, check_target: bool| {
+        let actual = sender_power.copied().unwrap_or_default();
+        if sender_power.filter(|&p| p >= &required).is_none() {
+            warn!(?power_levels_event_id, "User does not have enough power");
+            Err(RejectionReason::InsufficientPowerLevel { required, actual })
+        } else if check_target && target_power >= sender_power {
+            warn!(?target_user_membership_event_id, "Target user has at least as much power");
+            Err(RejectionReason::TargetPowerLevelNotLower)
+        } else {
+            Ok(())
+        }
+    };
+
     Ok(match target_membership {
         MembershipState::Join => {
             if sender != target_user {
                 warn!("Can't make other user join");
-                false
+                Err(RejectionReason::JoinOnBehalfOfOtherUser)
             } else if let MembershipState::Ban = target_user_current_membership {
                 warn!(?target_user_membership_event_id, "Banned user can't join");
-                false
-            } else {
-                let allow = join_rules == JoinRule::Invite
-                    && (target_user_current_membership == MembershipState::Join
-                        || target_user_current_membership == MembershipState::Invite)
-                    || join_rules == JoinRule::Public;
-
-                if !allow {
+                Err(RejectionReason::TargetBanned)
+            } else if room_version.restricted_join_rules && is_restricted(&join_rules) {
+                if target_user_current_membership == MembershipState::Join
+                    || target_user_current_membership == MembershipState::Invite
+                {
+                    Ok(())
+                } else if let Some(user_for_join_auth) = user_for_join_auth {
+                    // The authorising user must be in the room and able to invite the target
+                    let auth_user_power = power_levels
+                        .users
+                        .get(user_for_join_auth)
+                        .unwrap_or(&power_levels.users_default);
+
+                    if user_for_join_auth_membership == MembershipState::Join
+                        && *auth_user_power >= power_levels.invite
+                    {
+                        Ok(())
+                    } else {
+                        warn!(
+                            %user_for_join_auth,
+                            ?power_levels_event_id,
+                            "User authorising the join is not joined or cannot invite",
+                        );
+                        Err(RejectionReason::JoinNotAuthorised)
+                    }
+                } else {
                     warn!(
-                        join_rules_event_id = ?join_rules_event.as_ref().map(|e| e.event_id()),
                         ?target_user_membership_event_id,
-                        "Can't join if join rules is not public and user is not invited / joined",
+                        "Can't join a restricted room without being invited or authorised",
                     );
+                    Err(RejectionReason::JoinNotAuthorised)
                 }
-                allow
+            } else if join_rules == JoinRule::Invite
+                && (target_user_current_membership == MembershipState::Join
+                    || target_user_current_membership == MembershipState::Invite)
+                || join_rules == JoinRule::Public
+            {
+                Ok(())
+            } else {
+                warn!(
+                    join_rules_event_id = ?join_rules_event.as_ref().map(|e| e.event_id()),
+                    ?target_user_membership_event_id,
+                    "Can't join if join rules is not public and user is not invited / joined",
+                );
+                Err(RejectionReason::JoinRulesForbidJoin)
             }
         }
         MembershipState::Invite => {
@@ -473,116 +723,95 @@ fn valid_membership_change(
             if let Some(tp_id) = third_party_invite.and_then(|i| i.deserialize().ok()) {
                 if target_user_current_membership == MembershipState::Ban {
                     warn!(?target_user_membership_event_id, "Can't invite banned user");
-                    false
+                    Err(RejectionReason::TargetBanned)
                 } else {
-                    let allow = verify_third_party_invite(
+                    verify_third_party_invite(
                         Some(target_user),
                         sender,
                         &tp_id,
                         current_third_party_invite,
-                    );
-                    if !allow {
-                        warn!("Third party invite invalid");
-                    }
-                    allow
+                    )
                 }
-            } else if !sender_is_joined
-                || target_user_current_membership == MembershipState::Join
-                || target_user_current_membership == MembershipState::Ban
-            {
-                warn!(
-                    ?target_user_membership_event_id,
-                    ?sender_membership_event_id,
-                    "Can't invite user if sender not joined or the user is currently joined or \
-                     banned",
-                );
-                false
+            } else if !sender_is_joined {
+                warn!(?sender_membership_event_id, "Can't invite user if sender not joined");
+                Err(RejectionReason::SenderNotJoined)
+            } else if target_user_current_membership == MembershipState::Join {
+                warn!(?target_user_membership_event_id, "Can't invite user who is already joined");
+                Err(RejectionReason::TargetAlreadyJoined)
+            } else if target_user_current_membership == MembershipState::Ban {
+                warn!(?target_user_membership_event_id, "Can't invite banned user");
+                Err(RejectionReason::TargetBanned)
             } else {
-                let allow = sender_power.filter(|&p| p >= &power_levels.invite).is_some();
-                if !allow {
-                    warn!(
-                        ?target_user_membership_event_id,
-                        ?power_levels_event_id,
-                        "User does not have enough power to invite",
-                    );
-                }
-                allow
+                check_sender_power(power_levels.invite, false)
             }
         }
         MembershipState::Leave => {
             if sender == target_user {
-                let allow = target_user_current_membership == MembershipState::Join
-                    || target_user_current_membership == MembershipState::Invite;
-                if !allow {
+                if target_user_current_membership == MembershipState::Join
+                    || target_user_current_membership == MembershipState::Invite
+                {
+                    Ok(())
+                } else {
                     warn!(?target_user_membership_event_id, "Can't leave if not invited or joined");
+                    Err(RejectionReason::NotInvitedOrJoined)
                 }
-                allow
-            } else if !sender_is_joined
-                || target_user_current_membership == MembershipState::Ban
-                    && sender_power.filter(|&p| p < &power_levels.ban).is_some()
-            {
-                warn!(
-                    ?target_user_membership_event_id,
-                    ?sender_membership_event_id,
-                    "Can't kick if sender not joined or user is already banned",
-                );
-                false
+            } else if !sender_is_joined {
+                warn!(?sender_membership_event_id, "Can't kick if sender not joined");
+                Err(RejectionReason::SenderNotJoined)
+            } else if target_user_current_membership == MembershipState::Ban {
+                // Unbanning requires the ban level
+                check_sender_power(power_levels.ban, false)
+                    .and_then(|_| check_sender_power(power_levels.kick, true))
             } else {
-                let allow = sender_power.filter(|&p| p >= &power_levels.kick).is_some()
-                    && target_power < sender_power;
-                if !allow {
-                    warn!(
-                        ?target_user_membership_event_id,
-                        ?power_levels_event_id,
-                        "User does not have enough power to kick",
-                    );
-                }
-                allow
+                check_sender_power(power_levels.kick, true)
             }
         }
         MembershipState::Ban => {
             if !sender_is_joined {
                 warn!(?sender_membership_event_id, "Can't ban user if sender is not joined");
-                false
+                Err(RejectionReason::SenderNotJoined)
             } else {
-                let allow = sender_power.filter(|&p| p >= &power_levels.ban).is_some()
-                    && target_power < sender_power;
-                if !allow {
-                    warn!(
-                        ?target_user_membership_event_id,
-                        ?power_levels_event_id,
-                        "User does not have enough power to ban",
-                    );
-                }
-                allow
+                check_sender_power(power_levels.ban, true)
             }
         }
         _ => {
             warn!("Unknown membership transition");
-            false
+            Err(RejectionReason::UnknownMembership)
         }
     })
 }
 
+/// Whether the join rule only allows users meeting certain conditions to join.
+fn is_restricted(join_rule: &JoinRule) -> bool {
+    match join_rule {
+        #[cfg(feature = "unstable-pre-spec")]
+        JoinRule::Restricted(_) => true,
+        _ => false,
+    }
+}
+
 /// Is the user allowed to send a specific event based on the rooms power levels.
 ///
 /// Does the event have the correct userId as its state_key if it's not the "" state_key.
-fn can_send_event(event: impl Event, ple: Option<impl Event>, user_level: Int) -> bool {
+fn can_send_event(event: impl Event, ple: Option<impl Event>, user_level: Int) -> Verdict {
     let event_type_power_level = get_send_level(event.event_type(), event.state_key(), ple);
 
     debug!("{} ev_type {} usr {}", event.event_id(), event_type_power_level, user_level);
 
     if user_level < event_type_power_level {
-        return false;
+        return Err(RejectionReason::InsufficientPowerLevel {
+            required: event_type_power_level,
+            actual: user_level,
+        });
     }
 
     if event.state_key().map_or(false, |k| k.starts_with('@'))
         && event.state_key() != Some(event.sender().as_str())
     {
-        return false; // permission required to post in this room
+        return Err(RejectionReason::StateKeyNotSender); // permission required to post in this room
     }
 
-    true
+    Ok(())
 }
 
 /// Confirm that the event sender has the required power levels.
@@ -591,23 +820,23 @@ fn check_power_levels(
     power_event: impl Event,
     previous_power_event: Option<impl Event>,
     user_level: Int,
-) -> Option<bool> {
+) -> Verdict {
     match power_event.state_key() {
         Some("") => {}
         Some(key) => {
             error!("m.room.power_levels event has non-empty state key: {}", key);
-            return None;
+            return Err(RejectionReason::PowerLevelsInvalidStateKey);
         }
         None => {
             error!("check_power_levels requires an m.room.power_levels *state* event argument");
-            return None;
+            return Err(RejectionReason::PowerLevelsInvalidStateKey);
         }
     }
 
     let current_state = match previous_power_event {
         Some(current_state) => current_state,
         // If there is no previous m.room.power_levels event in the room, allow
-        None => return Some(true),
+        None => return Ok(()),
     };
 
     // If users key in content is not a dictionary with keys that are valid user IDs
@@ -658,7 +887,8 @@ fn check_power_levels(
         // If the current value is equal to the sender's current power level, reject
         if user != power_event.sender() && old_level == Some(&user_level) {
             warn!("m.room.power_level cannot remove ops == to own");
-            return Some(false); // cannot remove ops level == to own
+            // cannot remove ops level == to own
+            return Err(RejectionReason::PowerLevelsChangeNotAllowed);
         }
 
         // If the current value is higher than the sender's current power level, reject
@@ -667,7 +897,8 @@ fn check_power_levels(
         let new_level_too_big = new_level > Some(&user_level);
         if old_level_too_big || new_level_too_big {
             warn!("m.room.power_level failed to add ops > than own");
-            return Some(false); // cannot add ops greater than own
+            // cannot add ops greater than own
+            return Err(RejectionReason::PowerLevelsChangeNotAllowed);
         }
     }
 
@@ -685,7 +916,8 @@ fn check_power_levels(
         let new_level_too_big = new_level > Some(&user_level);
         if old_level_too_big || new_level_too_big {
             warn!("m.room.power_level failed to add ops > than own");
-            return Some(false); // cannot add ops greater than own
+            // cannot add ops greater than own
+            return Err(RejectionReason::PowerLevelsChangeNotAllowed);
         }
     }
 
@@ -700,7 +932,8 @@ fn check_power_levels(
             let new_level_too_big = new_level > user_level;
             if old_level_too_big || new_level_too_big {
                 warn!("m.room.power_level failed to add ops > than own");
-                return Some(false); // cannot add ops greater than own
+                // cannot add ops greater than own
+                return Err(RejectionReason::PowerLevelsChangeNotAllowed);
             }
         }
     }
@@ -716,12 +949,12 @@ fn check_power_levels(
 
             if old_level_too_big || new_level_too_big {
                 warn!("cannot add ops > than own");
-                return Some(false);
+                return Err(RejectionReason::PowerLevelsChangeNotAllowed);
             }
         }
     }
 
-    Some(true)
+    Ok(())
 }
 
 fn get_deserialize_levels(
@@ -741,10 +974,10 @@ fn check_redaction(
     redaction_event: impl Event,
     user_level: Int,
     redact_level: Int,
-) -> Result<bool> {
+) -> Verdict {
     if user_level >= redact_level {
         info!("redaction allowed via power levels");
-        return Ok(true);
+        return Ok(());
     }
 
     // If the domain of the event_id of the event being redacted is the same as the
@@ -753,10 +986,10 @@ fn check_redaction(
         == redaction_event.redacts().as_ref().and_then(|&id| id.borrow().server_name())
     {
         info!("redaction event allowed via room version 1 rules");
-        return Ok(true);
+        return Ok(());
     }
 
-    Ok(false)
+    Err(RejectionReason::RedactionNotAllowed)
 }
 
 /// Helper function to fetch the power level needed to send an event of type
@@ -788,59 +1021,69 @@ fn verify_third_party_invite(
     sender: &UserId,
     tp_id: &ThirdPartyInvite,
     current_third_party_invite: Option<impl Event>,
-) -> bool {
+) -> Verdict {
     // 1. Check for user being banned happens before this is called
     // checking for mxid and token keys is done by ruma when deserializing
 
     // The state key must match the invitee
     if target_user != Some(&tp_id.signed.mxid) {
-        return false;
+        return Err(RejectionReason::ThirdPartyInviteMxidMismatch);
     }
 
     // If there is no m.room.third_party_invite event in the current room state
     // with state_key matching token, reject
-    if let Some(current_tpid) = current_third_party_invite {
-        if current_tpid.state_key() != Some(&tp_id.signed.token) {
-            return false;
-        }
+    let current_tpid = match current_third_party_invite {
+        Some(current_tpid) if current_tpid.state_key() == Some(&tp_id.signed.token) => current_tpid,
+        _ => return Err(RejectionReason::MissingThirdPartyInvite),
+    };
 
-        if sender != current_tpid.sender() {
-            return false;
-        }
+    if sender != current_tpid.sender() {
+        return Err(RejectionReason::ThirdPartyInviteSenderMismatch);
+    }
 
-        // If any signature in signed matches any public key in the m.room.third_party_invite event,
-        // allow
-        if let Ok(tpid_ev) =
-            from_json_str::<RoomThirdPartyInviteEventContent>(current_tpid.content().get())
-        {
-            // A list of public keys in the public_keys field
-            for key in tpid_ev.public_keys.unwrap_or_default() {
-                if key.public_key == tp_id.signed.token {
-                    return true;
-                }
+    // If any signature in signed matches any public key in the m.room.third_party_invite event,
+    // allow
+    if let Ok(tpid_ev) =
+        from_json_str::<RoomThirdPartyInviteEventContent>(current_tpid.content().get())
+    {
+        // A list of public keys in the public_keys field
+        for key in tpid_ev.public_keys.unwrap_or_default() {
+            if key.public_key == tp_id.signed.token {
+                return Ok(());
             }
-            // A single public key in the public_key field
-            tpid_ev.public_key == tp_id.signed.token
-        } else {
-            false
         }
-    } else {
-        false
+        // A single public key in the public_key field
+        if tpid_ev.public_key == tp_id.signed.token {
+            return Ok(());
+        }
     }
+
+    Err(RejectionReason::ThirdPartyInviteBadSignature)
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
+    use js_int::int;
+    use ruma_events::EventType;
+    #[cfg(feature = "unstable-pre-spec")]
+    use ruma_identifiers::UserId;
+    #[cfg(feature = "unstable-pre-spec")]
+    use serde_json::Value as JsonValue;
+    use serde_json::{json, value::to_raw_value as to_raw_json_value};
+
+    #[cfg(feature = "unstable-pre-spec")]
+    use crate::test_utils::{bob, zara};
     use crate::{
-        event_auth::valid_membership_change,
+        event_auth::{auth_check, auth_types_for_event, valid_membership_change, RejectionReason},
+        room_version::RoomVersion,
         test_utils::{
-            alice, charlie, member_content_ban, to_pdu_event, StateEvent, INITIAL_EVENTS,
+            alice, charlie, ella, event_id, member_content_ban, to_pdu_event, StateEvent,
+            INITIAL_EVENTS,
         },
         Event, StateMap,
     };
-    use ruma_events::EventType;
 
     #[test]
     fn test_ban_pass() {
@@ -873,6 +1116,7 @@ mod tests {
         let sender = alice();
 
         assert!(valid_membership_change(
+            &RoomVersion::VERSION6,
             &target_user,
             fetch_state(EventType::RoomMember, target_user.to_string()),
             &sender,
@@ -882,8 +1126,11 @@ mod tests {
             None::<StateEvent>,
             fetch_state(EventType::RoomPowerLevels, "".to_owned()),
             fetch_state(EventType::RoomJoinRules, "".to_owned()),
+            None,
+            None::<StateEvent>,
         )
-        .unwrap());
+        .unwrap()
+        .is_ok());
     }
 
     #[test]
@@ -916,7 +1163,165 @@ mod tests {
         let target_user = alice();
         let sender = charlie();
 
-        assert!(!valid_membership_change(
+        assert_eq!(
+            valid_membership_change(
+                &RoomVersion::VERSION6,
+                &target_user,
+                fetch_state(EventType::RoomMember, target_user.to_string()),
+                &sender,
+                fetch_state(EventType::RoomMember, sender.to_string()),
+                requester.content(),
+                prev_event,
+                None::<StateEvent>,
+                fetch_state(EventType::RoomPowerLevels, "".to_owned()),
+                fetch_state(EventType::RoomJoinRules, "".to_owned()),
+                None,
+                None::<StateEvent>,
+            )
+            .unwrap(),
+            Err(RejectionReason::InsufficientPowerLevel { required: int!(50), actual: int!(0) })
+        );
+    }
+
+    #[test]
+    fn test_auth_check_sender_not_joined() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+        let events = INITIAL_EVENTS();
+
+        let auth_events = events
+            .values()
+            .map(|ev| {
+                ((ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()), Arc::clone(ev))
+            })
+            .collect::<StateMap<_>>();
+
+        let requester = to_pdu_event(
+            "HELLO",
+            ella(),
+            EventType::RoomTopic,
+            Some(""),
+            to_raw_json_value(&json!({ "topic": "Hello" })).unwrap(),
+            &["CREATE", "IPOWER"],
+            &["IMC"],
+        );
+
+        let outcome = auth_check(
+            &RoomVersion::VERSION6,
+            &requester,
+            None::<StateEvent>,
+            None::<StateEvent>,
+            |ty, key| auth_events.get(&(ty.clone(), key.to_owned())).cloned(),
+        )
+        .unwrap();
+
+        assert!(!outcome.is_allowed());
+        assert_eq!(outcome.rejection, Some(RejectionReason::SenderNotJoined));
+        assert_eq!(outcome.auth_events, vec![event_id("CREATE"), event_id("IPOWER")]);
+    }
+
+    #[test]
+    fn test_auth_check_insufficient_power_level() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+        let events = INITIAL_EVENTS();
+
+        let auth_events = events
+            .values()
+            .map(|ev| {
+                ((ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()), Arc::clone(ev))
+            })
+            .collect::<StateMap<_>>();
+
+        let requester = to_pdu_event(
+            "HELLO",
+            charlie(),
+            EventType::RoomTopic,
+            Some(""),
+            to_raw_json_value(&json!({ "topic": "Hello" })).unwrap(),
+            &["CREATE", "IMC", "IPOWER"],
+            &["IMC"],
+        );
+
+        let outcome = auth_check(
+            &RoomVersion::VERSION6,
+            &requester,
+            None::<StateEvent>,
+            None::<StateEvent>,
+            |ty, key| auth_events.get(&(ty.clone(), key.to_owned())).cloned(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            outcome.rejection,
+            Some(RejectionReason::InsufficientPowerLevel { required: int!(50), actual: int!(0) })
+        );
+        assert_eq!(
+            outcome.auth_events,
+            vec![event_id("CREATE"), event_id("IPOWER"), event_id("IMC")]
+        );
+    }
+
+    #[cfg(feature = "unstable-pre-spec")]
+    fn restricted_join(
+        authorising_user: Option<Box<UserId>>,
+        power_levels: JsonValue,
+    ) -> crate::Result<super::Verdict> {
+        let mut events = INITIAL_EVENTS();
+        *events.get_mut(&event_id("IJR")).unwrap() = to_pdu_event(
+            "IJR",
+            alice(),
+            EventType::RoomJoinRules,
+            Some(""),
+            to_raw_json_value(&json!({
+                "join_rule": "restricted",
+                "allow": [{ "type": "m.room_membership", "room_id": "!other:foo" }],
+            }))
+            .unwrap(),
+            &["CREATE", "IMA", "IPOWER"],
+            &["IPOWER"],
+        );
+        *events.get_mut(&event_id("IPOWER")).unwrap() = to_pdu_event(
+            "IPOWER",
+            alice(),
+            EventType::RoomPowerLevels,
+            Some(""),
+            to_raw_json_value(&power_levels).unwrap(),
+            &["CREATE", "IMA"],
+            &["IMA"],
+        );
+
+        let prev_event =
+            events.values().find(|ev| ev.event_id.as_str().contains("IMC")).map(Arc::clone);
+
+        let auth_events = events
+            .values()
+            .map(|ev| {
+                ((ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()), Arc::clone(ev))
+            })
+            .collect::<StateMap<_>>();
+
+        let content = json!({
+            "membership": "join",
+            "join_authorised_via_users_server": authorising_user,
+        });
+
+        let requester = to_pdu_event(
+            "HELLO",
+            ella(),
+            EventType::RoomMember,
+            Some(ella().as_str()),
+            to_raw_json_value(&content).unwrap(),
+            &[],
+            &["IMC"],
+        );
+
+        let fetch_state = |ty, key| auth_events.get(&(ty, key)).cloned();
+        let target_user = ella();
+        let sender = ella();
+
+        valid_membership_change(
+            &RoomVersion::VERSION8,
             &target_user,
             fetch_state(EventType::RoomMember, target_user.to_string()),
             &sender,
@@ -926,7 +1331,94 @@ mod tests {
             None::<StateEvent>,
             fetch_state(EventType::RoomPowerLevels, "".to_owned()),
             fetch_state(EventType::RoomJoinRules, "".to_owned()),
+            authorising_user.as_deref(),
+            authorising_user
+                .as_ref()
+                .and_then(|user| fetch_state(EventType::RoomMember, user.to_string())),
+        )
+    }
+
+    #[cfg(feature = "unstable-pre-spec")]
+    #[test]
+    fn test_restricted_join_authorised() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let power_levels = json!({ "users": { alice(): 100 }, "invite": 50 });
+        assert!(restricted_join(Some(alice()), power_levels).unwrap().is_ok());
+    }
+
+    #[cfg(feature = "unstable-pre-spec")]
+    #[test]
+    fn test_restricted_join_without_authorising_user() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let power_levels = json!({ "users": { alice(): 100 }, "invite": 50 });
+        assert_eq!(
+            restricted_join(None, power_levels).unwrap(),
+            Err(RejectionReason::JoinNotAuthorised)
+        );
+    }
+
+    #[cfg(feature = "unstable-pre-spec")]
+    #[test]
+    fn test_restricted_join_authorising_user_cannot_invite() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let power_levels = json!({ "users": { alice(): 100 }, "invite": 50 });
+        assert_eq!(
+            restricted_join(Some(bob()), power_levels).unwrap(),
+            Err(RejectionReason::JoinNotAuthorised)
+        );
+
+        let power_levels = json!({ "users": { alice(): 100, bob(): 50 }, "invite": 50 });
+        assert!(restricted_join(Some(bob()), power_levels).unwrap().is_ok());
+    }
+
+    #[cfg(feature = "unstable-pre-spec")]
+    #[test]
+    fn test_restricted_join_authorising_user_not_joined() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let power_levels = json!({ "users": { alice(): 100, zara(): 100 }, "invite": 50 });
+        assert_eq!(
+            restricted_join(Some(zara()), power_levels).unwrap(),
+            Err(RejectionReason::JoinNotAuthorised)
+        );
+    }
+
+    #[test]
+    fn test_auth_types_for_restricted_join() {
+        let content = to_raw_json_value(&json!({
+            "membership": "join",
+            "join_authorised_via_users_server": alice(),
+        }))
+        .unwrap();
+
+        let auth_member_key = (EventType::RoomMember, alice().to_string());
+
+        let auth_types = auth_types_for_event(
+            &EventType::RoomMember,
+            &ella(),
+            Some(ella().as_str()),
+            &content,
+            &RoomVersion::VERSION6,
+        )
+        .unwrap();
+        assert!(!auth_types.contains(&auth_member_key));
+
+        let room_version = RoomVersion { restricted_join_rules: true, ..RoomVersion::VERSION6 };
+        let auth_types = auth_types_for_event(
+            &EventType::RoomMember,
+            &ella(),
+            Some(ella().as_str()),
+            &content,
+            &room_version,
         )
-        .unwrap());
+        .unwrap();
+        assert!(auth_types.contains(&auth_member_key));
     }
 }
@@ -2,6 +2,7 @@ use std::{
     borrow::Borrow,
     cmp::Reverse,
     collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+    future::Future,
     hash::Hash,
 };
 
@@ -17,15 +18,20 @@ use serde::Deserialize;
 use serde_json::from_str as from_json_str;
 use tracing::{debug, info, trace, warn};
 
+mod auth_chain;
 mod error;
 pub mod event_auth;
+mod room_dag;
 pub mod room_version;
 mod state_event;
 #[cfg(test)]
 mod test_utils;
+mod v1;
 
+pub use auth_chain::AuthChainCache;
 pub use error::{Error, Result};
-pub use event_auth::{auth_check, auth_types_for_event};
+pub use event_auth::{auth_check, auth_types_for_event, AuthOutcome, RejectionReason};
+pub use room_dag::{DagState, RoomDag};
 pub use room_version::RoomVersion;
 pub use state_event::Event;
 
@@ -37,13 +43,18 @@ pub type StateMap<T> = HashMap<(EventType, String), T>;
 /// Internally `StateResolution` builds a graph and an auth chain to allow for state conflict
 /// resolution.
 ///
+/// The algorithm used depends on the `state_res` of the given room version. Rooms using
+/// [`StateResolutionVersion::V1`](room_version::StateResolutionVersion::V1) are resolved by
+/// ordering conflicting events by their depth and event ID, in which case `auth_chain_sets` is not
+/// used.
+///
 /// ## Arguments
 ///
 /// * `state_sets` - The incoming state to resolve. Each `StateMap` represents a possible fork in
 ///   the state of a room.
 ///
 /// * `auth_chain_sets` - The full recursive set of `auth_events` for each event in the
-///   `state_sets`.
+///   `state_sets`, see [`AuthChainCache::auth_chain_sets`].
 ///
 /// * `fetch_event` - Any event not found in the `event_map` will defer to this closure to find the
 ///   event.
@@ -79,6 +90,11 @@ where
     info!("conflicting events: {}", conflicting.len());
     debug!("{:?}", conflicting);
 
+    let room_version = RoomVersion::new(room_version)?;
+    if let room_version::StateResolutionVersion::V1 = room_version.state_res {
+        return v1::resolve(&room_version, clean, conflicting, fetch_event);
+    }
+
     // `all_conflicted` contains unique items
     // synapse says `full_set = {eid for eid in full_conflicted_set if eid in event_map}`
     let all_conflicted: HashSet<_> = get_auth_chain_diff(auth_chain_sets)
@@ -108,7 +124,6 @@ where
     debug!("sorted control events: {}", sorted_control_levels.len());
     trace!("{:?}", sorted_control_levels);
 
-    let room_version = RoomVersion::new(room_version)?;
     // Sequentially auth check each control event.
     let resolved_control =
         iterative_auth_check(&room_version, &sorted_control_levels, clean.clone(), &fetch_event)?;
@@ -153,6 +168,75 @@ where
     Ok(resolved_state)
 }
 
+/// Resolve sets of state events, fetching the events that are needed on demand.
+///
+/// This is the same as [`resolve`], except that the events are not looked up one at a time
+/// through a synchronous closure. Instead, starting from the conflicting state, the events the
+/// algorithm needs are requested in batches through `fetch_events`, which is given a list of
+/// event IDs and returns the events it could find. Events that are not returned are treated as
+/// missing, exactly like a `None` from the closure given to [`resolve`].
+///
+/// If `fetch_events` fails, resolution is aborted with [`Error::FetchFailed`].
+///
+/// ## Invariants
+///
+/// The caller of `resolve_async` must ensure that all the events are from the same room.
+pub async fn resolve_async<'a, E, SetIter, F, Fut, FetchError>(
+    room_version: &RoomVersionId,
+    state_sets: impl IntoIterator<IntoIter = SetIter>,
+    auth_chain_sets: Vec<HashSet<E::Id>>,
+    fetch_events: F,
+) -> Result<StateMap<E::Id>>
+where
+    E: Event + Clone,
+    E::Id: 'a,
+    SetIter: Iterator<Item = &'a StateMap<E::Id>> + Clone,
+    F: Fn(Vec<E::Id>) -> Fut,
+    Fut: Future<Output = std::result::Result<Vec<E>, FetchError>>,
+    FetchError: std::error::Error + 'static,
+{
+    let state_sets = state_sets.into_iter();
+    let (clean, conflicting) = separate(state_sets.clone());
+
+    let mut events = HashMap::new();
+    if !conflicting.is_empty() {
+        // The conflicted events are the ones that get sorted and auth checked, they need their
+        // auth events and prev events. Everything else is only looked up in the state.
+        let conflicted: HashSet<E::Id> = get_auth_chain_diff(auth_chain_sets.clone())
+            .chain(conflicting.into_iter().flat_map(|(_k, v)| v))
+            .collect();
+
+        let mut requested: HashSet<_> = conflicted.iter().chain(clean.values()).cloned().collect();
+        let mut to_fetch: Vec<_> = requested.iter().cloned().collect();
+
+        while !to_fetch.is_empty() {
+            debug!("fetching {} events", to_fetch.len());
+
+            let fetched =
+                fetch_events(to_fetch).await.map_err(|e| Error::FetchFailed(Box::new(e)))?;
+
+            to_fetch = Vec::new();
+            for event in fetched {
+                // Walking the power level events is needed to build the mainline
+                let mut next: Vec<_> = if conflicted.contains(event.event_id().borrow()) {
+                    event.auth_events().chain(event.prev_events()).cloned().collect()
+                } else if is_type_and_key(&event, &EventType::RoomPowerLevels, "") {
+                    event.auth_events().cloned().collect()
+                } else {
+                    Vec::new()
+                };
+
+                next.retain(|id| requested.insert(id.clone()));
+                to_fetch.extend(next);
+
+                events.insert(event.event_id().clone(), event);
+            }
+        }
+    }
+
+    resolve(room_version, state_sets, auth_chain_sets, |id| events.get(id).cloned())
+}
+
 /// Split the events that have no conflicts from those that are conflicting.
 ///
 /// The return tuple looks like `(unconflicted, conflicted)`.
@@ -441,6 +525,7 @@ fn iterative_auth_check<E: Event + Clone>(
             event.sender(),
             Some(state_key),
             event.content(),
+            room_version,
         )? {
             if let Some(ev_id) = resolved_state.get(&key) {
                 if let Some(event) = fetch_event(ev_id.borrow()) {
@@ -465,13 +550,15 @@ fn iterative_auth_check<E: Event + Clone>(
             })
         });
 
-        if auth_check(
+        let outcome = auth_check(
             room_version,
             &event,
             most_recent_prev_event.as_ref(),
             current_third_party.as_ref(),
             |ty, key| auth_events.get(&(ty.clone(), key.to_owned())).cloned(),
-        )? {
+        )?;
+
+        if outcome.is_allowed() {
             // add event to resolved state map
             resolved_state
                 .insert((event.event_type().to_owned(), state_key.to_owned()), event_id.clone());
@@ -643,7 +730,9 @@ fn is_power_event(event: impl Event) -> bool {
 #[cfg(test)]
 mod tests {
     use std::{
+        cell::RefCell,
         collections::{HashMap, HashSet},
+        future, io,
         sync::Arc,
     };
 
@@ -666,7 +755,7 @@ mod tests {
             alice, bob, charlie, do_check, ella, event_id, member_content_ban, member_content_join,
             room_id, to_init_pdu_event, to_pdu_event, zara, StateEvent, TestStore, INITIAL_EVENTS,
         },
-        Event, StateMap,
+        Error, Event, StateMap,
     };
 
     fn test_event_sort() {
@@ -1073,6 +1162,102 @@ mod tests {
         assert_eq!(expected, resolved)
     }
 
+    #[tokio::test]
+    async fn test_resolve_async() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let mut store = TestStore::<StateEvent>(hashmap! {});
+
+        // build up the DAG
+        let (state_at_bob, state_at_charlie, expected) = store.set_up();
+
+        let ev_map = store.0.clone();
+        let state_sets = [state_at_bob, state_at_charlie];
+        let requested = RefCell::new(Vec::new());
+        let resolved = match crate::resolve_async(
+            &RoomVersionId::V2,
+            &state_sets,
+            state_sets
+                .iter()
+                .map(|map| {
+                    store.auth_event_ids(room_id(), map.values().cloned().collect()).unwrap()
+                })
+                .collect(),
+            |ids: Vec<Box<EventId>>| {
+                let events =
+                    ids.iter().filter_map(|id| ev_map.get(id).map(Arc::clone)).collect::<Vec<_>>();
+                requested.borrow_mut().extend(ids);
+                future::ready(Ok::<_, io::Error>(events))
+            },
+        )
+        .await
+        {
+            Ok(state) => state,
+            Err(e) => panic!("{}", e),
+        };
+
+        assert_eq!(expected, resolved);
+
+        // Every event is only requested once
+        let requested = requested.into_inner();
+        assert_eq!(requested.len(), requested.iter().collect::<HashSet<_>>().len());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_async_no_conflicts() {
+        let state = INITIAL_EVENTS()
+            .values()
+            .map(|ev| {
+                (
+                    (ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()),
+                    ev.event_id.clone(),
+                )
+            })
+            .collect::<StateMap<_>>();
+
+        let state_sets = [state.clone(), state.clone()];
+        let resolved = crate::resolve_async(
+            &RoomVersionId::V6,
+            &state_sets,
+            Vec::new(),
+            |_ids: Vec<Box<EventId>>| -> future::Ready<Result<Vec<Arc<StateEvent>>, io::Error>> {
+                panic!("no events need to be fetched without conflicts")
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(state, resolved);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_async_fetch_error() {
+        let mut store = TestStore::<StateEvent>(hashmap! {});
+        let (state_at_bob, state_at_charlie, _) = store.set_up();
+
+        let state_sets = [state_at_bob, state_at_charlie];
+        let result = crate::resolve_async(
+            &RoomVersionId::V6,
+            &state_sets,
+            state_sets
+                .iter()
+                .map(|map| {
+                    store.auth_event_ids(room_id(), map.values().cloned().collect()).unwrap()
+                })
+                .collect(),
+            |_ids: Vec<Box<EventId>>| {
+                future::ready(Err::<Vec<Arc<StateEvent>>, _>(io::Error::new(
+                    io::ErrorKind::Other,
+                    "database unavailable",
+                )))
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::FetchFailed(_))));
+    }
+
     #[test]
     fn test_lexicographical_sort() {
         let _ =
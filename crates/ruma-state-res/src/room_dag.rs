@@ -0,0 +1,330 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+};
+
+use js_int::int;
+use ruma_events::EventType;
+use ruma_identifiers::{EventId, RoomVersionId};
+use tracing::{debug, info, warn};
+
+use crate::{
+    auth_check, auth_types_for_event, lexicographical_topological_sort, resolve,
+    room_version::RoomVersion, AuthChainCache, Error, Event, Result, StateMap,
+};
+
+/// A set of events of a single room, connected through their `prev_events`.
+///
+/// Use [`RoomDag::compute_state`] to get the state before and after every event of the DAG.
+#[derive(Clone, Debug)]
+pub struct RoomDag<E: Event> {
+    events: HashMap<E::Id, E>,
+}
+
+impl<E: Event + Clone> RoomDag<E> {
+    /// Creates a new `RoomDag` from the given events.
+    ///
+    /// The DAG should start at the room's `m.room.create` event: `prev_events` that are not part
+    /// of the given events are ignored, so an event that only references unknown events is
+    /// authorized against an empty state. `auth_events` that are not part of the given events
+    /// are skipped when computing auth chains.
+    pub fn new(events: impl IntoIterator<Item = E>) -> Self {
+        Self { events: events.into_iter().map(|ev| (ev.event_id().clone(), ev)).collect() }
+    }
+
+    /// Returns the event with the given ID, if it is part of the DAG.
+    pub fn get(&self, event_id: &EventId) -> Option<&E> {
+        self.events.get(event_id)
+    }
+
+    /// Computes the state before and after every event of the DAG.
+    ///
+    /// Events are visited in topological order. The state before an event is the state after its
+    /// single prev event, or the result of [`resolve`] over the state after each of its prev
+    /// events. The event is then checked with [`auth_check`] against the state before it; rejected
+    /// events don't change the state.
+    pub fn compute_state(&self, room_version: &RoomVersionId) -> Result<DagState<E::Id>> {
+        info!("computing the state of {} events", self.events.len());
+
+        let auth_rules = RoomVersion::new(room_version)?;
+
+        // event -> the prev events that are part of the DAG
+        let graph: HashMap<E::Id, HashSet<E::Id>> = self
+            .events
+            .iter()
+            .map(|(id, event)| {
+                let prev_events = event
+                    .prev_events()
+                    .filter(|&prev_id| self.events.contains_key(prev_id.borrow()))
+                    .cloned()
+                    .collect();
+                (id.clone(), prev_events)
+            })
+            .collect();
+
+        let sorted = lexicographical_topological_sort(&graph, |event_id| {
+            let event = self
+                .get(event_id)
+                .ok_or_else(|| Error::NotFound(format!("{} not found in the DAG", event_id)))?;
+            Ok((int!(0), event.origin_server_ts()))
+        })?;
+
+        let mut auth_chains = AuthChainCache::skipping_missing_events();
+        let mut dag_state = DagState {
+            state_before: HashMap::new(),
+            state_after: HashMap::new(),
+            forward_extremities: HashSet::new(),
+            rejected: HashSet::new(),
+        };
+
+        for event_id in sorted {
+            let event = &self.events[event_id.borrow()];
+
+            let state_sets: Vec<_> = event
+                .prev_events()
+                .filter_map(|prev_id| dag_state.state_after.get(prev_id.borrow()))
+                .collect();
+
+            let state_before = match state_sets.as_slice() {
+                [] => StateMap::new(),
+                [state] => (*state).clone(),
+                _ => {
+                    debug!("resolving {} forks before {}", state_sets.len(), event_id);
+
+                    let auth_chain_sets = auth_chains
+                        .auth_chain_sets(state_sets.iter().copied(), |id| self.get(id).cloned())?;
+
+                    resolve(room_version, state_sets, auth_chain_sets, |id| self.get(id).cloned())?
+                }
+            };
+
+            let mut state_after = state_before.clone();
+            if self.is_allowed(&auth_rules, event, &state_before)? {
+                if let Some(state_key) = event.state_key() {
+                    state_after.insert(
+                        (event.event_type().clone(), state_key.to_owned()),
+                        event_id.clone(),
+                    );
+                }
+            } else {
+                warn!("event {} was rejected", event_id);
+                dag_state.rejected.insert(event_id.clone());
+            }
+
+            dag_state.state_before.insert(event_id.clone(), state_before);
+            dag_state.state_after.insert(event_id, state_after);
+        }
+
+        // The accepted events that no other accepted event references as a prev event.
+        let referenced: HashSet<&E::Id> = self
+            .events
+            .iter()
+            .filter(|&(id, _)| !dag_state.rejected.contains::<E::Id>(id))
+            .flat_map(|(_, event)| event.prev_events())
+            .collect();
+        dag_state.forward_extremities = self
+            .events
+            .keys()
+            .filter(|&id| !referenced.contains(&id) && !dag_state.rejected.contains::<E::Id>(id))
+            .cloned()
+            .collect();
+
+        Ok(dag_state)
+    }
+
+    /// Check `event` against the authorization rules with the auth events selected from the
+    /// state before it.
+    fn is_allowed(
+        &self,
+        room_version: &RoomVersion,
+        event: &E,
+        state_before: &StateMap<E::Id>,
+    ) -> Result<bool> {
+        let auth_types = auth_types_for_event(
+            event.event_type(),
+            event.sender(),
+            event.state_key(),
+            event.content(),
+            room_version,
+        )?;
+
+        let auth_events: StateMap<&E> = auth_types
+            .into_iter()
+            .filter_map(|key| {
+                let event = self.get(state_before.get(&key)?.borrow())?;
+                Some((key, event))
+            })
+            .collect();
+
+        let most_recent_prev_event =
+            event.prev_events().filter_map(|id| self.get(id.borrow())).next_back();
+
+        let current_third_party = auth_events
+            .values()
+            .find(|pdu| *pdu.event_type() == EventType::RoomThirdPartyInvite)
+            .copied();
+
+        auth_check(room_version, event, most_recent_prev_event, current_third_party, |ty, key| {
+            auth_events.get(&(ty.clone(), key.to_owned())).copied()
+        })
+        .map(|outcome| outcome.is_allowed())
+    }
+}
+
+/// The state of every event of a [`RoomDag`].
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "unstable-exhaustive-types"), non_exhaustive)]
+pub struct DagState<Id> {
+    /// The state before each event.
+    pub state_before: HashMap<Id, StateMap<Id>>,
+
+    /// The state after each event.
+    ///
+    /// For rejected events and events that are not state events, this is the same as the state
+    /// before the event.
+    pub state_after: HashMap<Id, StateMap<Id>>,
+
+    /// The accepted events that are not referenced as a prev event by any other accepted event.
+    pub forward_extremities: HashSet<Id>,
+
+    /// The events that failed the authorization rules against the state before them.
+    pub rejected: HashSet<Id>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use maplit::hashset;
+    use ruma_events::EventType;
+    use ruma_identifiers::{RoomVersionId, UserId};
+    use serde_json::{json, value::to_raw_value as to_raw_json_value};
+
+    use super::RoomDag;
+    use crate::test_utils::{alice, bob, event_id, to_pdu_event, StateEvent, INITIAL_EVENTS};
+
+    fn initial_events() -> Vec<Arc<StateEvent>> {
+        let events = INITIAL_EVENTS();
+        ["CREATE", "IMA", "IPOWER", "IJR", "IMB", "IMC"]
+            .iter()
+            .map(|id| Arc::clone(&events[&event_id(id)]))
+            .collect()
+    }
+
+    fn topic(id: &str, sender: Box<UserId>, prev: &[&str]) -> Arc<StateEvent> {
+        to_pdu_event(
+            id,
+            sender,
+            EventType::RoomTopic,
+            Some(""),
+            to_raw_json_value(&json!({ "topic": id })).unwrap(),
+            &["CREATE", "IMA", "IPOWER"],
+            prev,
+        )
+    }
+
+    #[test]
+    fn linear_dag() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let dag = RoomDag::new(initial_events());
+        let state = dag.compute_state(&RoomVersionId::V6).unwrap();
+
+        assert!(state.rejected.is_empty());
+        assert_eq!(state.forward_extremities, hashset![event_id("IMC")]);
+        assert!(state.state_before[&event_id("CREATE")].is_empty());
+
+        let state_after: HashSet<_> = state.state_after[&event_id("IMC")].values().collect();
+        let expected: Vec<_> = ["CREATE", "IMA", "IPOWER", "IJR", "IMB", "IMC"]
+            .iter()
+            .map(|id| event_id(id))
+            .collect();
+        assert_eq!(state_after, expected.iter().collect());
+    }
+
+    #[test]
+    fn forks_are_resolved() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let mut events = initial_events();
+        events.push(topic("T1", alice(), &["IMC"]));
+        events.push(topic("T2", alice(), &["IMC"]));
+        events.push(to_pdu_event(
+            "MERGE",
+            alice(),
+            EventType::RoomMessage,
+            None,
+            to_raw_json_value(&json!({ "msgtype": "m.text", "body": "Hi" })).unwrap(),
+            &["CREATE", "IMA", "IPOWER"],
+            &["T1", "T2"],
+        ));
+
+        let dag = RoomDag::new(events);
+        let state = dag.compute_state(&RoomVersionId::V6).unwrap();
+
+        assert!(state.rejected.is_empty());
+        assert_eq!(state.forward_extremities, hashset![event_id("MERGE")]);
+
+        let topic_key = (EventType::RoomTopic, "".to_owned());
+        assert_eq!(state.state_after[&event_id("T1")][&topic_key], event_id("T1"));
+        assert_eq!(state.state_before[&event_id("MERGE")][&topic_key], event_id("T2"));
+        assert_eq!(state.state_after[&event_id("MERGE")], state.state_before[&event_id("MERGE")]);
+    }
+
+    #[test]
+    fn auth_events_outside_of_the_dag() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let outside_topic = |id: &str| {
+            to_pdu_event(
+                id,
+                alice(),
+                EventType::RoomTopic,
+                Some(""),
+                to_raw_json_value(&json!({ "topic": id })).unwrap(),
+                &["CREATE", "IMA", "IPOWER", "OUTSIDE"],
+                &["IMC"],
+            )
+        };
+
+        // The auth event OUTSIDE is not part of the DAG, e.g. because it predates its first event
+        let mut events = initial_events();
+        events.push(outside_topic("T1"));
+        events.push(outside_topic("T2"));
+        events.push(topic("MERGE", alice(), &["T1", "T2"]));
+
+        let dag = RoomDag::new(events);
+        let state = dag.compute_state(&RoomVersionId::V6).unwrap();
+
+        assert!(state.rejected.is_empty());
+        assert_eq!(state.forward_extremities, hashset![event_id("MERGE")]);
+        assert_eq!(
+            state.state_after[&event_id("MERGE")][&(EventType::RoomTopic, "".to_owned())],
+            event_id("MERGE")
+        );
+    }
+
+    #[test]
+    fn rejected_events_do_not_change_state() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let mut events = initial_events();
+        events.push(topic("T1", alice(), &["IMC"]));
+        // Bob doesn't have the power level to change the topic
+        events.push(topic("T2", bob(), &["T1"]));
+
+        let dag = RoomDag::new(events);
+        let state = dag.compute_state(&RoomVersionId::V6).unwrap();
+
+        assert_eq!(state.rejected, hashset![event_id("T2")]);
+        assert_eq!(state.forward_extremities, hashset![event_id("T1")]);
+        assert_eq!(
+            state.state_after[&event_id("T2")][&(EventType::RoomTopic, "".to_owned())],
+            event_id("T1")
+        );
+    }
+}
@@ -5,6 +5,7 @@ use std::{
     sync::Arc,
 };
 
+use js_int::UInt;
 use ruma_common::MilliSecondsSinceUnixEpoch;
 use ruma_events::EventType;
 use ruma_identifiers::{EventId, RoomId, UserId};
@@ -26,6 +27,11 @@ pub trait Event {
     /// The time of creation on the originating server.
     fn origin_server_ts(&self) -> MilliSecondsSinceUnixEpoch;
 
+    /// The depth of this event in the room's event graph.
+    ///
+    /// Only used to order conflicting events in state resolution for room version 1.
+    fn depth(&self) -> UInt;
+
     /// The event type.
     fn event_type(&self) -> &EventType;
 
@@ -66,6 +72,10 @@ impl<T: Event> Event for &T {
         (*self).origin_server_ts()
     }
 
+    fn depth(&self) -> UInt {
+        (*self).depth()
+    }
+
     fn event_type(&self) -> &EventType {
         (*self).event_type()
     }
@@ -110,6 +120,10 @@ impl<T: Event> Event for Arc<T> {
         (&**self).origin_server_ts()
     }
 
+    fn depth(&self) -> UInt {
+        (&**self).depth()
+    }
+
     fn event_type(&self) -> &EventType {
         (&**self).event_type()
     }
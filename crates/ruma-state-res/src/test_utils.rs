@@ -8,7 +8,7 @@ use std::{
     },
 };
 
-use js_int::{int, uint};
+use js_int::{int, uint, UInt};
 use ruma_common::MilliSecondsSinceUnixEpoch;
 use ruma_events::{
     pdu::{EventHash, Pdu, RoomV3Pdu},
@@ -25,7 +25,7 @@ use serde_json::{
 };
 use tracing::info;
 
-use crate::{auth_types_for_event, Error, Event, Result, StateMap};
+use crate::{auth_types_for_event, Error, Event, Result, RoomVersion, StateMap};
 
 pub use event::StateEvent;
 
@@ -35,6 +35,15 @@ pub fn do_check(
     events: &[Arc<StateEvent>],
     edges: Vec<Vec<Box<EventId>>>,
     expected_state_ids: Vec<Box<EventId>>,
+) {
+    do_check_for_version(&RoomVersionId::V6, events, edges, expected_state_ids)
+}
+
+pub fn do_check_for_version(
+    room_version: &RoomVersionId,
+    events: &[Arc<StateEvent>],
+    edges: Vec<Vec<Box<EventId>>>,
+    expected_state_ids: Vec<Box<EventId>>,
 ) {
     // To activate logging use `RUST_LOG=debug cargo t`
 
@@ -78,6 +87,8 @@ pub fn do_check(
     let mut event_map: HashMap<Box<EventId>, Arc<StateEvent>> = HashMap::new();
     // event_id -> StateMap<Box<EventId>>
     let mut state_at_event: HashMap<Box<EventId>, StateMap<Box<EventId>>> = HashMap::new();
+    // event_id -> depth, events without `prev_events` are at depth 1
+    let mut depth_at_event: HashMap<Box<EventId>, UInt> = HashMap::new();
 
     // Resolve the current state and add it to the state_at_event map then continue
     // on in "time"
@@ -117,7 +128,7 @@ pub fn do_check(
                 })
                 .collect();
 
-            let resolved = crate::resolve(&RoomVersionId::V6, state_sets, auth_chain_sets, |id| {
+            let resolved = crate::resolve(room_version, state_sets, auth_chain_sets, |id| {
                 event_map.get(id).map(Arc::clone)
             });
             match resolved {
@@ -137,6 +148,7 @@ pub fn do_check(
             fake_event.sender(),
             fake_event.state_key(),
             fake_event.content(),
+            &RoomVersion::new(room_version).unwrap(),
         )
         .unwrap();
 
@@ -151,7 +163,7 @@ pub fn do_check(
         // the `to_pdu_event` was split into `init` and the fn below, could be better
         let e = fake_event;
         let ev_id = e.event_id();
-        let event = to_pdu_event(
+        let mut event = to_pdu_event(
             e.event_id().as_str(),
             e.sender().to_owned(),
             e.event_type().clone(),
@@ -161,6 +173,14 @@ pub fn do_check(
             &prev_events.iter().cloned().collect::<Vec<_>>(),
         );
 
+        let depth = prev_events
+            .iter()
+            .map(|id| depth_at_event[id])
+            .max()
+            .map_or(uint!(1), |depth| depth + uint!(1));
+        Arc::make_mut(&mut event).set_depth(depth);
+        depth_at_event.insert(node.clone(), depth);
+
         // We have to update our store, an actual user of this lib would
         // be giving us state from a DB.
         store.0.insert(ev_id.to_owned(), event.clone());
@@ -543,6 +563,7 @@ pub fn INITIAL_EDGES() -> Vec<Box<EventId>> {
 }
 
 pub mod event {
+    use js_int::UInt;
     use ruma_events::{exports::ruma_common::MilliSecondsSinceUnixEpoch, pdu::Pdu, EventType};
     use ruma_identifiers::{EventId, RoomId, UserId};
     use serde::{Deserialize, Serialize};
@@ -602,6 +623,15 @@ pub mod event {
             }
         }
 
+        fn depth(&self) -> UInt {
+            match &self.rest {
+                Pdu::RoomV1Pdu(ev) => ev.depth,
+                Pdu::RoomV3Pdu(ev) => ev.depth,
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("new PDU version"),
+            }
+        }
+
         fn state_key(&self) -> Option<&str> {
             match &self.rest {
                 Pdu::RoomV1Pdu(ev) => ev.state_key.as_deref(),
@@ -647,6 +677,17 @@ pub mod event {
         pub rest: Pdu,
     }
 
+    impl StateEvent {
+        pub fn set_depth(&mut self, depth: UInt) {
+            match &mut self.rest {
+                Pdu::RoomV1Pdu(ev) => ev.depth = depth,
+                Pdu::RoomV3Pdu(ev) => ev.depth = depth,
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("new PDU version"),
+            }
+        }
+    }
+
     //impl StateEvent {
     //    pub fn state_key(&self) -> &str {
     //        match &self.rest {
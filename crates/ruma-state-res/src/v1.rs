@@ -0,0 +1,446 @@
+use std::{
+    borrow::Borrow,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use itertools::Itertools;
+use ruma_events::EventType;
+use ruma_identifiers::EventId;
+use sha1::{Digest, Sha1};
+use tracing::{debug, info, trace};
+
+use crate::{auth_check, auth_types_for_event, room_version::RoomVersion, Event, Result, StateMap};
+
+/// Resolve the `conflicted` state on top of the `unconflicted` state using the original state
+/// resolution algorithm of room version 1.
+///
+/// Conflicts are resolved in the following order, each step being authorized against the outcome
+/// of the previous ones:
+///
+/// 1. `m.room.power_levels`
+/// 2. `m.room.join_rules`
+/// 3. `m.room.member`
+/// 4. all other state events
+///
+/// See <https://spec.matrix.org/unstable/rooms/v1/#state-resolution>.
+pub(crate) fn resolve<E: Event + Clone>(
+    room_version: &RoomVersion,
+    mut unconflicted: StateMap<E::Id>,
+    conflicted: StateMap<Vec<E::Id>>,
+    fetch_event: impl Fn(&EventId) -> Option<E>,
+) -> Result<StateMap<E::Id>> {
+    info!("starting state resolution v1");
+
+    // Events we cannot find are not honored, any key that is left with a single event is no
+    // longer in conflict.
+    let mut conflicted_events: StateMap<Vec<E>> = HashMap::new();
+    for (key, event_ids) in conflicted {
+        let mut events = event_ids
+            .into_iter()
+            .unique()
+            .filter_map(|id| fetch_event(id.borrow()))
+            .collect::<Vec<_>>();
+
+        match events.len() {
+            0 => {}
+            1 => {
+                let event = events.pop().expect("there is exactly one event");
+                unconflicted.insert(key, event.event_id().clone());
+            }
+            _ => {
+                conflicted_events.insert(key, events);
+            }
+        }
+    }
+
+    debug!("conflicted events: {}", conflicted_events.len());
+    trace!("{:?}", conflicted_events.keys().collect::<Vec<_>>());
+
+    // The auth events of the conflicted events, as far as they are part of the unconflicted state
+    let mut auth_events: StateMap<E> = HashMap::new();
+    for event in conflicted_events.values().flatten() {
+        for key in auth_types_for_event(
+            event.event_type(),
+            event.sender(),
+            event.state_key(),
+            event.content(),
+            room_version,
+        )? {
+            if auth_events.contains_key(&key) {
+                continue;
+            }
+
+            if let Some(auth_event) = unconflicted.get(&key).and_then(|id| fetch_event(id.borrow()))
+            {
+                auth_events.insert(key, auth_event);
+            }
+        }
+    }
+
+    let mut resolved_state: StateMap<E> = HashMap::new();
+
+    // Only the `m.room.power_levels` event with an empty state key takes part in authorization.
+    let is_auth_key = |(ty, state_key): &(EventType, String), auth_ty: &EventType| {
+        ty == auth_ty && (*ty != EventType::RoomPowerLevels || state_key.is_empty())
+    };
+
+    for auth_ty in &[EventType::RoomPowerLevels, EventType::RoomJoinRules, EventType::RoomMember] {
+        let mut resolved_step = StateMap::new();
+        for (key, events) in conflicted_events.iter().filter(|(key, _)| is_auth_key(key, auth_ty)) {
+            let event = resolve_auth_events(room_version, key, events, &auth_events, &fetch_event)?;
+            resolved_step.insert(key.clone(), event);
+        }
+
+        auth_events.extend(resolved_step.clone());
+        resolved_state.extend(resolved_step);
+    }
+
+    for (key, events) in &conflicted_events {
+        if !resolved_state.contains_key(key) {
+            let event = resolve_normal_events(room_version, events, &auth_events, &fetch_event)?;
+            resolved_state.insert(key.clone(), event);
+        }
+    }
+
+    unconflicted.extend(resolved_state.into_iter().map(|(key, ev)| (key, ev.event_id().clone())));
+    Ok(unconflicted)
+}
+
+/// Resolve conflicting events that take part in authorization.
+///
+/// Starting with the event with the lowest depth, each event replaces the previous one as long as
+/// it is allowed by the authorization rules given the previous one. The last allowed event wins.
+fn resolve_auth_events<E: Event + Clone>(
+    room_version: &RoomVersion,
+    key: &(EventType, String),
+    events: &[E],
+    auth_events: &StateMap<E>,
+    fetch_event: impl Fn(&EventId) -> Option<E>,
+) -> Result<E> {
+    let mut auth_keys = HashSet::new();
+    for event in events {
+        auth_keys.extend(auth_types_for_event(
+            event.event_type(),
+            event.sender(),
+            event.state_key(),
+            event.content(),
+            room_version,
+        )?);
+    }
+
+    let mut auth_events = auth_keys
+        .into_iter()
+        .filter_map(|key| auth_events.get(&key).cloned().map(|ev| (key, ev)))
+        .collect::<StateMap<_>>();
+
+    let mut sorted = ordered_events(events).into_iter().rev();
+    let mut prev_event = sorted.next().expect("there are at least two conflicting events");
+    for event in sorted {
+        auth_events.insert(key.clone(), prev_event.clone());
+
+        if !is_allowed(room_version, &event, &auth_events, &fetch_event)? {
+            debug!("{} failed the authentication check, keeping {}", event.event_id(), key.0);
+            break;
+        }
+
+        prev_event = event;
+    }
+
+    Ok(prev_event)
+}
+
+/// Resolve conflicting events that do not take part in authorization.
+///
+/// The event with the greatest depth that is allowed by the authorization rules wins. If none of
+/// them is allowed, the one with the lowest depth is used.
+fn resolve_normal_events<E: Event + Clone>(
+    room_version: &RoomVersion,
+    events: &[E],
+    auth_events: &StateMap<E>,
+    fetch_event: impl Fn(&EventId) -> Option<E>,
+) -> Result<E> {
+    let sorted = ordered_events(events);
+    for event in &sorted {
+        if is_allowed(room_version, event, auth_events, &fetch_event)? {
+            return Ok(event.clone());
+        }
+    }
+
+    Ok(sorted.last().expect("there are at least two conflicting events").clone())
+}
+
+/// Check `event` against the authorization rules with the given `auth_events` as the current state.
+fn is_allowed<E: Event + Clone>(
+    room_version: &RoomVersion,
+    event: &E,
+    auth_events: &StateMap<E>,
+    fetch_event: impl Fn(&EventId) -> Option<E>,
+) -> Result<bool> {
+    let most_recent_prev_event =
+        event.prev_events().filter_map(|id| fetch_event(id.borrow())).next_back();
+
+    let current_third_party =
+        auth_events.values().find(|pdu| *pdu.event_type() == EventType::RoomThirdPartyInvite);
+
+    auth_check(
+        room_version,
+        event,
+        most_recent_prev_event.as_ref(),
+        current_third_party,
+        |ty, key| auth_events.get(&(ty.clone(), key.to_owned())).cloned(),
+    )
+    .map(|outcome| outcome.is_allowed())
+}
+
+/// Sort the events by descending depth, breaking ties with the ascending SHA-1 hash of the event
+/// ID.
+fn ordered_events<E: Event + Clone>(events: &[E]) -> Vec<E> {
+    let mut events = events.to_vec();
+    events.sort_by_cached_key(|ev| {
+        let event_id: &EventId = ev.event_id().borrow();
+        (Reverse(ev.depth()), Sha1::digest(event_id.as_bytes()))
+    });
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use js_int::{uint, UInt};
+    use ruma_events::EventType;
+    use ruma_identifiers::{EventId, RoomVersionId, UserId};
+    use serde_json::{json, value::to_raw_value as to_raw_json_value};
+
+    use crate::{
+        test_utils::{
+            alice, bob, charlie, do_check_for_version, ella, event_id, member_content_join,
+            room_id, to_init_pdu_event, to_pdu_event, StateEvent, TestStore, INITIAL_EVENTS,
+        },
+        Event, StateMap,
+    };
+
+    fn topic_event(id: &str, sender: Box<UserId>, depth: UInt) -> Arc<StateEvent> {
+        let mut event = to_pdu_event(
+            id,
+            sender,
+            EventType::RoomTopic,
+            Some(""),
+            to_raw_json_value(&json!({ "topic": id })).unwrap(),
+            &["CREATE", "IMA", "IPOWER"],
+            &["IMC"],
+        );
+        Arc::make_mut(&mut event).set_depth(depth);
+        event
+    }
+
+    /// Resolve the state of `INITIAL_EVENTS` forked with each of `forks` as version 1 room state.
+    fn resolve_forks(forks: &[Arc<StateEvent>]) -> StateMap<Box<EventId>> {
+        let mut events = INITIAL_EVENTS();
+        events.extend(forks.iter().map(|ev| (ev.event_id().clone(), Arc::clone(ev))));
+        let store = TestStore(events);
+
+        let initial_state = INITIAL_EVENTS()
+            .values()
+            .map(|ev| {
+                (
+                    (ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()),
+                    ev.event_id.clone(),
+                )
+            })
+            .collect::<StateMap<_>>();
+
+        let state_sets = forks
+            .iter()
+            .map(|ev| {
+                let mut state = initial_state.clone();
+                state.insert(
+                    (ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()),
+                    ev.event_id.clone(),
+                );
+                state
+            })
+            .collect::<Vec<_>>();
+
+        crate::resolve(
+            &RoomVersionId::V1,
+            &state_sets,
+            state_sets
+                .iter()
+                .map(|map| {
+                    store.auth_event_ids(room_id(), map.values().cloned().collect()).unwrap()
+                })
+                .collect(),
+            |id| store.0.get(id).map(Arc::clone),
+        )
+        .unwrap()
+    }
+
+    fn resolved_topic(forks: &[Arc<StateEvent>]) -> Box<EventId> {
+        resolve_forks(forks).remove(&(EventType::RoomTopic, "".to_owned())).unwrap()
+    }
+
+    #[test]
+    fn deepest_topic_wins() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let forks = [topic_event("T1", alice(), uint!(11)), topic_event("T2", alice(), uint!(10))];
+        assert_eq!(resolved_topic(&forks), event_id("T1"));
+
+        let forks = [topic_event("T1", alice(), uint!(10)), topic_event("T2", alice(), uint!(11))];
+        assert_eq!(resolved_topic(&forks), event_id("T2"));
+    }
+
+    #[test]
+    fn topic_depth_tie_uses_sha1_of_event_id() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        // sha1("$T1:foo") = d74529..., sha1("$T2:foo") = b69371...
+        let forks = [topic_event("T1", alice(), uint!(10)), topic_event("T2", alice(), uint!(10))];
+        assert_eq!(resolved_topic(&forks), event_id("T2"));
+    }
+
+    #[test]
+    fn unauthorized_deeper_topic_loses() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        // bob is at power level 0 and can't set the topic
+        let forks = [topic_event("T1", alice(), uint!(10)), topic_event("T2", bob(), uint!(11))];
+        assert_eq!(resolved_topic(&forks), event_id("T1"));
+    }
+
+    #[test]
+    fn power_levels_stop_at_first_unauthorized_event() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let power_levels = |id, sender, content, depth| {
+            let mut event = to_pdu_event(
+                id,
+                sender,
+                EventType::RoomPowerLevels,
+                Some(""),
+                to_raw_json_value(&content).unwrap(),
+                &["CREATE", "IMA", "IPOWER"],
+                &["IMC"],
+            );
+            Arc::make_mut(&mut event).set_depth(depth);
+            event
+        };
+
+        // alice gives bob 50, bob then tries to give charlie more power than he has himself
+        let pa =
+            power_levels("PA", alice(), json!({ "users": { alice(): 100, bob(): 50 } }), uint!(10));
+        let pb = power_levels(
+            "PB",
+            bob(),
+            json!({ "users": { alice(): 100, bob(): 50, charlie(): 100 } }),
+            uint!(11),
+        );
+
+        let resolved = resolve_forks(&[pa, pb]);
+        assert_eq!(resolved[&(EventType::RoomPowerLevels, "".to_owned())], event_id("PA"));
+    }
+
+    #[test]
+    fn join_rule_evasion() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let events = &[
+            to_init_pdu_event(
+                "JR",
+                alice(),
+                EventType::RoomJoinRules,
+                Some(""),
+                to_raw_json_value(&json!({ "join_rule": "private" })).unwrap(),
+            ),
+            to_init_pdu_event(
+                "ME",
+                ella(),
+                EventType::RoomMember,
+                Some(ella().to_string().as_str()),
+                member_content_join(),
+            ),
+        ];
+
+        let edges = vec![vec!["END", "JR", "START"], vec!["END", "ME", "START"]]
+            .into_iter()
+            .map(|list| list.into_iter().map(event_id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // Only one of the forks has a membership event for ella, so it is never auth checked.
+        let expected_state_ids = vec!["JR", "ME"].into_iter().map(event_id).collect::<Vec<_>>();
+
+        do_check_for_version(&RoomVersionId::V1, events, edges, expected_state_ids)
+    }
+
+    #[test]
+    fn offtopic_power_level() {
+        let _ =
+            tracing::subscriber::set_default(tracing_subscriber::fmt().with_test_writer().finish());
+
+        let events = &[
+            to_init_pdu_event(
+                "PA",
+                alice(),
+                EventType::RoomPowerLevels,
+                Some(""),
+                to_raw_json_value(&json!({ "users": { alice(): 100, bob(): 50 } })).unwrap(),
+            ),
+            to_init_pdu_event(
+                "PB",
+                bob(),
+                EventType::RoomPowerLevels,
+                Some(""),
+                to_raw_json_value(&json!({ "users": { alice(): 100, bob(): 50, charlie(): 50 } }))
+                    .unwrap(),
+            ),
+            to_init_pdu_event(
+                "PC",
+                charlie(),
+                EventType::RoomPowerLevels,
+                Some(""),
+                to_raw_json_value(&json!({ "users": { alice(): 100, bob(): 50, charlie(): 0 } }))
+                    .unwrap(),
+            ),
+        ];
+
+        let edges = vec![vec!["END", "PC", "PB", "PA", "START"], vec!["END", "PA"]]
+            .into_iter()
+            .map(|list| list.into_iter().map(event_id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // Unlike state resolution v2, PB is not part of the conflicted set so charlie never had
+        // the power to send PC.
+        let expected_state_ids = vec!["PA"].into_iter().map(event_id).collect::<Vec<_>>();
+
+        do_check_for_version(&RoomVersionId::V1, events, edges, expected_state_ids)
+    }
+
+    #[test]
+    fn unconflicted_state_is_kept() {
+        let state = INITIAL_EVENTS()
+            .values()
+            .map(|ev| {
+                (
+                    (ev.event_type().to_owned(), ev.state_key().unwrap().to_owned()),
+                    ev.event_id.clone(),
+                )
+            })
+            .collect::<StateMap<_>>();
+
+        let events = INITIAL_EVENTS();
+        let resolved =
+            crate::resolve(&RoomVersionId::V1, &[state.clone(), state.clone()], Vec::new(), |id| {
+                events.get(id).map(Arc::clone)
+            })
+            .unwrap();
+
+        assert_eq!(resolved, state);
+    }
+}